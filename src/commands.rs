@@ -0,0 +1,260 @@
+//! Команды on-chain слоя, которых нет в off-chain движке.
+//!
+//! Движковый `Command` описывает только игровую механику. Всё, что касается
+//! именно on-chain приложения (авто-раздача, настройки столов и т.п.),
+//! живёт здесь и приходит через `Operation::Onchain`.
+
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use poker_engine::api::commands::CreateTableCommand;
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::tournament::TournamentConfig;
+use poker_engine::domain::{PlayerId, TableId, TournamentId};
+use poker_engine::engine::actions::PlayerActionKind;
+
+use crate::deals::DealKind;
+use crate::players::PlayerPreferences;
+use crate::roles::Role;
+use crate::rules::TableRules;
+use crate::sng::{SngTemplateId, SpinMultiplier};
+
+/// Все on-chain команды приложения.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum OnchainCommand {
+    SetAutoDeal(SetAutoDealCommand),
+    Act(ActCommand),
+    OpenRemoteTable(OpenRemoteTableCommand),
+    SetTableChains(SetTableChainsCommand),
+    CreditBankroll(CreditBankrollCommand),
+    RequestSeat(RequestSeatCommand),
+    CashOut(CashOutCommand),
+    UpdateProfile(UpdateProfileCommand),
+    SetTournamentPayouts(SetTournamentPayoutsCommand),
+    StartSeason(StartSeasonCommand),
+    GrantRole(GrantRoleCommand),
+    RevokeRole(RevokeRoleCommand),
+    ProposeOwner(ProposeOwnerCommand),
+    AcceptOwnership(AcceptOwnershipCommand),
+    RegisterPlayer(RegisterPlayerCommand),
+    SetDisplayName(SetDisplayNameCommand),
+    RotateAccount(RotateAccountCommand),
+    CreateTableWithRules(CreateTableWithRulesCommand),
+    CreateSngTemplate(CreateSngTemplateCommand),
+    RemoveSngTemplate(RemoveSngTemplateCommand),
+    ScheduleTournament(ScheduleTournamentCommand),
+    SetSatellite(SetSatelliteCommand),
+    ProposeDeal(ProposeDealCommand),
+    AcceptDeal(DealVoteCommand),
+    RejectDeal(DealVoteCommand),
+    SetLevelDuration(SetLevelDurationCommand),
+}
+
+/// Включить / выключить авто-раздачу на столе.
+///
+/// `delay_secs` — пауза между концом раздачи и стартом следующей
+/// (считается по времени цепи).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetAutoDealCommand {
+    pub table_id: TableId,
+    pub enabled: bool,
+    pub delay_secs: u64,
+}
+
+/// Действие игрока от имени подписанта операции.
+///
+/// player_id и seat не передаются: контракт берёт игрока из
+/// `account_players[signer]` и проверяет, что сейчас его ход.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActCommand {
+    pub table_id: TableId,
+    pub kind: PlayerActionKind,
+}
+
+/// Открыть cash-стол на отдельной микроцепи (эта цепь — координатор).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpenRemoteTableCommand {
+    pub chain_id: ChainId,
+    pub table: CreateTableCommand,
+    #[serde(default)]
+    pub rules: TableRules,
+}
+
+/// Пул цепей, по которым раскладываются столы турниров при старте.
+/// Пустой список — все столы остаются на цепи координатора.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetTableChainsCommand {
+    pub chains: Vec<ChainId>,
+}
+
+/// Пополнить банкролл игрока на его цепи (admin, отправляется сообщением).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreditBankrollCommand {
+    pub player_chain: ChainId,
+    pub player_id: PlayerId,
+    pub amount: Chips,
+}
+
+/// Выполняется на цепи игрока: списать бай-ин из банкролла
+/// и попросить место за cash-столом (через координатор).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestSeatCommand {
+    /// Цепь стола: от неё придут SeatStack и CashOut.
+    pub table_chain: ChainId,
+    pub table_id: TableId,
+    pub player_id: PlayerId,
+    /// Желаемое место; если занято — сядем на любое свободное.
+    pub seat_index: u8,
+    pub buy_in: Chips,
+    pub display_name: String,
+}
+
+/// Выполняется на цепи игрока: встать из-за стола и вернуть фишки в банкролл.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CashOutCommand {
+    pub table_chain: ChainId,
+    pub table_id: TableId,
+    pub player_id: PlayerId,
+}
+
+/// Обновить профиль своего игрока. `None` — поле не меняется.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateProfileCommand {
+    pub player_id: PlayerId,
+    pub avatar_url: Option<String>,
+    pub country: Option<String>,
+    pub preferences: Option<PlayerPreferences>,
+}
+
+/// Призовые турнира по местам (турнирный директор): `payouts[0]` — за первое место.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetTournamentPayoutsCommand {
+    pub tournament_id: TournamentId,
+    pub payouts: Vec<Chips>,
+}
+
+/// Длительность уровня блайндов турнира (турнирный директор), для таймера
+/// в таблице турнира. 0 — снять длительность.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetLevelDurationCommand {
+    pub tournament_id: TournamentId,
+    pub duration_secs: u64,
+}
+
+/// Начать новый сезон рейтинга (admin). Сезоны дальше сменяются
+/// автоматически каждые `duration_secs` по времени цепи.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartSeasonCommand {
+    pub duration_secs: u64,
+}
+
+/// Выдать роль аккаунту. Admin выдаёт только owner,
+/// остальные роли — owner или admin.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrantRoleCommand {
+    pub account: AccountOwner,
+    pub role: Role,
+    /// Ограничить роль одним столом (None — все столы).
+    pub table_id: Option<TableId>,
+}
+
+/// Отозвать роль (те же права, что и на выдачу).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RevokeRoleCommand {
+    pub account: AccountOwner,
+    pub role: Role,
+    pub table_id: Option<TableId>,
+}
+
+/// Шаг 1 передачи владения: текущий owner предлагает нового.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProposeOwnerCommand {
+    pub new_owner: AccountOwner,
+}
+
+/// Шаг 2: предложенный аккаунт подтверждает владение своей подписью.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AcceptOwnershipCommand;
+
+/// Явно зарегистрировать игрока за подписантом и занять имя.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegisterPlayerCommand {
+    pub player_id: PlayerId,
+    pub display_name: String,
+}
+
+/// Сменить отображаемое имя своего игрока (см. `crate::identity`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetDisplayNameCommand {
+    pub player_id: PlayerId,
+    pub display_name: String,
+}
+
+/// Перенести игрока на новый аккаунт (подписывает текущий аккаунт).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RotateAccountCommand {
+    pub player_id: PlayerId,
+    pub new_account: AccountOwner,
+}
+
+/// Создать стол с правилами, отличными от NL Hold'em (см. `crate::rules`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateTableWithRulesCommand {
+    pub table: CreateTableCommand,
+    pub rules: TableRules,
+}
+
+/// Шаблон SNG / спина (турнирный директор). Сразу открывает первый турнир.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateSngTemplateCommand {
+    pub template_id: SngTemplateId,
+    pub config: TournamentConfig,
+    /// 2, 6 или 9.
+    pub players: u32,
+    pub buy_in: Chips,
+    /// Пусто — обычный SNG; иначе спин с этой таблицей множителей.
+    #[serde(default)]
+    pub spin: Vec<SpinMultiplier>,
+}
+
+/// Убрать шаблон. Открытый турнир без регистраций удаляется вместе с ним.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemoveSngTemplateCommand {
+    pub template_id: SngTemplateId,
+}
+
+/// Расписание турнира (турнирный директор): старт и закрытие регистрации
+/// по времени цепи. Без `reg_close_at` регистрация закрывается к старту.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduleTournamentCommand {
+    pub tournament_id: TournamentId,
+    pub start_at: Timestamp,
+    pub reg_close_at: Option<Timestamp>,
+    /// Меньше к старту — турнир отменяется с возвратом бай-инов.
+    pub min_players: u32,
+}
+
+/// Сделать турнир сателлитом в `target_tournament_id` (турнирный директор,
+/// до старта). У цели должен быть бай-ин — это цена билета.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetSatelliteCommand {
+    pub tournament_id: TournamentId,
+    pub target_tournament_id: TournamentId,
+}
+
+/// Предложить сделку оставшимся игрокам турнира (любой живой игрок).
+/// Доли считаются на цепи по текущим стекам; предложивший сразу согласен.
+/// Новое предложение заменяет прежнее.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProposeDealCommand {
+    pub tournament_id: TournamentId,
+    pub kind: DealKind,
+    /// Часть первого приза, которая остаётся на игру (0 — делим всё).
+    pub play_for: Chips,
+}
+
+/// Согласие или отказ подписанта по текущему предложению сделки.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DealVoteCommand {
+    pub tournament_id: TournamentId,
+}
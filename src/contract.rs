@@ -6,10 +6,11 @@ use linera_sdk::{
     Contract,
     ContractRuntime,
 };
-use linera_sdk::linera_base_types::AccountOwner;
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, StreamName};
 
 use poker_engine::api::dto::CommandResponse;
 
+use poker_onchain::events::{PokerEvent, POKER_EVENTS_STREAM};
 use poker_onchain::{ApplicationParameters, Message, Operation, PokerAbi};
 use poker_onchain::orchestrator::PokerOrchestrator;
 use poker_onchain::state::PokerState;
@@ -22,6 +23,27 @@ pub struct PokerContract {
 
 linera_sdk::contract!(PokerContract);
 
+impl PokerContract {
+    /// Отправить всё, что оркестратор накопил за операцию/сообщение:
+    /// события — в Linera-стрим, сообщения — на другие цепи.
+    fn flush(&mut self, events: Vec<PokerEvent>, outgoing: Vec<(ChainId, Message)>) {
+        for event in events {
+            self.runtime
+                .emit(StreamName(POKER_EVENTS_STREAM.to_vec()), &event);
+        }
+
+        for (chain_id, message) in outgoing {
+            let authenticated = message.is_authenticated();
+            let prepared = self.runtime.prepare_message(message).with_tracking();
+            if authenticated {
+                prepared.with_authentication().send_to(chain_id);
+            } else {
+                prepared.send_to(chain_id);
+            }
+        }
+    }
+}
+
 impl WithContractAbi for PokerContract {
     type Abi = PokerAbi;
 }
@@ -30,7 +52,7 @@ impl Contract for PokerContract {
     type Message = Message;
     type Parameters = ApplicationParameters;
     type InstantiationArgument = ();
-    type EventValue = ();
+    type EventValue = PokerEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = PokerState::load(runtime.root_view_storage_context())
@@ -56,15 +78,86 @@ impl Contract for PokerContract {
 
     async fn execute_operation(&mut self, operation: Operation) -> CommandResponse {
         let signer: Option<AccountOwner> = self.runtime.authenticated_signer();
-        let mut orchestrator = PokerOrchestrator::new(&mut self.state, signer);
-
-        match operation {
+        let now = self.runtime.system_time();
+        let chain_id = self.runtime.chain_id();
+        let coordinator = self.runtime.application_creator_chain_id();
+        let block_height = self.runtime.block_height();
+        let mut orchestrator = PokerOrchestrator::new(
+            &mut self.state,
+            signer,
+            now,
+            chain_id,
+            coordinator,
+            block_height,
+        );
+
+        let result = match operation {
             Operation::Command(cmd) => orchestrator.execute_command(cmd).await,
+            Operation::Onchain(cmd) => {
+                orchestrator.execute_onchain_command(cmd).await
+            }
+        };
+
+        match result {
+            Ok(response) => {
+                let events = std::mem::take(&mut orchestrator.events);
+                let outgoing = std::mem::take(&mut orchestrator.outgoing);
+                self.flush(events, outgoing);
+                response
+            }
+            Err(err) => {
+                // Неудачная операция не оставляет следов: ни записей,
+                // ни событий, ни сообщений.
+                let response = orchestrator.error_response(err);
+                self.state.rollback();
+                response
+            }
         }
     }
 
-    async fn execute_message(&mut self, _message: Self::Message) {
-        // Пока не используем cross-chain сообщения.
+    async fn execute_message(&mut self, message: Self::Message) {
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .expect("Incoming message must have an origin chain");
+        // Вернувшиеся сообщения с фишками зачисляются обратно в банкролл.
+        let is_bouncing = self.runtime.message_is_bouncing().unwrap_or(false);
+        let signer: Option<AccountOwner> = self.runtime.authenticated_signer();
+        let now = self.runtime.system_time();
+        let chain_id = self.runtime.chain_id();
+        let coordinator = self.runtime.application_creator_chain_id();
+        let block_height = self.runtime.block_height();
+        let mut orchestrator = PokerOrchestrator::new(
+            &mut self.state,
+            signer,
+            now,
+            chain_id,
+            coordinator,
+            block_height,
+        );
+
+        match orchestrator
+            .execute_message(origin, is_bouncing, message.clone())
+            .await
+        {
+            Ok(()) => {
+                let events = std::mem::take(&mut orchestrator.events);
+                let outgoing = std::mem::take(&mut orchestrator.outgoing);
+                self.flush(events, outgoing);
+            }
+            Err(e) => {
+                // Не роняем блок из-за одного сообщения: откатываем его
+                // записи и возвращаем отправителю фишки из сообщения.
+                self.state.rollback();
+                let replies = PokerOrchestrator::rejection_replies(
+                    origin,
+                    is_bouncing,
+                    message,
+                    e.to_string(),
+                );
+                self.flush(Vec::new(), replies);
+            }
+        }
     }
 
     async fn store(mut self) {
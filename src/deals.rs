@@ -0,0 +1,330 @@
+//! Сделки за финальным столом: ICM и чип-чоп.
+//!
+//! Калькулятор делит оставшиеся призовые (места `1..=n` из
+//! `tournament_payouts`, где `n` — живые игроки) по текущим стекам
+//! `registrations`. Часть первого приза можно оставить «на игру»
+//! (`play_for`): она достаётся победителю доигрывания.
+//!
+//! Предложение (`PokerState::deal_proposals`) принимают подписанты всех
+//! живых игроков; после последнего согласия суммы фиксируются в
+//! `PokerState::locked_deals` и выплачиваются вместо призов по местам.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::tournament::Tournament;
+use poker_engine::domain::PlayerId;
+
+/// Больше игроков ICM не считаем: перебор подмножеств растёт как 2^n.
+pub const MAX_DEAL_PLAYERS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DealKind {
+    /// Модель Malmuth–Harville по стекам и структуре призовых.
+    Icm,
+    /// Пропорционально стекам.
+    ChipChop,
+}
+
+/// Доля игрока в сделке.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DealShare {
+    pub player_id: PlayerId,
+    /// Стек на момент предложения: если он изменился, сделка устарела.
+    pub stack: Chips,
+    pub amount: Chips,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DealProposal {
+    pub proposer: PlayerId,
+    pub kind: DealKind,
+    pub play_for: Chips,
+    pub shares: Vec<DealShare>,
+    pub accepted: Vec<PlayerId>,
+    pub proposed_at: Timestamp,
+}
+
+impl DealProposal {
+    pub fn is_unanimous(&self) -> bool {
+        self.shares
+            .iter()
+            .all(|s| self.accepted.contains(&s.player_id))
+    }
+}
+
+/// Принятая сделка.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LockedDeal {
+    pub kind: DealKind,
+    /// Сколько достанется победителю сверх его доли.
+    pub play_for: Chips,
+    pub shares: Vec<DealShare>,
+    pub agreed_at: Timestamp,
+}
+
+impl LockedDeal {
+    /// Приз по сделке за `place`; None — игрок в сделке не участвует.
+    pub fn prize(&self, player_id: PlayerId, place: u32) -> Option<Chips> {
+        let share = self.shares.iter().find(|s| s.player_id == player_id)?;
+        let bonus = if place == 1 { self.play_for.0 } else { 0 };
+        Some(Chips(share.amount.0 + bonus))
+    }
+}
+
+/// Живые игроки турнира и их стеки, по player_id.
+pub fn remaining_stacks(tournament: &Tournament) -> Vec<(PlayerId, Chips)> {
+    let mut stacks: Vec<(PlayerId, Chips)> = tournament
+        .registrations
+        .iter()
+        .filter(|(_, r)| !r.is_busted)
+        .map(|(pid, r)| (*pid, r.total_chips))
+        .collect();
+    stacks.sort_unstable_by_key(|(pid, _)| *pid);
+    stacks
+}
+
+/// Посчитать доли для `stacks` (player_id, стек) при оставшихся
+/// призовых `prizes` (по местам, от первого). Err — причина отказа.
+pub fn compute_deal(
+    kind: DealKind,
+    stacks: &[(PlayerId, Chips)],
+    prizes: &[Chips],
+    play_for: Chips,
+) -> Result<Vec<DealShare>, String> {
+    if stacks.len() < 2 {
+        return Err("a deal needs at least two remaining players".to_string());
+    }
+    if stacks.len() > MAX_DEAL_PLAYERS {
+        return Err(format!(
+            "deals are limited to {MAX_DEAL_PLAYERS} remaining players"
+        ));
+    }
+    if stacks.iter().any(|(_, s)| s.is_zero()) {
+        return Err("every remaining player must have chips".to_string());
+    }
+
+    let mut prizes: Vec<u64> = prizes.iter().map(|c| c.0).collect();
+    prizes.resize(stacks.len(), 0);
+    let first = prizes.first().copied().unwrap_or(0);
+    if play_for.0 > first {
+        return Err(format!(
+            "play_for {} exceeds the first prize {first}",
+            play_for.0
+        ));
+    }
+    prizes[0] -= play_for.0;
+
+    let pool: u64 = prizes.iter().sum();
+    if pool == 0 {
+        return Err("there is no prize money left to split".to_string());
+    }
+
+    let chips: Vec<u64> = stacks.iter().map(|(_, s)| s.0).collect();
+    let weights = match kind {
+        DealKind::Icm => icm_equities(&chips, &prizes),
+        DealKind::ChipChop => {
+            let total: u64 = chips.iter().sum();
+            chips
+                .iter()
+                .map(|s| *s as f64 / total as f64 * pool as f64)
+                .collect()
+        }
+    };
+
+    let amounts = round_to_pool(&weights, &chips, pool);
+    Ok(stacks
+        .iter()
+        .zip(amounts)
+        .map(|((player_id, stack), amount)| DealShare {
+            player_id: *player_id,
+            stack: *stack,
+            amount: Chips(amount),
+        })
+        .collect())
+}
+
+/// ICM-эквити каждого стека (Malmuth–Harville).
+///
+/// `reach[mask]` — вероятность того, что первые |mask| мест заняли
+/// ровно игроки из `mask`; следующее место игрок `i` берёт с
+/// вероятностью `stack_i / (оставшиеся фишки)`.
+pub fn icm_equities(stacks: &[u64], prizes: &[u64]) -> Vec<f64> {
+    let n = stacks.len();
+    let total: u64 = stacks.iter().sum();
+    let mut equity = vec![0.0f64; n];
+    let mut reach = vec![0.0f64; 1 << n];
+    reach[0] = 1.0;
+
+    for mask in 0..(1usize << n) {
+        let p = reach[mask];
+        let place = mask.count_ones() as usize;
+        if p == 0.0 || place >= n {
+            continue;
+        }
+        let placed: u64 = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| stacks[i])
+            .sum();
+        let left = (total - placed) as f64;
+        let prize = prizes.get(place).copied().unwrap_or(0) as f64;
+
+        for (i, stack) in stacks.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                continue;
+            }
+            let q = p * *stack as f64 / left;
+            equity[i] += q * prize;
+            reach[mask | (1 << i)] += q;
+        }
+    }
+
+    equity
+}
+
+/// Округлить доли вниз до фишек; недостающее до `pool` раздать по
+/// одной фишке по убыванию стека (при равенстве — по порядку).
+fn round_to_pool(weights: &[f64], stacks: &[u64], pool: u64) -> Vec<u64> {
+    let mut amounts: Vec<u64> = weights
+        .iter()
+        .map(|w| (w.max(0.0).floor() as u64).min(pool))
+        .collect();
+
+    // Плавающая точка может дать сумму чуть больше фонда — срезаем с младших.
+    let mut order: Vec<usize> = (0..stacks.len()).collect();
+    order.sort_by(|a, b| stacks[*b].cmp(&stacks[*a]).then(a.cmp(b)));
+
+    let mut sum: u64 = amounts.iter().sum();
+    for i in order.iter().rev().cycle() {
+        if sum <= pool {
+            break;
+        }
+        if amounts[*i] > 0 {
+            amounts[*i] -= 1;
+            sum -= 1;
+        }
+    }
+    for i in order.iter().cycle() {
+        if sum >= pool {
+            break;
+        }
+        amounts[*i] += 1;
+        sum += 1;
+    }
+
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks(chips: &[u64]) -> Vec<(PlayerId, Chips)> {
+        chips
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i as PlayerId + 1, Chips(*c)))
+            .collect()
+    }
+
+    fn prizes(amounts: &[u64]) -> Vec<Chips> {
+        amounts.iter().map(|a| Chips(*a)).collect()
+    }
+
+    fn amounts(shares: &[DealShare]) -> Vec<u64> {
+        shares.iter().map(|s| s.amount.0).collect()
+    }
+
+    #[test]
+    fn icm_matches_a_known_three_player_example() {
+        // 5000/3000/2000 фишек, призы 500/300/200.
+        let equity = icm_equities(&[5000, 3000, 2000], &[500, 300, 200]);
+        let expected = [383.928_571_428_571_4, 327.5, 288.571_428_571_428_6];
+        for (got, want) in equity.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-9, "{got} != {want}");
+        }
+
+        let shares = compute_deal(
+            DealKind::Icm,
+            &stacks(&[5000, 3000, 2000]),
+            &prizes(&[500, 300, 200]),
+            Chips::ZERO,
+        )
+        .expect("valid deal");
+        // Две недостающие фишки — двум самым большим стекам.
+        assert_eq!(amounts(&shares), vec![384, 328, 288]);
+    }
+
+    #[test]
+    fn shares_always_sum_to_the_pool() {
+        let cases: [(&[u64], &[u64], u64); 4] = [
+            (&[1, 1, 1][..], &[100][..], 0),
+            (&[7, 13, 29, 51][..], &[1000, 600, 400, 1][..], 0),
+            (&[12_345, 678, 9_012][..], &[997, 503, 251][..], 101),
+            (&[1, 1_000_000][..], &[3, 2][..], 1),
+        ];
+
+        for (chips, paid, play_for) in cases {
+            let pool = paid.iter().sum::<u64>() - play_for;
+            for kind in [DealKind::Icm, DealKind::ChipChop] {
+                let shares = compute_deal(kind, &stacks(chips), &prizes(paid), Chips(play_for))
+                    .expect("valid deal");
+                let got = amounts(&shares);
+                assert_eq!(got.iter().sum::<u64>(), pool, "{kind:?} {chips:?}");
+                assert!(got.iter().all(|a| *a <= pool));
+            }
+        }
+    }
+
+    #[test]
+    fn equal_stacks_split_the_odd_chip_in_order() {
+        let shares = compute_deal(
+            DealKind::ChipChop,
+            &stacks(&[10, 10, 10]),
+            &prizes(&[100]),
+            Chips::ZERO,
+        )
+        .expect("valid deal");
+        assert_eq!(amounts(&shares), vec![34, 33, 33]);
+    }
+
+    #[test]
+    fn rounding_trims_overshoot_from_the_smallest_stacks() {
+        // Доли с перебором (как от погрешности плавающей точки).
+        let rounded = round_to_pool(&[500.0, 500.0, 1.0], &[300, 200, 100], 1000);
+        assert_eq!(rounded, vec![500, 500, 0]);
+
+        let rounded = round_to_pool(&[2_000.0, 0.0], &[1, 1], 1000);
+        assert_eq!(rounded.iter().sum::<u64>(), 1000);
+        assert!(rounded.iter().all(|a| *a <= 1000));
+    }
+
+    #[test]
+    fn rejects_invalid_deals() {
+        let two = stacks(&[100, 200]);
+        assert!(compute_deal(DealKind::Icm, &stacks(&[100]), &prizes(&[10]), Chips::ZERO).is_err());
+        assert!(compute_deal(DealKind::Icm, &stacks(&[100, 0]), &prizes(&[10]), Chips::ZERO).is_err());
+        assert!(compute_deal(DealKind::Icm, &two, &prizes(&[10, 5]), Chips(11)).is_err());
+        assert!(compute_deal(DealKind::Icm, &two, &prizes(&[]), Chips::ZERO).is_err());
+    }
+
+    #[test]
+    fn play_for_goes_to_the_winner_only() {
+        let deal = LockedDeal {
+            kind: DealKind::ChipChop,
+            play_for: Chips(50),
+            shares: compute_deal(
+                DealKind::ChipChop,
+                &stacks(&[100, 100]),
+                &prizes(&[150, 50]),
+                Chips(50),
+            )
+            .expect("valid deal"),
+            agreed_at: Timestamp::from(0),
+        };
+        assert_eq!(deal.prize(1, 1), Some(Chips(125)));
+        assert_eq!(deal.prize(2, 2), Some(Chips(75)));
+        assert_eq!(deal.prize(3, 1), None);
+    }
+}
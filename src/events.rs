@@ -0,0 +1,91 @@
+//! События приложения для живых обновлений UI.
+//!
+//! Оркестратор копит события за операцию, контракт публикует их в
+//! Linera-стрим `POKER_EVENTS_STREAM` и параллельно пишет в
+//! `PokerState::event_log`, чтобы сервис мог отдавать их по курсору.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::card::Card;
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::hand::Street;
+use poker_engine::domain::{HandId, PlayerId, SeatIndex, TableId, TournamentId};
+use poker_engine::engine::actions::PlayerActionKind;
+
+/// Имя Linera-стрима, в который контракт эмитит `PokerEvent`.
+pub const POKER_EVENTS_STREAM: &[u8] = b"poker_events";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PokerEvent {
+    HandStarted {
+        table_id: TableId,
+        hand_id: HandId,
+        dealer_button: Option<SeatIndex>,
+    },
+    ActionTaken {
+        table_id: TableId,
+        hand_id: HandId,
+        seat: SeatIndex,
+        player_id: PlayerId,
+        kind: PlayerActionKind,
+        /// true — авто-действие по таймауту.
+        auto: bool,
+    },
+    StreetDealt {
+        table_id: TableId,
+        hand_id: HandId,
+        street: Street,
+        board: Vec<Card>,
+    },
+    HandFinished {
+        table_id: TableId,
+        hand_id: HandId,
+        winners: Vec<(PlayerId, Chips)>,
+    },
+    PlayerSeated {
+        table_id: TableId,
+        seat: SeatIndex,
+        player_id: PlayerId,
+    },
+    LevelChanged {
+        tournament_id: TournamentId,
+        level: u32,
+    },
+    TournamentStarted {
+        tournament_id: TournamentId,
+        prize_pool: Chips,
+        /// Множитель спина (None — не спин).
+        spin_multiplier: Option<u32>,
+    },
+}
+
+impl PokerEvent {
+    /// Стол, к которому относится событие (если есть).
+    pub fn table_id(&self) -> Option<TableId> {
+        match self {
+            PokerEvent::HandStarted { table_id, .. }
+            | PokerEvent::ActionTaken { table_id, .. }
+            | PokerEvent::StreetDealt { table_id, .. }
+            | PokerEvent::HandFinished { table_id, .. }
+            | PokerEvent::PlayerSeated { table_id, .. } => Some(*table_id),
+            PokerEvent::LevelChanged { .. } | PokerEvent::TournamentStarted { .. } => None,
+        }
+    }
+
+    /// Турнир, к которому относится событие (если есть).
+    pub fn tournament_id(&self) -> Option<TournamentId> {
+        match self {
+            PokerEvent::LevelChanged { tournament_id, .. }
+            | PokerEvent::TournamentStarted { tournament_id, .. } => Some(*tournament_id),
+            _ => None,
+        }
+    }
+}
+
+/// Запись в `event_log`: событие + время блока.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub at: Timestamp,
+    pub event: PokerEvent,
+}
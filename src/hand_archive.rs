@@ -0,0 +1,317 @@
+//! Архив сыгранных раздач.
+//!
+//! Пока раздача идёт, on-chain слой ведёт `ArchivedHand` в `PokerState::hand_logs`
+//! (стартовые стеки, все действия по улицам). Когда раздача завершается,
+//! запись дополняется бордом, вскрытыми картами, победителями и банками
+//! и переезжает в `PokerState::hand_archive`.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use poker_engine::domain::card::Card;
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::hand::Street;
+use poker_engine::domain::player::PlayerAtTable;
+use poker_engine::domain::table::Table;
+use poker_engine::domain::{HandId, PlayerId, SeatIndex, TableId, TournamentId};
+use poker_engine::engine::actions::PlayerActionKind;
+use poker_engine::engine::hand_history::HandHistory;
+use poker_engine::engine::pot::Pot;
+use poker_engine::engine::side_pots::SidePot;
+
+use crate::rules::{BettingStructure, GameVariant, TableRules};
+use crate::HandEngineSnapshot;
+
+/// Игрок, участвовавший в раздаче.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedSeat {
+    pub seat: SeatIndex,
+    pub player_id: PlayerId,
+    pub display_name: String,
+    /// Стек до блайндов/анте.
+    pub starting_stack: Chips,
+    /// Стек после раздачи (заполняется при завершении).
+    pub final_stack: Chips,
+    /// Карты, показанные на шоудауне (None — сбросил или не вскрывался).
+    pub showdown_cards: Option<Vec<Card>>,
+}
+
+/// Одно действие игрока.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedAction {
+    pub street: Street,
+    pub seat: SeatIndex,
+    pub player_id: PlayerId,
+    pub kind: PlayerActionKind,
+    /// true — действие сделано автоматически (таймаут).
+    pub auto: bool,
+}
+
+/// Выигрыш игрока в раздаче.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedWinner {
+    pub seat: SeatIndex,
+    pub player_id: PlayerId,
+    pub amount: Chips,
+}
+
+/// Полная запись раздачи.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedHand {
+    pub hand_id: HandId,
+    pub table_id: TableId,
+    pub table_name: String,
+    pub tournament_id: Option<TournamentId>,
+    #[serde(default)]
+    pub variant: GameVariant,
+    #[serde(default)]
+    pub betting: BettingStructure,
+    pub max_seats: u8,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,
+    pub dealer_button: Option<SeatIndex>,
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+
+    pub seats: Vec<ArchivedSeat>,
+    pub actions: Vec<ArchivedAction>,
+    pub board: Vec<Card>,
+
+    pub winners: Vec<ArchivedWinner>,
+    pub total_pot: Chips,
+    pub pot: Option<Pot>,
+    pub side_pots: Vec<SidePot>,
+
+    /// Сырой HandHistory движка — на случай, если понадобятся детали.
+    pub history: Option<HandHistory>,
+}
+
+impl ArchivedHand {
+    /// Начать запись раздачи.
+    ///
+    /// `table` — стол ДО `engine::start_hand` (стеки ещё без блайндов),
+    /// `names` — отображаемые имена игроков по местам.
+    pub fn begin(
+        hand_id: HandId,
+        table: &Table,
+        tournament_id: Option<TournamentId>,
+        names: Vec<(SeatIndex, String)>,
+        now: Timestamp,
+    ) -> Self {
+        let seats = table
+            .seats
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, s)| s.as_ref().map(|p| (idx as SeatIndex, p)))
+            .map(|(seat, p)| ArchivedSeat {
+                seat,
+                player_id: p.player_id,
+                display_name: names
+                    .iter()
+                    .find(|(s, _)| *s == seat)
+                    .map(|(_, n)| n.clone())
+                    .unwrap_or_else(|| format!("Player #{}", p.player_id)),
+                starting_stack: p.stack,
+                final_stack: p.stack,
+                showdown_cards: None,
+            })
+            .collect();
+
+        Self {
+            hand_id,
+            table_id: table.id,
+            table_name: table.name.clone(),
+            tournament_id,
+            variant: GameVariant::default(),
+            betting: BettingStructure::default(),
+            max_seats: table.config.max_seats,
+            small_blind: table.config.stakes.small_blind,
+            big_blind: table.config.stakes.big_blind,
+            ante: table.config.stakes.ante,
+            dealer_button: None,
+            started_at: now,
+            finished_at: None,
+            seats,
+            actions: Vec::new(),
+            board: Vec::new(),
+            winners: Vec::new(),
+            total_pot: Chips::ZERO,
+            pot: None,
+            side_pots: Vec::new(),
+            history: None,
+        }
+    }
+
+    /// Дописать действие игрока.
+    pub fn record_action(
+        &mut self,
+        street: Street,
+        seat: SeatIndex,
+        player_id: PlayerId,
+        kind: PlayerActionKind,
+        auto: bool,
+    ) {
+        self.actions.push(ArchivedAction {
+            street,
+            seat,
+            player_id,
+            kind,
+            auto,
+        });
+    }
+
+    /// Закрыть запись: итоговые стеки, борд, вскрытые карты, победители, банки.
+    pub fn finish(
+        &mut self,
+        table: &Table,
+        snapshot: &HandEngineSnapshot,
+        now: Timestamp,
+    ) {
+        self.finished_at = Some(now);
+        self.board = table.board.clone();
+        self.pot = Some(snapshot.pot.clone());
+        self.side_pots = snapshot.side_pots.clone();
+        self.history = Some(snapshot.history.clone());
+
+        let mut total_pot = Chips::ZERO;
+        for amount in snapshot.contributions.values() {
+            total_pot += *amount;
+        }
+        self.total_pot = total_pot;
+
+        // Шоудаун был, если до конца дошли хотя бы двое.
+        let live_players = table
+            .seats
+            .iter()
+            .filter_map(|s| s.as_ref())
+            .filter(|p| !is_folded(p))
+            .count();
+        let went_to_showdown = live_players >= 2;
+
+        self.winners.clear();
+        for seat in self.seats.iter_mut() {
+            let player = table
+                .seats
+                .get(seat.seat as usize)
+                .and_then(|s| s.as_ref())
+                .filter(|p| p.player_id == seat.player_id);
+
+            let Some(p) = player else {
+                continue;
+            };
+
+            seat.final_stack = p.stack;
+
+            if went_to_showdown && !is_folded(p) {
+                seat.showdown_cards = hole_cards(p);
+            }
+
+            // Выигрыш = итоговый стек − (стартовый − вложено в банк).
+            let contributed = snapshot
+                .contributions
+                .get(&seat.seat)
+                .copied()
+                .unwrap_or(Chips::ZERO);
+            let after_contrib = seat.starting_stack.0.saturating_sub(contributed.0);
+            let won = p.stack.0.saturating_sub(after_contrib);
+
+            if won > 0 {
+                self.winners.push(ArchivedWinner {
+                    seat: seat.seat,
+                    player_id: seat.player_id,
+                    amount: Chips(won),
+                });
+            }
+        }
+    }
+
+    /// Название игры для истории, например "Omaha Pot Limit".
+    pub fn game_label(&self) -> String {
+        TableRules {
+            variant: self.variant,
+            betting: self.betting,
+            ..TableRules::default()
+        }
+        .label()
+    }
+
+    /// Сколько ставок было на улице: ставки и рейзы плюс большой
+    /// блайнд на префлопе (нужно для потолка ставок в лимите).
+    pub fn bets_on(&self, street: Street) -> u8 {
+        let raises = self
+            .actions
+            .iter()
+            .filter(|a| a.street == street)
+            .filter(|a| matches!(a.kind, PlayerActionKind::Bet(_) | PlayerActionKind::Raise(_)))
+            .count();
+        let blind = u8::from(matches!(street, Street::Preflop));
+        blind.saturating_add(raises.min(u8::MAX as usize) as u8)
+    }
+
+    /// Участвовал ли игрок в раздаче.
+    pub fn has_player(&self, player_id: PlayerId) -> bool {
+        self.seats.iter().any(|s| s.player_id == player_id)
+    }
+}
+
+/// Статус игрока как строка (тот же serde-трюк, что и в сервисе).
+fn status_string(p: &PlayerAtTable) -> String {
+    match serde_json::to_value(p.status) {
+        Ok(JsonValue::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+pub(crate) fn is_folded(p: &PlayerAtTable) -> bool {
+    status_string(p) == "Folded"
+}
+
+pub(crate) fn is_sitting_out(p: &PlayerAtTable) -> bool {
+    status_string(p) == "SittingOut"
+}
+
+/// Закрытые карты игрока: домен не даёт к ним прямого API,
+/// поэтому достаём поле `hole_cards` через serde.
+pub(crate) fn hole_cards(p: &PlayerAtTable) -> Option<Vec<Card>> {
+    let val = serde_json::to_value(p).ok()?;
+    let cards = val.get("hole_cards")?.clone();
+    serde_json::from_value::<Option<Vec<Card>>>(cards).ok().flatten()
+}
+
+/// Страница индекса раздач по курсору.
+///
+/// В индексе `count` записей с номерами `0..count` (по возрастанию
+/// hand_id); отдаём номера от новых к старым. `after` — последний номер
+/// предыдущей страницы, следующая начинается строго перед ним.
+pub fn page_positions(count: u64, after: Option<u64>, limit: usize) -> (Vec<u64>, bool) {
+    let end = after.map_or(count, |a| a.min(count));
+    let start = end.saturating_sub(limit as u64);
+    let page: Vec<u64> = (start..end).rev().collect();
+    (page, start > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_walk_the_index_from_newest() {
+        let (page, more) = page_positions(5, None, 2);
+        assert_eq!((page, more), (vec![4, 3], true));
+        let (page, more) = page_positions(5, Some(3), 2);
+        assert_eq!((page, more), (vec![2, 1], true));
+        let (page, more) = page_positions(5, Some(1), 2);
+        assert_eq!((page, more), (vec![0], false));
+        let (page, more) = page_positions(5, Some(0), 2);
+        assert_eq!((page, more), (vec![], false));
+    }
+
+    #[test]
+    fn pages_tolerate_stale_or_empty_cursors() {
+        assert_eq!(page_positions(0, None, 20), (vec![], false));
+        assert_eq!(page_positions(3, Some(u64::MAX), 20), (vec![2, 1, 0], false));
+        assert_eq!(page_positions(3, None, 3), (vec![2, 1, 0], false));
+    }
+}
@@ -0,0 +1,678 @@
+//! Экспорт архивной раздачи в текстовый формат PokerStars.
+//!
+//! Формат понимают трекеры (HM3, PT4, H2N и т.п.), поэтому держимся
+//! его дословно: заголовок, места, блайнды, улицы, шоудаун, summary.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde_json::Value as JsonValue;
+
+use poker_engine::domain::card::Card;
+use poker_engine::domain::{PlayerId, SeatIndex};
+use poker_engine::engine::actions::PlayerActionKind;
+
+use crate::hand_archive::{ArchivedAction, ArchivedHand, ArchivedSeat};
+use crate::showdown::rank_suit;
+
+/// Улицы в порядке раздачи: (имя в Street, заголовок PokerStars, карт на борде).
+const STREETS: [(&str, &str, usize); 4] = [
+    ("Preflop", "HOLE CARDS", 0),
+    ("Flop", "FLOP", 3),
+    ("Turn", "TURN", 4),
+    ("River", "RIVER", 5),
+];
+
+/// Отрендерить раздачу в формате PokerStars.
+pub fn to_pokerstars_text(hand: &ArchivedHand) -> String {
+    let mut out = String::new();
+    let names: HashMap<PlayerId, &str> = hand
+        .seats
+        .iter()
+        .map(|s| (s.player_id, s.display_name.as_str()))
+        .collect();
+    let name = |pid: PlayerId| names.get(&pid).copied().unwrap_or("Unknown");
+
+    // ---- Заголовок ----
+    let game = match hand.tournament_id {
+        Some(tid) => format!(
+            "Tournament #{tid}, {} - ({}/{})",
+            hand.game_label(),
+            hand.small_blind.0,
+            hand.big_blind.0
+        ),
+        None => format!(
+            "{} ({}/{})",
+            hand.game_label(),
+            hand.small_blind.0,
+            hand.big_blind.0
+        ),
+    };
+    let _ = writeln!(
+        out,
+        "PokerStars Hand #{}: {} - {} UTC",
+        hand.hand_id,
+        game,
+        format_timestamp(hand.started_at.micros())
+    );
+
+    let button_seat = hand.dealer_button.map(|b| b as u32 + 1).unwrap_or(1);
+    let _ = writeln!(
+        out,
+        "Table '{}' {}-max Seat #{} is the button",
+        hand.table_name, hand.max_seats, button_seat
+    );
+
+    for seat in &hand.seats {
+        let _ = writeln!(
+            out,
+            "Seat {}: {} ({} in chips)",
+            seat.seat as u32 + 1,
+            seat.display_name,
+            seat.starting_stack.0
+        );
+    }
+
+    // ---- Анте и блайнды ----
+    // Сколько игрок вложил на текущей улице и сколько у него осталось.
+    let mut committed: HashMap<PlayerId, u64> = HashMap::new();
+    let mut remaining: HashMap<PlayerId, u64> = hand
+        .seats
+        .iter()
+        .map(|s| (s.player_id, s.starting_stack.0))
+        .collect();
+
+    if !hand.ante.is_zero() {
+        for seat in &hand.seats {
+            let paid = post(&mut remaining, seat.player_id, hand.ante.0);
+            if paid > 0 {
+                let _ = writeln!(
+                    out,
+                    "{}: posts the ante {}",
+                    seat.display_name, paid
+                );
+            }
+        }
+    }
+
+    let (sb_seat, bb_seat) = blind_seats(hand);
+    if let Some(sb) = sb_seat {
+        let paid = post(&mut remaining, sb.player_id, hand.small_blind.0);
+        *committed.entry(sb.player_id).or_default() += paid;
+        let _ = writeln!(out, "{}: posts small blind {}", sb.display_name, paid);
+    }
+    if let Some(bb) = bb_seat {
+        let paid = post(&mut remaining, bb.player_id, hand.big_blind.0);
+        *committed.entry(bb.player_id).or_default() += paid;
+        let _ = writeln!(out, "{}: posts big blind {}", bb.display_name, paid);
+    }
+
+    // ---- Улицы ----
+    let mut folded_on: HashMap<PlayerId, &str> = HashMap::new();
+    let mut current_bet: u64 = hand.big_blind.0;
+
+    for (street_name, header, board_len) in STREETS {
+        let actions: Vec<&ArchivedAction> = hand
+            .actions
+            .iter()
+            .filter(|a| enum_to_string(&a.street) == street_name)
+            .collect();
+
+        if board_len > 0 {
+            if hand.board.len() < board_len {
+                break;
+            }
+            // Новая улица — ставки обнуляются.
+            committed.clear();
+            current_bet = 0;
+
+            let _ = writeln!(
+                out,
+                "*** {} *** {}",
+                header,
+                street_board(&hand.board, board_len)
+            );
+        } else {
+            let _ = writeln!(out, "*** {header} ***");
+        }
+
+        for a in actions {
+            let who = name(a.player_id);
+            let line = render_action(
+                a,
+                &mut committed,
+                &mut remaining,
+                &mut current_bet,
+            );
+            if matches!(a.kind, PlayerActionKind::Fold) {
+                folded_on.insert(a.player_id, street_label(street_name));
+            }
+            let _ = writeln!(out, "{who}: {line}");
+        }
+    }
+
+    // Ставку, которую никто не уравнял, игрок забирает назад: в банк
+    // и в выигрыш она не входит.
+    let uncalled = uncalled_bet(&committed);
+    if let Some((player_id, amount)) = uncalled {
+        let _ = writeln!(out, "Uncalled bet ({amount}) returned to {}", name(player_id));
+    }
+    let returned_to = |pid: PlayerId| match uncalled {
+        Some((player_id, amount)) if player_id == pid => amount,
+        _ => 0,
+    };
+    let collected = |pid: PlayerId, amount: u64| amount.saturating_sub(returned_to(pid));
+
+    // ---- Шоудаун ----
+    let showed: Vec<&ArchivedSeat> = hand
+        .seats
+        .iter()
+        .filter(|s| s.showdown_cards.is_some())
+        .collect();
+
+    if !showed.is_empty() {
+        let _ = writeln!(out, "*** SHOW DOWN ***");
+        for s in &showed {
+            let _ = writeln!(
+                out,
+                "{}: shows {}",
+                s.display_name,
+                cards_to_text(s.showdown_cards.as_deref().unwrap_or(&[]))
+            );
+        }
+    }
+
+    for w in &hand.winners {
+        let _ = writeln!(
+            out,
+            "{} collected {} from pot",
+            name(w.player_id),
+            collected(w.player_id, w.amount.0)
+        );
+    }
+
+    // ---- Summary ----
+    let total_pot = hand
+        .total_pot
+        .0
+        .saturating_sub(uncalled.map_or(0, |(_, amount)| amount));
+    let _ = writeln!(out, "*** SUMMARY ***");
+    let _ = writeln!(out, "Total pot {total_pot} | Rake 0");
+    if !hand.board.is_empty() {
+        let _ = writeln!(out, "Board {}", cards_to_text(&hand.board));
+    }
+
+    for seat in &hand.seats {
+        let mut line = format!("Seat {}: {}", seat.seat as u32 + 1, seat.display_name);
+        if Some(seat.seat) == hand.dealer_button {
+            line.push_str(" (button)");
+        } else if Some(seat.seat) == sb_seat.map(|s| s.seat) {
+            line.push_str(" (small blind)");
+        } else if Some(seat.seat) == bb_seat.map(|s| s.seat) {
+            line.push_str(" (big blind)");
+        }
+
+        let won = hand
+            .winners
+            .iter()
+            .find(|w| w.player_id == seat.player_id)
+            .map(|w| collected(w.player_id, w.amount.0));
+
+        match (&seat.showdown_cards, won, folded_on.get(&seat.player_id)) {
+            (_, _, Some(street)) => {
+                let _ = write!(line, " folded {street}");
+            }
+            (Some(cards), Some(amount), _) => {
+                let _ = write!(
+                    line,
+                    " showed {} and won ({})",
+                    cards_to_text(cards),
+                    amount
+                );
+            }
+            (Some(cards), None, _) => {
+                let _ = write!(line, " showed {} and lost", cards_to_text(cards));
+            }
+            (None, Some(amount), _) => {
+                let _ = write!(line, " collected ({amount})");
+            }
+            (None, None, _) => {}
+        }
+
+        let _ = writeln!(out, "{line}");
+    }
+
+    out
+}
+
+/// Неуравненная часть самой большой ставки последней улицы:
+/// (кто поставил, сколько вернётся).
+fn uncalled_bet(committed: &HashMap<PlayerId, u64>) -> Option<(PlayerId, u64)> {
+    let (&top_player, &top) = committed.iter().max_by_key(|(_, amount)| **amount)?;
+    let called = committed
+        .iter()
+        .filter(|(pid, _)| **pid != top_player)
+        .map(|(_, amount)| *amount)
+        .max()
+        .unwrap_or(0);
+    (top > called).then_some((top_player, top - called))
+}
+
+/// Одно действие в терминах PokerStars ("calls 20", "raises 40 to 60" и т.п.).
+fn render_action(
+    a: &ArchivedAction,
+    committed: &mut HashMap<PlayerId, u64>,
+    remaining: &mut HashMap<PlayerId, u64>,
+    current_bet: &mut u64,
+) -> String {
+    let already = committed.get(&a.player_id).copied().unwrap_or(0);
+    let stack = remaining.get(&a.player_id).copied().unwrap_or(0);
+
+    let line = match &a.kind {
+        PlayerActionKind::Fold => "folds".to_string(),
+        PlayerActionKind::Check => "checks".to_string(),
+        PlayerActionKind::Call => {
+            let paid = current_bet.saturating_sub(already).min(stack);
+            pay(committed, remaining, a.player_id, paid);
+            all_in_suffix(format!("calls {paid}"), paid, stack)
+        }
+        // Bet — прибавка к уже вложенному на улице (как в `rules::requested_total`),
+        // поверх чужой ставки это рейз.
+        PlayerActionKind::Bet(amount) => {
+            let paid = amount.0.min(stack);
+            let total = already + paid;
+            pay(committed, remaining, a.player_id, paid);
+            let line = if *current_bet == 0 {
+                format!("bets {paid}")
+            } else {
+                format!("raises {} to {total}", total.saturating_sub(*current_bet))
+            };
+            *current_bet = (*current_bet).max(total);
+            all_in_suffix(line, paid, stack)
+        }
+        PlayerActionKind::Raise(to) => {
+            let paid = to.0.saturating_sub(already).min(stack);
+            let total = already + paid;
+            let by = total.saturating_sub(*current_bet);
+            pay(committed, remaining, a.player_id, paid);
+            *current_bet = total;
+            all_in_suffix(format!("raises {by} to {total}"), paid, stack)
+        }
+        PlayerActionKind::AllIn => {
+            let total = already + stack;
+            pay(committed, remaining, a.player_id, stack);
+            if total <= *current_bet {
+                format!("calls {stack} and is all-in")
+            } else if *current_bet == 0 {
+                *current_bet = total;
+                format!("bets {stack} and is all-in")
+            } else {
+                let by = total - *current_bet;
+                *current_bet = total;
+                format!("raises {by} to {total} and is all-in")
+            }
+        }
+    };
+
+    if a.auto {
+        format!("{line} (timed out)")
+    } else {
+        line
+    }
+}
+
+fn all_in_suffix(line: String, paid: u64, stack: u64) -> String {
+    if paid > 0 && paid == stack {
+        format!("{line} and is all-in")
+    } else {
+        line
+    }
+}
+
+fn pay(
+    committed: &mut HashMap<PlayerId, u64>,
+    remaining: &mut HashMap<PlayerId, u64>,
+    player_id: PlayerId,
+    amount: u64,
+) {
+    *committed.entry(player_id).or_default() += amount;
+    if let Some(r) = remaining.get_mut(&player_id) {
+        *r = r.saturating_sub(amount);
+    }
+}
+
+/// Списать обязательную ставку (не больше стека), вернуть фактически внесённое.
+fn post(remaining: &mut HashMap<PlayerId, u64>, player_id: PlayerId, amount: u64) -> u64 {
+    let stack = remaining.get(&player_id).copied().unwrap_or(0);
+    let paid = amount.min(stack);
+    if let Some(r) = remaining.get_mut(&player_id) {
+        *r -= paid;
+    }
+    paid
+}
+
+/// SB и BB по кнопке: следующие занятые места по часовой стрелке.
+/// Хедз-ап: кнопка ставит малый блайнд.
+fn blind_seats(hand: &ArchivedHand) -> (Option<&ArchivedSeat>, Option<&ArchivedSeat>) {
+    let mut seats: Vec<&ArchivedSeat> = hand.seats.iter().collect();
+    seats.sort_by_key(|s| s.seat);
+
+    if seats.len() < 2 {
+        return (None, None);
+    }
+
+    let button: SeatIndex = hand.dealer_button.unwrap_or(seats[0].seat);
+    let button_pos = seats.iter().position(|s| s.seat == button).unwrap_or(0);
+
+    let sb_pos = if seats.len() == 2 {
+        button_pos
+    } else {
+        (button_pos + 1) % seats.len()
+    };
+    let bb_pos = (sb_pos + 1) % seats.len();
+
+    (Some(seats[sb_pos]), Some(seats[bb_pos]))
+}
+
+fn street_label(street: &str) -> &'static str {
+    match street {
+        "Preflop" => "before Flop",
+        "Flop" => "on the Flop",
+        "Turn" => "on the Turn",
+        _ => "on the River",
+    }
+}
+
+/// Борд для заголовка улицы: "[Ah Kd 2c]", "[Ah Kd 2c] [5s]" и т.д.
+fn street_board(board: &[Card], upto: usize) -> String {
+    if upto <= 3 {
+        cards_to_text(&board[..upto])
+    } else {
+        format!(
+            "{} {}",
+            cards_to_text(&board[..upto - 1]),
+            cards_to_text(&board[upto - 1..upto])
+        )
+    }
+}
+
+fn cards_to_text(cards: &[Card]) -> String {
+    let inner: Vec<String> = cards.iter().map(card_to_text).collect();
+    format!("[{}]", inner.join(" "))
+}
+
+/// Карта в нотации PokerStars ("As", "Td", "9c").
+fn card_to_text(card: &Card) -> String {
+    const RANKS: &[u8; 13] = b"23456789TJQKA";
+    const SUITS: &[u8; 4] = b"shdc";
+
+    match rank_suit(card) {
+        Some((rank, suit)) => format!(
+            "{}{}",
+            RANKS[usize::from(rank - 2)] as char,
+            SUITS[usize::from(suit)] as char
+        ),
+        None => "??".to_string(),
+    }
+}
+
+fn enum_to_string<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value).unwrap_or(JsonValue::Null) {
+        JsonValue::String(s) => s,
+        _ => String::new(),
+    }
+}
+
+/// Время цепи (микросекунды с эпохи) → "YYYY/MM/DD HH:MM:SS".
+fn format_timestamp(micros: u64) -> String {
+    let secs = micros / 1_000_000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (hh, mm, ss) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    // Civil-from-days (Howard Hinnant).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}/{month:02}/{day:02} {hh:02}:{mm:02}:{ss:02}")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Timestamp;
+
+    use poker_engine::domain::chips::Chips;
+    use poker_engine::domain::hand::Street;
+    use poker_engine::domain::TournamentId;
+    use poker_engine::engine::actions::PlayerActionKind::{self, *};
+
+    use super::*;
+    use crate::hand_archive::ArchivedWinner;
+    use crate::rules::{BettingStructure, GameVariant};
+    use crate::test_support::cards;
+
+    /// 2026-10-18 19:00:00 UTC.
+    const EVENING: u64 = 1_792_350_000_000_000;
+
+    struct Spec {
+        hand_id: u64,
+        tournament_id: Option<TournamentId>,
+        table_name: &'static str,
+        betting: BettingStructure,
+        blinds: (u64, u64, u64),
+        button: SeatIndex,
+        minutes: u64,
+        /// (место, player_id, имя, стек, вскрытые карты)
+        seats: Vec<(SeatIndex, PlayerId, &'static str, u64, Option<&'static str>)>,
+        actions: Vec<(Street, PlayerId, PlayerActionKind)>,
+        board: &'static str,
+        winners: Vec<(PlayerId, u64)>,
+    }
+
+    fn hand(spec: Spec) -> ArchivedHand {
+        let seat_of = |pid: PlayerId| {
+            spec.seats
+                .iter()
+                .find(|s| s.1 == pid)
+                .map_or(0, |s| s.0)
+        };
+        let started_at = Timestamp::from(EVENING + spec.minutes * 60_000_000);
+        ArchivedHand {
+            hand_id: spec.hand_id,
+            table_id: 7,
+            table_name: spec.table_name.to_string(),
+            tournament_id: spec.tournament_id,
+            variant: GameVariant::Holdem,
+            betting: spec.betting,
+            max_seats: 6,
+            small_blind: Chips(spec.blinds.0),
+            big_blind: Chips(spec.blinds.1),
+            ante: Chips(spec.blinds.2),
+            dealer_button: Some(spec.button),
+            started_at,
+            finished_at: Some(started_at),
+            seats: spec
+                .seats
+                .iter()
+                .map(|(seat, player_id, name, stack, shown)| ArchivedSeat {
+                    seat: *seat,
+                    player_id: *player_id,
+                    display_name: name.to_string(),
+                    starting_stack: Chips(*stack),
+                    final_stack: Chips::ZERO,
+                    showdown_cards: shown.map(cards),
+                })
+                .collect(),
+            actions: spec
+                .actions
+                .iter()
+                .map(|(street, player_id, kind)| ArchivedAction {
+                    street: *street,
+                    seat: seat_of(*player_id),
+                    player_id: *player_id,
+                    kind: kind.clone(),
+                    auto: false,
+                })
+                .collect(),
+            board: cards(spec.board),
+            winners: spec
+                .winners
+                .iter()
+                .map(|(player_id, amount)| ArchivedWinner {
+                    seat: seat_of(*player_id),
+                    player_id: *player_id,
+                    amount: Chips(*amount),
+                })
+                .collect(),
+            total_pot: Chips(spec.winners.iter().map(|(_, a)| a).sum()),
+            pot: None,
+            side_pots: Vec::new(),
+            history: None,
+        }
+    }
+
+    #[test]
+    fn no_limit_hand_matches_golden() {
+        let hand = hand(Spec {
+            hand_id: 1001,
+            tournament_id: None,
+            table_name: "Main",
+            betting: BettingStructure::NoLimit,
+            blinds: (5, 10, 0),
+            button: 0,
+            minutes: 0,
+            seats: vec![
+                (0, 1, "alice", 1000, Some("As Ks")),
+                (1, 2, "bob", 1000, None),
+                (2, 3, "carol", 500, Some("Kh Qh")),
+            ],
+            actions: vec![
+                (Street::Preflop, 1, Raise(Chips(30))),
+                (Street::Preflop, 2, Call),
+                (Street::Preflop, 3, Call),
+                (Street::Flop, 2, Check),
+                (Street::Flop, 3, Bet(Chips(40))),
+                (Street::Flop, 1, Raise(Chips(120))),
+                (Street::Flop, 2, Fold),
+                (Street::Flop, 3, Call),
+                (Street::Turn, 3, Check),
+                (Street::Turn, 1, Check),
+                (Street::River, 3, Bet(Chips(50))),
+                (Street::River, 1, Call),
+            ],
+            board: "Ah Kd 2c 5s 9h",
+            winners: vec![(1, 430)],
+        });
+
+        assert_eq!(
+            to_pokerstars_text(&hand),
+            include_str!("../tests/golden/hand_export_no_limit.txt")
+        );
+    }
+
+    #[test]
+    fn side_pot_all_in_hand_matches_golden() {
+        let hand = hand(Spec {
+            hand_id: 2002,
+            tournament_id: Some(42),
+            table_name: "T#42/1",
+            betting: BettingStructure::NoLimit,
+            blinds: (50, 100, 10),
+            button: 4,
+            minutes: 5,
+            seats: vec![
+                (0, 11, "dave", 1500, Some("Qc Qd")),
+                (2, 12, "erin", 800, Some("Ac Ad")),
+                (4, 13, "frank", 2000, Some("Kc Kd")),
+            ],
+            actions: vec![
+                (Street::Preflop, 13, Raise(Chips(300))),
+                (Street::Preflop, 11, AllIn),
+                (Street::Preflop, 12, AllIn),
+                (Street::Preflop, 13, Call),
+            ],
+            board: "2h 7s 9d Jc 3h",
+            winners: vec![(12, 2400), (13, 1400)],
+        });
+
+        assert_eq!(
+            to_pokerstars_text(&hand),
+            include_str!("../tests/golden/hand_export_side_pot.txt")
+        );
+    }
+
+    #[test]
+    fn fixed_limit_hand_matches_golden() {
+        let hand = hand(Spec {
+            hand_id: 3003,
+            tournament_id: None,
+            table_name: "Limit",
+            betting: BettingStructure::FixedLimit {
+                small_bet: Chips(10),
+                big_bet: Chips(20),
+                max_bets: 4,
+            },
+            blinds: (5, 10, 0),
+            button: 1,
+            minutes: 10,
+            seats: vec![(0, 21, "gina", 200, None), (1, 22, "hank", 200, None)],
+            actions: vec![
+                (Street::Preflop, 22, Raise(Chips(20))),
+                (Street::Preflop, 21, Call),
+                (Street::Flop, 21, Check),
+                (Street::Flop, 22, Bet(Chips(10))),
+                (Street::Flop, 21, Raise(Chips(20))),
+                (Street::Flop, 22, Call),
+                (Street::Turn, 21, Bet(Chips(20))),
+                (Street::Turn, 22, Fold),
+            ],
+            board: "Th 8s 3d 4c",
+            winners: vec![(21, 100)],
+        });
+
+        assert_eq!(
+            to_pokerstars_text(&hand),
+            include_str!("../tests/golden/hand_export_limit.txt")
+        );
+    }
+
+    #[test]
+    fn uncalled_part_of_the_top_bet_is_returned() {
+        // Все сбросили до большого блайнда.
+        assert_eq!(uncalled_bet(&HashMap::from([(1, 5), (2, 10)])), Some((2, 5)));
+        // Олл-ин больше стека единственного соперника.
+        assert_eq!(
+            uncalled_bet(&HashMap::from([(1, 300), (2, 120), (3, 40)])),
+            Some((1, 180))
+        );
+        assert_eq!(uncalled_bet(&HashMap::from([(1, 50), (2, 50)])), None);
+        assert_eq!(uncalled_bet(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn bet_over_a_bet_is_rendered_as_raise_increment() {
+        let mut committed = HashMap::from([(1, 10)]);
+        let mut remaining = HashMap::from([(1, 100)]);
+        let mut current_bet = 20;
+        let action = ArchivedAction {
+            street: Street::Flop,
+            seat: 0,
+            player_id: 1,
+            kind: Bet(Chips(30)),
+            auto: false,
+        };
+
+        let line = render_action(&action, &mut committed, &mut remaining, &mut current_bet);
+        assert_eq!(line, "raises 20 to 40");
+        assert_eq!(current_bet, 40);
+        assert_eq!(remaining[&1], 70);
+    }
+}
@@ -0,0 +1,101 @@
+//! Игра рука-в-руку на пузыре турнира.
+//!
+//! Когда до призовых остаётся один вылет, турнир переходит в раунды
+//! (`PokerState::hand_for_hand`): каждый стол играет одну раздачу и ждёт,
+//! пока её доиграют все столы `tournament_tables`. Вылетевшие за раунд
+//! получают места после его конца — выше тот, у кого был больше стек
+//! на начало раздачи; при равных стеках места и призы делятся.
+//!
+//! Ожидание проверяется там, где раздача стартует на этой цепи
+//! (`StartHand` и авто-дилер).
+
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::{PlayerId, TableId};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandForHand {
+    pub round: u32,
+    /// Стеки живых игроков на начало раунда.
+    pub starting_stacks: Vec<(PlayerId, Chips)>,
+    /// Столы, уже доигравшие раздачу этого раунда.
+    pub done: Vec<TableId>,
+    /// Вылетевшие в раунде; места раздаются в конце раунда.
+    pub busted: Vec<PlayerId>,
+}
+
+impl HandForHand {
+    pub fn new(starting_stacks: Vec<(PlayerId, Chips)>) -> Self {
+        Self {
+            round: 1,
+            starting_stacks,
+            done: Vec::new(),
+            busted: Vec::new(),
+        }
+    }
+
+    pub fn table_done(&self, table_id: TableId) -> bool {
+        self.done.contains(&table_id)
+    }
+
+    pub fn mark_done(&mut self, table_id: TableId) {
+        if !self.table_done(table_id) {
+            self.done.push(table_id);
+        }
+    }
+
+    /// Раунд окончен, когда раздачу доиграли все столы, где она возможна.
+    pub fn round_complete(&self, dealing_tables: &[TableId]) -> bool {
+        dealing_tables.iter().all(|t| self.table_done(*t))
+    }
+
+    pub fn next_round(&mut self, starting_stacks: Vec<(PlayerId, Chips)>) {
+        self.round += 1;
+        self.starting_stacks = starting_stacks;
+        self.done.clear();
+        self.busted.clear();
+    }
+
+    fn starting_stack(&self, player_id: PlayerId) -> u64 {
+        self.starting_stacks
+            .iter()
+            .find(|(pid, _)| *pid == player_id)
+            .map_or(0, |(_, stack)| stack.0)
+    }
+
+    /// Места вылетевших в раунде при `remaining` живых после него.
+    ///
+    /// Группы (места, игроки) от лучших мест к худшим: игроки с равным
+    /// стартовым стеком попадают в одну группу и делят её места.
+    pub fn placements(&self, remaining: u32) -> Vec<(Vec<u32>, Vec<PlayerId>)> {
+        let mut busted = self.busted.clone();
+        busted.sort_by(|a, b| {
+            self.starting_stack(*b)
+                .cmp(&self.starting_stack(*a))
+                .then(a.cmp(b))
+        });
+
+        let mut groups: Vec<(Vec<u32>, Vec<PlayerId>)> = Vec::new();
+        let mut place = remaining;
+        let mut last_stack = None;
+        for player_id in busted {
+            place += 1;
+            let stack = self.starting_stack(player_id);
+            match groups.last_mut() {
+                Some((places, players)) if last_stack == Some(stack) => {
+                    places.push(place);
+                    players.push(player_id);
+                }
+                _ => groups.push((vec![place], vec![player_id])),
+            }
+            last_stack = Some(stack);
+        }
+        groups
+    }
+}
+
+/// Один вылет до призовых: живых ровно на одного больше, чем платных мест.
+pub fn on_bubble(alive: u32, paid_places: u32) -> bool {
+    paid_places > 0 && alive == paid_places + 1
+}
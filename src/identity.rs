@@ -0,0 +1,99 @@
+//! Отображаемые имена игроков: валидация и нормализация.
+//!
+//! Имена уникальны без учёта регистра: индекс `PokerState::name_index`
+//! хранит ключ `normalize_name(name)`. Зарезервированные имена тоже
+//! сравниваются без учёта регистра, так что "Admin" и "ADMIN" запрещены.
+
+/// Допустимая длина имени, в символах.
+pub const DISPLAY_NAME_MIN_LEN: usize = 3;
+pub const DISPLAY_NAME_MAX_LEN: usize = 20;
+
+/// Имена, которые нельзя занять (в нижнем регистре).
+const RESERVED_NAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "owner",
+    "dealer",
+    "system",
+    "moderator",
+    "support",
+    "linera",
+];
+
+/// Ключ для индекса уникальности и сравнения с зарезервированными.
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// Проверить имя. Err — человекочитаемая причина.
+///
+/// Разрешены латинские буквы, цифры, `_`, `-`, `.` и одиночные пробелы
+/// внутри. Только ASCII: иначе "аdmin" с кириллической "а" обходит
+/// и резерв, и уникальность.
+pub fn validate_display_name(name: &str) -> Result<(), String> {
+    let len = name.chars().count();
+    if !(DISPLAY_NAME_MIN_LEN..=DISPLAY_NAME_MAX_LEN).contains(&len) {
+        return Err(format!(
+            "length must be {DISPLAY_NAME_MIN_LEN}..={DISPLAY_NAME_MAX_LEN} characters"
+        ));
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ')))
+    {
+        return Err(format!("character {c:?} is not allowed"));
+    }
+
+    if name.starts_with(' ') || name.ends_with(' ') || name.contains("  ") {
+        return Err("leading, trailing or repeated spaces are not allowed".to_string());
+    }
+
+    if RESERVED_NAMES.contains(&normalize_name(name).as_str()) {
+        return Err(format!("{name:?} is reserved"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_names_are_rejected_in_any_case() {
+        for name in ["admin", "Admin", "ADMIN", "Dealer", "linera"] {
+            assert!(validate_display_name(name).is_err(), "{name}");
+        }
+        assert!(validate_display_name("admin2").is_ok());
+    }
+
+    #[test]
+    fn length_is_limited() {
+        assert!(validate_display_name("ab").is_err());
+        assert!(validate_display_name("abc").is_ok());
+        assert!(validate_display_name(&"a".repeat(DISPLAY_NAME_MAX_LEN)).is_ok());
+        assert!(validate_display_name(&"a".repeat(DISPLAY_NAME_MAX_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn only_ascii_letters_digits_and_separators_are_allowed() {
+        assert!(validate_display_name("Big_Stack-99.x").is_ok());
+        assert!(validate_display_name("big stack").is_ok());
+        // Кириллическая "а" и греческая "ο" выглядят как латиница.
+        assert!(validate_display_name("\u{430}dmin").is_err());
+        assert!(validate_display_name("b\u{3bf}b").is_err());
+        assert!(validate_display_name("José").is_err());
+        assert!(validate_display_name("bob!").is_err());
+        assert!(validate_display_name(" bob").is_err());
+        assert!(validate_display_name("bob ").is_err());
+        assert!(validate_display_name("big  stack").is_err());
+    }
+
+    #[test]
+    fn names_differing_only_in_case_collide() {
+        assert_eq!(normalize_name("PokerPro"), normalize_name("pokerpro"));
+        assert_eq!(normalize_name("POKER.PRO"), normalize_name("poker.pro"));
+        assert_ne!(normalize_name("poker_pro"), normalize_name("poker-pro"));
+    }
+}
@@ -1,16 +1,44 @@
 //! Poker on-chain application for Linera 0.15.6.
 
+pub mod commands;
+pub mod deals;
+pub mod events;
+pub mod hand_archive;
+pub mod hand_export;
+pub mod hand_for_hand;
+pub mod identity;
+pub mod players;
+pub mod roles;
+pub mod rules;
+pub mod satellites;
+pub mod schedule;
+pub mod seasons;
+pub mod seat_draw;
+pub mod showdown;
+pub mod sng;
+pub mod standings;
 pub mod state;
 pub mod orchestrator;
 pub mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 use async_graphql::{Request, Response};
 use linera_sdk::abi::{ContractAbi, ServiceAbi};
-use linera_sdk::linera_base_types::AccountOwner;
+use linera_sdk::linera_base_types::{AccountOwner, ChainId};
 use serde::{Deserialize, Serialize};
 
 use poker_engine::api::commands::Command;
 use poker_engine::api::dto::CommandResponse;
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::player::PlayerAtTable;
+use poker_engine::domain::table::{Table, TableStakes};
+use poker_engine::domain::{HandId, PlayerId, SeatIndex, TableId, TournamentId};
+
+use crate::commands::OnchainCommand;
+use crate::roles::TableRoles;
+use crate::rules::TableRules;
 
 /// Параметры приложения, задаются при деплое.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -24,15 +52,155 @@ pub struct ApplicationParameters {
 #[derive(Clone, Debug)]
 pub struct PokerAbi;
 
-/// Единственный тип операции.
+/// Операции приложения.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Operation {
+    /// Команда off-chain движка (столы, раздачи, турниры).
     Command(Command),
+    /// Команда on-chain слоя (см. `commands`).
+    Onchain(OnchainCommand),
 }
 
-/// Сообщения между цепями (пока не используем).
+/// Сообщения между цепями.
+///
+/// Банкролл игрока живёт на его собственной цепи: бай-ин уходит на цепь
+/// стола в `SeatRequest`, обратно фишки приходят в `CashOut` / `SeatRejected`.
+/// Реестр игроков общий и ведётся на координаторе: регистрация и смена
+/// аккаунта дублируются туда, а `SeatRequest` идёт через координатор,
+/// который проверяет подписанта и пересылает запрос цепи стола.
+/// Сообщения отправляются с трекингом: вернувшиеся (bounced) фишки
+/// паркуются в банкролле игрока на той цепи, куда вернулись. Отклонённое
+/// получателем сообщение с фишками возвращается отправителю ответом
+/// (`SeatRejected` / `ChipsReturned`).
+///
+/// Схема шардинга: цепь-координатор (лобби) держит турниры и зеркала
+/// столов, сами столы живут на своих микроцепях. Координатор открывает
+/// стол (`OpenTable`), цепь стола после каждой раздачи отчитывается
+/// (`HandFinished`), координатор в ответ пересаживает игроков (`SyncSeats`),
+/// меняет блайнды (`UpdateStakes`) или закрывает пустой стол (`CloseTable`).
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum Message {}
+pub enum Message {
+    /// Координатор → цепь стола: разместить стол на этой цепи.
+    OpenTable {
+        table: Table,
+        tournament_id: Option<TournamentId>,
+        #[serde(default)]
+        rules: TableRules,
+        /// Роли, действующие на этом столе (см. `SyncRoles`).
+        #[serde(default)]
+        roles: TableRoles,
+    },
+    /// Координатор → цепь стола: новая рассадка (ребалансировка турнира).
+    /// Применяется между раздачами.
+    SyncSeats {
+        table_id: TableId,
+        seats: Vec<Option<PlayerAtTable>>,
+    },
+    /// Координатор → цепь стола: новые блайнды (уровень турнира).
+    UpdateStakes {
+        table_id: TableId,
+        stakes: TableStakes,
+    },
+    /// Координатор → цепь стола: роли изменились (выдача, отзыв,
+    /// смена владельца). Цепь стола проверяет права по этой копии.
+    SyncRoles { table_id: TableId, roles: TableRoles },
+    /// Координатор → цепь стола: стол больше не нужен.
+    CloseTable { table_id: TableId },
+    /// Цепь стола → координатор: раздача завершена, итоговые стеки по местам.
+    HandFinished {
+        table_id: TableId,
+        hand_id: HandId,
+        stacks: Vec<(SeatIndex, PlayerId, Chips)>,
+        /// Банк раздачи (для статистики лобби на координаторе).
+        total_pot: Chips,
+        /// Игроки, оставшиеся без фишек.
+        busted: Vec<PlayerId>,
+    },
+    /// Цепь стола → координатор: стеки изменились вне раздачи
+    /// (посадка, AdjustStack и т.п.).
+    StacksUpdated {
+        table_id: TableId,
+        stacks: Vec<(SeatIndex, PlayerId, Chips)>,
+    },
+
+    // ---- Банкролл на цепи игрока ----
+    /// Координатор → цепь игрока: пополнение банкролла.
+    Credit { player_id: PlayerId, amount: Chips },
+    /// Цепь игрока → координатор: посадить игрока за cash-стол, бай-ин
+    /// уже списан. Подписант должен быть зарегистрированным владельцем
+    /// `player_id`.
+    SeatRequest {
+        table_id: TableId,
+        player_id: PlayerId,
+        seat_index: SeatIndex,
+        buy_in: Chips,
+        display_name: String,
+    },
+    /// Координатор → цепь стола: проверенный `SeatRequest` с цепи игрока
+    /// `player_chain`.
+    SeatForwarded {
+        table_id: TableId,
+        player_id: PlayerId,
+        player_chain: ChainId,
+        seat_index: SeatIndex,
+        buy_in: Chips,
+        display_name: String,
+    },
+    /// Цепь игрока → координатор: игрок зарегистрирован на своей цепи.
+    RegisterPlayer {
+        player_id: PlayerId,
+        display_name: String,
+    },
+    /// Цепь игрока → координатор: player_id перенесён на новый аккаунт.
+    RotateAccount {
+        player_id: PlayerId,
+        new_account: AccountOwner,
+    },
+    /// Цепь стола или координатор → цепь игрока: посадка не удалась,
+    /// бай-ин возвращается.
+    SeatRejected {
+        table_id: TableId,
+        player_id: PlayerId,
+        refund: Chips,
+        reason: String,
+    },
+    /// Цепь игрока → цепь стола: игрок хочет встать.
+    CashOutRequest { table_id: TableId, player_id: PlayerId },
+    /// Цепь стола → цепь игрока: стек игрока возвращается в банкролл.
+    CashOut {
+        table_id: TableId,
+        player_id: PlayerId,
+        amount: Chips,
+    },
+    /// Цепь игрока → цепь стола: CashOut или SeatRejected не принят,
+    /// фишки паркуются в банкролле игрока на цепи стола.
+    ChipsReturned {
+        table_id: TableId,
+        player_id: PlayerId,
+        amount: Chips,
+        reason: String,
+    },
+    /// Цепь стола → цепь игрока: текущий стек за столом (после посадки,
+    /// раздачи или AdjustStack). По нему проверяется сумма CashOut.
+    SeatStack {
+        table_id: TableId,
+        player_id: PlayerId,
+        stack: Chips,
+    },
+}
+
+impl Message {
+    /// Сообщение несёт подпись отправителя: по ней координатор узнаёт
+    /// владельца player_id.
+    pub fn is_authenticated(&self) -> bool {
+        matches!(
+            self,
+            Message::SeatRequest { .. }
+                | Message::RegisterPlayer { .. }
+                | Message::RotateAccount { .. }
+        )
+    }
+}
 
 impl ContractAbi for PokerAbi {
     type Operation = Operation;
@@ -47,4 +215,6 @@ impl ServiceAbi for PokerAbi {
 }
 
 /// Удобный реэкспорт состояния.
-pub use state::{HandEngineSnapshot, PokerState};
+pub use state::{
+    AutoDealConfig, HandEngineSnapshot, PokerState, TableStats, TournamentFinish,
+};
@@ -1,4 +1,5 @@
-use linera_sdk::linera_base_types::AccountOwner;
+use linera_sdk::linera_base_types::{AccountOwner, BlockHeight, ChainId, TimeDelta, Timestamp};
+use serde_json::Value as JsonValue;
 use thiserror::Error;
 
 use poker_engine::api::commands::{
@@ -29,6 +30,7 @@ use poker_engine::api::dto::{
 };
 
 use poker_engine::domain::blinds::{AnteType, BlindLevel};
+use poker_engine::domain::card::Card;
 use poker_engine::domain::chips::Chips;
 use poker_engine::domain::hand::Street;
 use poker_engine::domain::player::PlayerAtTable;
@@ -39,13 +41,60 @@ use poker_engine::domain::tournament::{
     TournamentError,
     TournamentStatus,
 };
-use poker_engine::domain::{PlayerId, SeatIndex, TableId, TournamentId};
+use poker_engine::domain::{HandId, PlayerId, SeatIndex, TableId, TournamentId};
+use poker_engine::engine::game_loop::HandEngine;
 use poker_engine::engine::{self, HandStatus};
 use poker_engine::engine::actions::{PlayerAction, PlayerActionKind};
 use poker_engine::infra::rng_seed::RngSeed;
 use poker_engine::time_ctrl::{AutoActionDecision, TimeController, TimeProfile};
 
-use crate::{HandEngineSnapshot, PokerState};
+use crate::commands::{
+    AcceptOwnershipCommand,
+    ActCommand,
+    CashOutCommand,
+    CreateSngTemplateCommand,
+    CreateTableWithRulesCommand,
+    CreditBankrollCommand,
+    DealVoteCommand,
+    GrantRoleCommand,
+    OnchainCommand,
+    OpenRemoteTableCommand,
+    ProposeDealCommand,
+    ProposeOwnerCommand,
+    RegisterPlayerCommand,
+    RemoveSngTemplateCommand,
+    RequestSeatCommand,
+    RevokeRoleCommand,
+    RotateAccountCommand,
+    ScheduleTournamentCommand,
+    SetAutoDealCommand,
+    SetDisplayNameCommand,
+    SetLevelDurationCommand,
+    SetSatelliteCommand,
+    SetTableChainsCommand,
+    SetTournamentPayoutsCommand,
+    StartSeasonCommand,
+    UpdateProfileCommand,
+};
+use crate::deals::{compute_deal, remaining_stacks, DealProposal, DealShare, LockedDeal};
+use crate::events::{EventRecord, PokerEvent};
+use crate::hand_for_hand::{on_bubble, HandForHand};
+use crate::hand_archive::{hole_cards, is_folded, is_sitting_out, ArchivedHand};
+use crate::identity::{normalize_name, validate_display_name};
+use crate::players::{PlayerProfile, PlayerStats};
+use crate::roles::{Role, RoleAssignment, RoleChange, RoleChangeKind, TableRoles, DEALER_ROLES};
+use crate::rules::{
+    check_bet_size, resolve_limit_amount, seats_from_button, BetSizeError, BettingRound,
+    TableRules,
+};
+use crate::satellites::{satellite_seats, SatelliteConfig, Ticket};
+use crate::schedule::{ScheduleState, ScheduleStep, TournamentSchedule};
+use crate::seasons::{tournament_points, ArchivedSeason, Season, SeasonEntry};
+use crate::seat_draw::{draw_seats, table_sizes, SeatDraw, SEAT_DRAW_SALT};
+use crate::sng::{draw_multiplier, validate_template, SngTemplate, StartEntropy, SPIN_SALT};
+use crate::showdown::{best_hand, rank_suit, split_pots, PotEntry, RankSuit};
+use crate::state::SeatClaim;
+use crate::{AutoDealConfig, HandEngineSnapshot, Message, PokerState, TournamentFinish};
 use std::collections::HashMap;
 
 /// Ошибки on-chain уровня (storage, авторизация, валидация команд, турнирные ошибки).
@@ -87,6 +136,12 @@ pub enum OnchainError {
     #[error("player id mismatch for this signer")]
     PlayerIdMismatch,
 
+    #[error("signer is not linked to any player")]
+    PlayerNotLinked,
+
+    #[error("not your turn: seat {seat} at table {table}")]
+    NotYourTurn { table: TableId, seat: SeatIndex },
+
     #[error("tournament error: {0}")]
     Tournament(#[from] TournamentError),
 
@@ -95,6 +150,122 @@ pub enum OnchainError {
 
     #[error("tournament not running: {0}")]
     TournamentNotRunning(TournamentId),
+
+    #[error("table {table} is hosted on chain {chain}")]
+    TableIsRemote { table: TableId, chain: ChainId },
+
+    #[error("unexpected message origin for table {0}")]
+    UnexpectedMessageOrigin(TableId),
+
+    #[error("only the coordinator chain {0} can open tables on other chains")]
+    NotCoordinatorChain(ChainId),
+
+    #[error("unexpected bankroll message origin for player {0}")]
+    UnexpectedBankrollOrigin(PlayerId),
+
+    #[error("player {player} already has a seat request for table {table}")]
+    SeatAlreadyClaimed { player: PlayerId, table: TableId },
+
+    #[error("cash out of {amount:?} exceeds recorded stack {stack:?} of player {player}")]
+    CashOutExceedsStack {
+        player: PlayerId,
+        amount: Chips,
+        stack: Chips,
+    },
+
+    #[error("table {0} is full")]
+    TableFull(TableId),
+
+    #[error("invalid profile: {0}")]
+    InvalidProfile(String),
+
+    #[error("invalid season: {0}")]
+    InvalidSeason(String),
+
+    #[error("missing role {0:?}")]
+    MissingRole(Role),
+
+    #[error("no pending ownership transfer for this account")]
+    NoPendingOwnership,
+
+    #[error("invalid display name: {0}")]
+    InvalidDisplayName(String),
+
+    #[error("display name {0:?} is already taken")]
+    DisplayNameTaken(String),
+
+    #[error("account is already linked to a player")]
+    AccountAlreadyLinked,
+
+    #[error("account was rotated away from player {0} and cannot be linked again")]
+    AccountRetired(PlayerId),
+
+    #[error("player {0} is already registered")]
+    PlayerAlreadyRegistered(PlayerId),
+
+    #[error("variant not supported: {0}")]
+    VariantNotSupported(String),
+
+    #[error("illegal bet on table {table}: {reason}")]
+    IllegalBet {
+        table: TableId,
+        reason: BetSizeError,
+    },
+
+    #[error("invalid table rules: {0}")]
+    InvalidTableRules(String),
+
+    #[error("invalid sit-and-go template: {0}")]
+    InvalidSngTemplate(String),
+
+    #[error("sit-and-go template {0} not found")]
+    SngTemplateNotFound(u64),
+
+    #[error("sit-and-go template {0} already exists")]
+    SngTemplateAlreadyExists(u64),
+
+    #[error("invalid tournament schedule: {0}")]
+    InvalidSchedule(String),
+
+    #[error("registration for tournament {0} is closed")]
+    RegistrationClosed(TournamentId),
+
+    #[error("invalid satellite: {0}")]
+    InvalidSatellite(String),
+
+    #[error("prize pool of tournament {0} overflows")]
+    PrizePoolOverflow(TournamentId),
+
+    #[error("invalid deal: {0}")]
+    InvalidDeal(String),
+
+    #[error("no deal proposed in tournament {0}")]
+    DealNotFound(TournamentId),
+
+    #[error("table {0} is waiting for other tables to finish the hand-for-hand round")]
+    HandForHandWait(TableId),
+
+    #[error("cannot seat tournament: {0}")]
+    InvalidTournamentSeating(String),
+
+    #[error("insufficient bankroll for player {player}: needed {needed:?}, available {available:?}")]
+    InsufficientBankroll {
+        player: PlayerId,
+        needed: Chips,
+        available: Chips,
+    },
+
+    #[error("table {0} is not a cash table")]
+    NotCashTable(TableId),
+
+    #[error("cash table {0} seats players only through a bankroll seat request")]
+    CashTableNeedsBuyIn(TableId),
+
+    #[error("buy-in must be positive")]
+    ZeroBuyIn,
+
+    #[error("player {0} is not registered with this signer")]
+    PlayerNotRegistered(PlayerId),
 }
 
 type OnchainResult<T> = Result<T, OnchainError>;
@@ -102,36 +273,122 @@ type OnchainResult<T> = Result<T, OnchainError>;
 pub struct PokerOrchestrator<'a> {
     pub state: &'a mut PokerState,
     pub signer: Option<AccountOwner>,
+    /// Текущее время цепи (runtime.system_time()).
+    pub now: Timestamp,
+    /// События за текущую операцию; контракт эмитит их после выполнения.
+    pub events: Vec<PokerEvent>,
+    /// Цепь, на которой выполняемся.
+    pub chain_id: ChainId,
+    /// Высота текущего блока (энтропия розыгрышей при старте турнира).
+    pub block_height: BlockHeight,
+    /// Исходящие cross-chain сообщения; контракт отправляет их после выполнения.
+    pub outgoing: Vec<(ChainId, Message)>,
+    /// Цепь-координатор: цепь, на которой создано приложение.
+    /// Только она пополняет банкроллы (`Message::Credit`).
+    pub coordinator: ChainId,
 }
 
 impl<'a> PokerOrchestrator<'a> {
-    pub fn new(state: &'a mut PokerState, signer: Option<AccountOwner>) -> Self {
-        Self { state, signer }
+    pub fn new(
+        state: &'a mut PokerState,
+        signer: Option<AccountOwner>,
+        now: Timestamp,
+        chain_id: ChainId,
+        coordinator: ChainId,
+        block_height: BlockHeight,
+    ) -> Self {
+        Self {
+            state,
+            signer,
+            now,
+            events: Vec::new(),
+            chain_id,
+            block_height,
+            outgoing: Vec::new(),
+            coordinator,
+        }
     }
 
     /// Главная точка входа: применить high-level команду.
-    /// Внутри работаем через Result, наружу всегда возвращаем CommandResponse.
-    pub async fn execute_command(&mut self, cmd: Command) -> CommandResponse {
-        let result: OnchainResult<CommandResponse> = match cmd {
+    /// Err контракт откатывает целиком и отвечает через `error_response`.
+    pub async fn execute_command(&mut self, cmd: Command) -> OnchainResult<CommandResponse> {
+        match cmd {
             Command::CreateTable(c) => self.handle_create_table(c).await,
             Command::TableCommand(tc) => self.handle_table_command(tc).await,
             Command::TournamentCommand(tc) => {
                 self.handle_tournament_command(tc).await
             }
-        };
+        }
+    }
 
-        match result {
-            Ok(resp) => resp,
-            Err(err) => self.error_response(err),
+    /// Точка входа для команд on-chain слоя (см. `crate::commands`).
+    pub async fn execute_onchain_command(
+        &mut self,
+        cmd: OnchainCommand,
+    ) -> OnchainResult<CommandResponse> {
+        match cmd {
+            OnchainCommand::SetAutoDeal(c) => self.handle_set_auto_deal(c).await,
+            OnchainCommand::Act(c) => self.handle_act(c).await,
+            OnchainCommand::OpenRemoteTable(c) => {
+                self.handle_open_remote_table(c).await
+            }
+            OnchainCommand::SetTableChains(c) => {
+                self.handle_set_table_chains(c).await
+            }
+            OnchainCommand::CreditBankroll(c) => {
+                self.handle_credit_bankroll(c).await
+            }
+            OnchainCommand::RequestSeat(c) => self.handle_request_seat(c).await,
+            OnchainCommand::CashOut(c) => self.handle_cash_out(c).await,
+            OnchainCommand::UpdateProfile(c) => self.handle_update_profile(c).await,
+            OnchainCommand::SetTournamentPayouts(c) => {
+                self.handle_set_tournament_payouts(c).await
+            }
+            OnchainCommand::StartSeason(c) => self.handle_start_season(c).await,
+            OnchainCommand::GrantRole(c) => self.handle_grant_role(c).await,
+            OnchainCommand::RevokeRole(c) => self.handle_revoke_role(c).await,
+            OnchainCommand::ProposeOwner(c) => self.handle_propose_owner(c).await,
+            OnchainCommand::AcceptOwnership(c) => {
+                self.handle_accept_ownership(c).await
+            }
+            OnchainCommand::RegisterPlayer(c) => self.handle_register_player(c).await,
+            OnchainCommand::SetDisplayName(c) => {
+                self.handle_set_display_name(c).await
+            }
+            OnchainCommand::RotateAccount(c) => self.handle_rotate_account(c).await,
+            OnchainCommand::CreateTableWithRules(c) => {
+                self.handle_create_table_with_rules(c).await
+            }
+            OnchainCommand::CreateSngTemplate(c) => {
+                self.handle_create_sng_template(c).await
+            }
+            OnchainCommand::RemoveSngTemplate(c) => {
+                self.handle_remove_sng_template(c).await
+            }
+            OnchainCommand::ScheduleTournament(c) => {
+                self.handle_schedule_tournament(c).await
+            }
+            OnchainCommand::SetSatellite(c) => self.handle_set_satellite(c).await,
+            OnchainCommand::ProposeDeal(c) => self.handle_propose_deal(c).await,
+            OnchainCommand::AcceptDeal(c) => self.handle_accept_deal(c).await,
+            OnchainCommand::RejectDeal(c) => self.handle_reject_deal(c).await,
+            OnchainCommand::SetLevelDuration(c) => {
+                self.handle_set_level_duration(c).await
+            }
         }
     }
 
     /// Преобразование OnchainError → CommandResponse.
     /// Пока отдаём "специальный" TableViewDto с сообщением об ошибке в name.
-    fn error_response(&self, err: OnchainError) -> CommandResponse {
-        let table = TableViewDto {
+    pub fn error_response(&self, err: OnchainError) -> CommandResponse {
+        CommandResponse::TableState(self.empty_table_view(&format!("ERROR: {err}")))
+    }
+
+    /// Пустой TableViewDto с сообщением в name — для ответов без стола.
+    fn empty_table_view(&self, message: &str) -> TableViewDto {
+        TableViewDto {
             table_id: 0,
-            name: format!("ERROR: {err}"),
+            name: message.to_string(),
             max_seats: 0,
             small_blind: Chips(0),
             big_blind: Chips(0),
@@ -143,9 +400,7 @@ impl<'a> PokerOrchestrator<'a> {
             players: Vec::new(),
             hand_in_progress: false,
             current_actor_seat: None,
-        };
-
-        CommandResponse::TableState(table)
+        }
     }
 
     // =====================================================================
@@ -156,7 +411,8 @@ impl<'a> PokerOrchestrator<'a> {
         *self.state.owner.get()
     }
 
-    async fn ensure_admin(&self) -> OnchainResult<()> {
+    /// Только владелец приложения.
+    async fn ensure_owner(&self) -> OnchainResult<AccountOwner> {
         let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
         let owner = self
             .app_owner()
@@ -167,9 +423,74 @@ impl<'a> PokerOrchestrator<'a> {
             return Err(OnchainError::Unauthorized);
         }
 
+        Ok(signer)
+    }
+
+    async fn ensure_admin(&self) -> OnchainResult<()> {
+        self.ensure_role(Role::Admin, None).await
+    }
+
+    /// Владелец или аккаунт с ролью `role` (admin покрывает все роли),
+    /// для ролей с областью — на столе `table_id`. На цепи стола, открытого
+    /// координатором, права проверяются по присланной им копии.
+    async fn ensure_role(
+        &self,
+        role: Role,
+        table_id: Option<TableId>,
+    ) -> OnchainResult<()> {
+        let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
+
+        if let Some(table_id) = table_id {
+            if self.coordinator_of(table_id).await?.is_some() {
+                let roles = self
+                    .state
+                    .table_roles
+                    .get(&table_id)
+                    .await
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?
+                    .unwrap_or_default();
+                if !roles.allows(signer, role, Some(table_id)) {
+                    return Err(OnchainError::MissingRole(role));
+                }
+                return Ok(());
+            }
+        }
+
+        if self.app_owner().await == Some(signer) {
+            return Ok(());
+        }
+
+        let granted = self
+            .state
+            .roles
+            .get(&signer)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default()
+            .iter()
+            .any(|a| a.grants(role, table_id));
+
+        if !granted {
+            return Err(OnchainError::MissingRole(role));
+        }
         Ok(())
     }
 
+    /// Старт раздач и тики: одна из ролей `DEALER_ROLES` (или владелец).
+    ///
+    /// До появления ролей StartHand и TickTable мог прислать любой
+    /// подписант. Теперь без роли они отклоняются, так что боту или
+    /// клиенту, который двигает таймеры, нужно выдать DealerBot.
+    async fn ensure_dealer(&self, table_id: TableId) -> OnchainResult<()> {
+        for role in DEALER_ROLES {
+            match self.ensure_role(role, Some(table_id)).await {
+                Err(OnchainError::MissingRole(_)) => continue,
+                other => return other,
+            }
+        }
+        Err(OnchainError::MissingRole(Role::DealerBot))
+    }
+
     /// Привязать signer ↔ player_id (один раз) и проверять соответствие.
     async fn ensure_player_for_signer(
         &mut self,
@@ -189,6 +510,20 @@ impl<'a> PokerOrchestrator<'a> {
             }
             Ok(existing)
         } else {
+            // player_id уже закреплён за другим аккаунтом.
+            if self
+                .state
+                .player_accounts
+                .get(&player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+                .is_some_and(|owner| owner != signer)
+            {
+                return Err(OnchainError::PlayerIdMismatch);
+            }
+
+            self.ensure_account_not_retired(signer).await?;
+
             // Первая привязка.
             self.state
                 .account_players
@@ -204,6 +539,60 @@ impl<'a> PokerOrchestrator<'a> {
         }
     }
 
+    /// Аккаунт, выведенный ротацией, больше ни к кому не привязывается.
+    async fn ensure_account_not_retired(&self, account: AccountOwner) -> OnchainResult<()> {
+        match self
+            .state
+            .retired_accounts
+            .get(&account)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            Some(player_id) => Err(OnchainError::AccountRetired(player_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// player_id, привязанный к текущему подписанту.
+    async fn signer_player_id(&self) -> OnchainResult<PlayerId> {
+        let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
+
+        self.state
+            .account_players
+            .get(&signer)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .ok_or(OnchainError::PlayerNotLinked)
+    }
+
+    /// Действовать может только подписант, чей игрок сидит на месте current_actor.
+    async fn ensure_signer_is_actor(
+        &self,
+        table: &Table,
+        snapshot: &HandEngineSnapshot,
+        action: &PlayerAction,
+    ) -> OnchainResult<()> {
+        let player_id = self.signer_player_id().await?;
+        if player_id != action.player_id {
+            return Err(OnchainError::PlayerIdMismatch);
+        }
+
+        let seated = table
+            .seats
+            .get(action.seat as usize)
+            .and_then(|s| s.as_ref())
+            .is_some_and(|p| p.player_id == player_id);
+
+        if !seated || snapshot.current_actor != Some(action.seat) {
+            return Err(OnchainError::NotYourTurn {
+                table: table.id,
+                seat: action.seat,
+            });
+        }
+
+        Ok(())
+    }
+
     // =====================================================================
     //                           CASH / TABLE COMMANDS
     // =====================================================================
@@ -212,8 +601,8 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: CreateTableCommand,
     ) -> OnchainResult<CommandResponse> {
-        // Admin-only.
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TableManager, Some(cmd.table_id))
+            .await?;
 
         if self
             .state
@@ -229,31 +618,17 @@ impl<'a> PokerOrchestrator<'a> {
             )));
         }
 
-        let stakes = TableStakes::new(
-            cmd.small_blind,
-            cmd.big_blind,
-            map_ante_type(cmd.ante_type),
-            cmd.ante,
-        );
-
-        let config = TableConfig {
-            max_seats: cmd.max_seats,
-            table_type: TableType::Cash,
-            stakes,
-            allow_straddle: false,
-            allow_run_it_twice: false,
-        };
-
-        let table = Table::new(cmd.table_id, cmd.name.clone(), config);
+        let table_id = cmd.table_id;
+        let table = build_cash_table(cmd);
 
         self.state
             .tables
-            .insert(&cmd.table_id, table.clone())
+            .insert(&table_id, table.clone())
             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
         self.state
             .active_hands
-            .insert(&cmd.table_id, None)
+            .insert(&table_id, None)
             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
         let table_view = self.build_table_view(&table, None).await?;
@@ -282,9 +657,15 @@ impl<'a> PokerOrchestrator<'a> {
         // Привязываем signer ↔ player_id.
         let player_id = self.ensure_player_for_signer(cmd.player_id).await?;
 
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
         let seat: SeatIndex = cmd.seat_index as SeatIndex;
 
+        // Фишки cash-стола приходят только из банкролла (RequestSeat).
+        if table.config.table_type == TableType::Cash {
+            return Err(OnchainError::CashTableNeedsBuyIn(table.id));
+        }
+
         if !table.is_seat_empty(seat) {
             return Err(OnchainError::SeatNotEmpty {
                 table: table.id,
@@ -304,14 +685,19 @@ impl<'a> PokerOrchestrator<'a> {
         }
 
         if !cmd.display_name.is_empty() {
-            self.state
-                .player_names
-                .insert(&player_id, cmd.display_name.clone())
-                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            self.claim_display_name(player_id, &cmd.display_name).await?;
         }
 
         self.save_table(table.clone())?;
 
+        self.emit(PokerEvent::PlayerSeated {
+            table_id: table.id,
+            seat,
+            player_id,
+        });
+
+        self.report_stacks(&table).await?;
+
         let active_snapshot = self.load_active_snapshot(table.id).await?;
         let table_view = self
             .build_table_view(&table, active_snapshot.as_ref())
@@ -324,11 +710,44 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: UnseatPlayerCommand,
     ) -> OnchainResult<CommandResponse> {
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
         let seat: SeatIndex = cmd.seat_index as SeatIndex;
 
+        // Встать может сам игрок, снять чужого — только менеджер стола.
+        let seated = table
+            .seats
+            .get(seat as usize)
+            .and_then(|s| s.as_ref())
+            .map(|p| p.player_id);
+        let own_seat = seated.is_some() && self.signer_player_id().await.ok() == seated;
+        if !own_seat {
+            self.ensure_role(Role::TableManager, Some(table.id)).await?;
+        }
+
+        let cash_table = table.config.table_type == TableType::Cash;
         if let Some(slot) = table.seats.get_mut(seat as usize) {
-            *slot = None;
+            // Игрок пришёл со своей цепи — стек возвращаем в его банкролл.
+            // Турнирные фишки в банкролл не превращаются.
+            if let Some(p) = slot.take().filter(|_| cash_table) {
+                if let Some(chain) = self
+                    .state
+                    .player_chains
+                    .get(&p.player_id)
+                    .await
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?
+                {
+                    // И с пустым стеком: CashOut закрывает место на цепи игрока.
+                    self.outgoing.push((
+                        chain,
+                        Message::CashOut {
+                            table_id: table.id,
+                            player_id: p.player_id,
+                            amount: p.stack,
+                        },
+                    ));
+                }
+            }
         } else {
             return Err(OnchainError::InvalidSeatIndex {
                 table: table.id,
@@ -338,6 +757,8 @@ impl<'a> PokerOrchestrator<'a> {
 
         self.save_table(table.clone())?;
 
+        self.report_stacks(&table).await?;
+
         let active_snapshot = self.load_active_snapshot(table.id).await?;
         let table_view = self
             .build_table_view(&table, active_snapshot.as_ref())
@@ -350,19 +771,25 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: AdjustStackCommand,
     ) -> OnchainResult<CommandResponse> {
-        // Admin-only.
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TableManager, Some(cmd.table_id))
+            .await?;
 
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
         let seat: SeatIndex = cmd.seat_index as SeatIndex;
 
+        // Стек за cash-столом обеспечен банкроллом — вручную не меняется.
+        if table.config.table_type == TableType::Cash {
+            return Err(OnchainError::CashTableNeedsBuyIn(table.id));
+        }
+
         let delta = cmd.delta;
 
         if let Some(Some(player)) = table.seats.get_mut(seat as usize) {
             if delta >= 0 {
                 player.stack += Chips(delta as u64);
             } else {
-                let abs = (-delta) as u64;
+                let abs = delta.unsigned_abs();
                 if player.stack.0 >= abs {
                     player.stack -= Chips(abs);
                 } else {
@@ -378,6 +805,8 @@ impl<'a> PokerOrchestrator<'a> {
 
         self.save_table(table.clone())?;
 
+        self.report_stacks(&table).await?;
+
         let active_snapshot = self.load_active_snapshot(table.id).await?;
         let table_view = self
             .build_table_view(&table, active_snapshot.as_ref())
@@ -390,12 +819,34 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: StartHandCommand,
     ) -> OnchainResult<CommandResponse> {
+        self.ensure_dealer(cmd.table_id).await?;
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
 
         if table.hand_in_progress {
             return Err(OnchainError::HandAlreadyInProgress(table.id));
         }
+        if self.waits_hand_for_hand(table.id).await? {
+            return Err(OnchainError::HandForHandWait(table.id));
+        }
+
+        let snapshot = self.start_hand_on_table(&mut table).await?;
+
+        let table_view = self
+            .build_table_view(&table, Some(&snapshot))
+            .await?;
+
+        Ok(CommandResponse::TableState(table_view))
+    }
 
+    /// Общий старт раздачи: ручной (StartHand) и авто-дилер.
+    ///
+    /// hand_id берётся из глобального счётчика `next_hand_id`,
+    /// кнопку двигает сам движок в `engine::start_hand`.
+    async fn start_hand_on_table(
+        &mut self,
+        table: &mut Table,
+    ) -> OnchainResult<HandEngineSnapshot> {
         // Берём hand_id из глобального счётчика.
         let current_id = *self.state.next_hand_id.get();
         let hand_id = current_id.saturating_add(1);
@@ -405,11 +856,36 @@ impl<'a> PokerOrchestrator<'a> {
         let seed = RngSeed::from_u64(base_seed ^ hand_id ^ table.id as u64);
         let mut rng = seed.to_rng();
 
+        // Запись для архива — стеки берём до блайндов/анте.
+        let mut log = self.begin_hand_log(hand_id, table).await?;
+
         let mut engine =
-            engine::start_hand(&mut table, &mut rng, hand_id).map_err(|e| {
+            engine::start_hand(table, &mut rng, hand_id).map_err(|e| {
                 OnchainError::EngineError(format!("start_hand failed: {e:?}"))
             })?;
 
+        // Движок раздаёт по две карты из полной колоды — остальное по
+        // правилам стола доделываем сами.
+        let rules = self.load_table_rules(table.id).await?;
+        if rules.variant.min_rank() > 2 {
+            strip_low_cards(table, &mut engine, rules.variant.min_rank())?;
+        }
+        let extra = rules.variant.hole_cards().saturating_sub(2);
+        if extra > 0 {
+            deal_extra_hole_cards(table, &mut engine, extra)?;
+        }
+        if let Some(ante) = rules.button_ante {
+            post_button_ante(table, &mut engine, ante)?;
+        }
+
+        log.dealer_button = table.dealer_button;
+        log.variant = rules.variant;
+        log.betting = rules.betting();
+        self.state
+            .hand_logs
+            .insert(&table.id, log)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
         let total = *self.state.total_hands_played.get();
         self.state.total_hands_played
             .set(total.saturating_add(1));
@@ -423,20 +899,23 @@ impl<'a> PokerOrchestrator<'a> {
         self.save_table(table.clone())?;
 
         // Тайм-контроль: инициализируем или обновляем контроллер под первого актёра.
-        self.update_time_controller_for_actor(&table, engine.current_actor)
+        self.update_time_controller_for_actor(table, engine.current_actor)
             .await?;
 
-        let table_view = self
-            .build_table_view(&table, Some(&snapshot))
-            .await?;
+        self.emit(PokerEvent::HandStarted {
+            table_id: table.id,
+            hand_id,
+            dealer_button: table.dealer_button,
+        });
 
-        Ok(CommandResponse::TableState(table_view))
+        Ok(snapshot)
     }
 
     async fn handle_player_action(
         &mut self,
-        cmd: PlayerActionCommand,
+        mut cmd: PlayerActionCommand,
     ) -> OnchainResult<CommandResponse> {
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
 
         let snapshot_opt = self
@@ -444,7 +923,15 @@ impl<'a> PokerOrchestrator<'a> {
             .await?;
         let snapshot = snapshot_opt.ok_or(OnchainError::NoActiveHand(cmd.table_id))?;
 
+        self.ensure_signer_is_actor(&table, &snapshot, &cmd.action)
+            .await?;
+        cmd.action.kind = self
+            .check_bet_limits(&table, &snapshot, &cmd.action)
+            .await?;
+
         let mut engine = snapshot.into_engine();
+        let street = table.street;
+        let board_before = table.board.len();
 
         let mut status =
             engine::apply_action(&mut table, &mut engine, cmd.action.clone())
@@ -454,10 +941,29 @@ impl<'a> PokerOrchestrator<'a> {
                     ))
                 })?;
 
+        self.record_hand_action(table.id, street, &cmd.action, false)
+            .await?;
+
+        self.emit(PokerEvent::ActionTaken {
+            table_id: table.id,
+            hand_id: engine.hand_id,
+            seat: cmd.action.seat,
+            player_id: cmd.action.player_id,
+            kind: cmd.action.kind.clone(),
+            auto: false,
+        });
+
         if let Ok(next_status) = engine::advance_if_needed(&mut table, &mut engine) {
             status = next_status;
         }
 
+        self.emit_street_if_dealt(&table, engine.hand_id, board_before);
+
+        if !matches!(status, HandStatus::Ongoing) {
+            self.settle_variant_showdown(&mut table, &engine.contributions)
+                .await?;
+        }
+
         let snapshot_after = HandEngineSnapshot::from_engine(&engine);
         self.save_table(table.clone())?;
 
@@ -483,6 +989,10 @@ impl<'a> PokerOrchestrator<'a> {
                     .insert(&table.id, None)
                     .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
+                self.archive_finished_hand(&table, &snapshot_after).await?;
+                self.report_hand_to_coordinator(&table, &snapshot_after)
+                    .await?;
+
                 // Сбрасываем текущий ход, но не обнуляем таймбанк.
                 if let Err(e) = self.clear_current_turn_for_table(table.id).await {
                     // Не ломаем игру, если что-то пошло не так с таймбанком.
@@ -493,39 +1003,66 @@ impl<'a> PokerOrchestrator<'a> {
                 if let Some(tournament_id) =
                     self.table_tournament_id(table.id).await?
                 {
-                    self.handle_tournament_after_hand(
-                        tournament_id,
-                        &table,
-                    )
-                    .await?;
+                    self.run_tournament_hook(tournament_id, &table)
+                        .await?;
                 }
 
                 let table_view = self
                     .build_table_view(&table, Some(&snapshot_after))
                     .await?;
-                map_hand_status_to_response(finished_status, table_view)
+                let response =
+                    map_hand_status_to_response(finished_status, table_view);
+
+                // Авто-дилер: планируем (или сразу стартуем) следующую раздачу.
+                self.schedule_next_hand(table.id).await?;
+
+                response
             }
         };
 
         Ok(response)
     }
 
+    /// Действие от имени подписанта: сами находим его игрока и место,
+    /// дальше — обычный путь `handle_player_action` (с проверкой хода).
+    async fn handle_act(&mut self, cmd: ActCommand) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        let seat = self.find_seat_by_player(cmd.table_id, player_id).await?;
+
+        let action = PlayerAction {
+            seat,
+            player_id,
+            kind: cmd.kind,
+        };
+
+        self.handle_player_action(PlayerActionCommand {
+            table_id: cmd.table_id,
+            action,
+        })
+        .await
+    }
+
     /// Tick-команда для тайм-контроля (ЭТАП 7):
     /// - двигаем часы;
     /// - если произошёл timeout — делаем auto-fold от имени игрока;
+    /// - если раздачи нет и включён авто-дилер — стартуем следующую;
     /// - возвращаем актуальное состояние стола.
     async fn handle_tick_table(
         &mut self,
         cmd: TickTableCommand,
     ) -> OnchainResult<CommandResponse> {
+        self.ensure_dealer(cmd.table_id).await?;
+        self.ensure_local_table(cmd.table_id).await?;
         let mut table = self.load_table(cmd.table_id).await?;
 
         let snapshot_opt = self.load_active_snapshot(cmd.table_id).await?;
         let snapshot = match snapshot_opt {
             Some(s) => s,
             None => {
-                // Нет активной раздачи — просто вернуть состояние стола.
-                let table_view = self.build_table_view(&table, None).await?;
+                // Нет активной раздачи — возможно, пора стартовать авто-раздачу.
+                let started = self.try_auto_deal(&mut table).await?;
+                let table_view =
+                    self.build_table_view(&table, started.as_ref()).await?;
                 return Ok(CommandResponse::TableState(table_view));
             }
         };
@@ -561,20 +1098,42 @@ impl<'a> PokerOrchestrator<'a> {
                     kind: PlayerActionKind::Fold,
                 };
 
+                let street = table.street;
+                let board_before = table.board.len();
+
                 let mut status =
-                    engine::apply_action(&mut table, &mut engine, action)
+                    engine::apply_action(&mut table, &mut engine, action.clone())
                         .map_err(|e| {
                             OnchainError::EngineError(format!(
                                 "auto-fold failed: {e:?}"
                             ))
                         })?;
 
+                self.record_hand_action(table.id, street, &action, true)
+                    .await?;
+
+                self.emit(PokerEvent::ActionTaken {
+                    table_id: table.id,
+                    hand_id: engine.hand_id,
+                    seat,
+                    player_id,
+                    kind: PlayerActionKind::Fold,
+                    auto: true,
+                });
+
                 if let Ok(next_status) =
                     engine::advance_if_needed(&mut table, &mut engine)
                 {
                     status = next_status;
                 }
 
+                self.emit_street_if_dealt(&table, engine.hand_id, board_before);
+
+                if !matches!(status, HandStatus::Ongoing) {
+                    self.settle_variant_showdown(&mut table, &engine.contributions)
+                        .await?;
+                }
+
                 let snapshot_after = HandEngineSnapshot::from_engine(&engine);
                 self.save_table(table.clone())?;
 
@@ -603,35 +1162,3519 @@ impl<'a> PokerOrchestrator<'a> {
                             .insert(&table.id, None)
                             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
-                        // Сбрасываем current_turn в тайм-контроллере.
-                        if let Err(e) =
-                            self.clear_current_turn_for_table(table.id).await
-                        {
-                            eprintln!("time controller clear error: {e:?}");
-                        }
+                        self.archive_finished_hand(&table, &snapshot_after)
+                            .await?;
+                        self.report_hand_to_coordinator(&table, &snapshot_after)
+                            .await?;
+
+                        // Сбрасываем current_turn в тайм-контроллере.
+                        if let Err(e) =
+                            self.clear_current_turn_for_table(table.id).await
+                        {
+                            eprintln!("time controller clear error: {e:?}");
+                        }
+
+                        if let Some(tournament_id) =
+                            self.table_tournament_id(table.id).await?
+                        {
+                            self.run_tournament_hook(tournament_id, &table)
+                                .await?;
+                        }
+
+                        let table_view = self
+                            .build_table_view(&table, Some(&snapshot_after))
+                            .await?;
+                        let response = map_hand_status_to_response(
+                            finished_status,
+                            table_view,
+                        );
+
+                        self.schedule_next_hand(table.id).await?;
+
+                        response
+                    }
+                };
+
+                Ok(response)
+            }
+        }
+    }
+
+    // =====================================================================
+    //                        AUTO-DEAL (ДИЛЕР-БОТ)
+    // =====================================================================
+
+    async fn handle_set_auto_deal(
+        &mut self,
+        cmd: SetAutoDealCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TableManager, Some(cmd.table_id))
+            .await?;
+
+        self.ensure_local_table(cmd.table_id).await?;
+        let mut table = self.load_table(cmd.table_id).await?;
+
+        let cfg = AutoDealConfig {
+            enabled: cmd.enabled,
+            delay_secs: cmd.delay_secs,
+            next_hand_at: None,
+        };
+        self.save_auto_deal(table.id, cfg)?;
+
+        // Если раздачи нет — сразу пробуем запустить цикл.
+        let mut active = self.load_active_snapshot(table.id).await?;
+        if active.is_none() {
+            active = self.try_auto_deal(&mut table).await?;
+        }
+
+        let table_view = self.build_table_view(&table, active.as_ref()).await?;
+        Ok(CommandResponse::TableState(table_view))
+    }
+
+    /// Вызывается после завершения раздачи: ставим время следующей
+    /// и, если пауза нулевая, сразу её стартуем.
+    async fn schedule_next_hand(&mut self, table_id: TableId) -> OnchainResult<()> {
+        let Some(mut cfg) = self.load_auto_deal(table_id).await? else {
+            return Ok(());
+        };
+        if !cfg.enabled {
+            return Ok(());
+        }
+
+        // Турнирный хук мог убрать стол (ребалансировка) — тогда выходим.
+        let Some(mut table) = self
+            .state
+            .tables
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        cfg.next_hand_at = Some(next_auto_deal_time(self.now, cfg.delay_secs));
+        self.save_auto_deal(table_id, cfg)?;
+
+        self.try_auto_deal(&mut table).await?;
+        Ok(())
+    }
+
+    /// Стартовать авто-раздачу, если она включена и её время пришло.
+    ///
+    /// Возвращает снапшот новой раздачи, если она стартовала.
+    /// Меньше двух игроков с фишками не в sit-out — пауза (`next_hand_at = None`),
+    /// как только игроки появятся, отсчёт паузы начнётся заново.
+    async fn try_auto_deal(
+        &mut self,
+        table: &mut Table,
+    ) -> OnchainResult<Option<HandEngineSnapshot>> {
+        let Some(mut cfg) = self.load_auto_deal(table.id).await? else {
+            return Ok(None);
+        };
+        if !cfg.enabled || table.hand_in_progress {
+            return Ok(None);
+        }
+        if self.waits_hand_for_hand(table.id).await? {
+            return Ok(None);
+        }
+
+        let scheduled = cfg.next_hand_at;
+        let due = auto_deal_due(&mut cfg, table, self.now);
+        if cfg.next_hand_at != scheduled {
+            self.save_auto_deal(table.id, cfg)?;
+        }
+        if !due {
+            return Ok(None);
+        }
+
+        let snapshot = self.start_hand_on_table(table).await?;
+        Ok(Some(snapshot))
+    }
+
+    async fn load_auto_deal(
+        &self,
+        table_id: TableId,
+    ) -> OnchainResult<Option<AutoDealConfig>> {
+        self.state
+            .auto_deal
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    fn save_auto_deal(
+        &mut self,
+        table_id: TableId,
+        cfg: AutoDealConfig,
+    ) -> OnchainResult<()> {
+        self.state
+            .auto_deal
+            .insert(&table_id, cfg)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                          HAND HISTORY ARCHIVE
+    // =====================================================================
+
+    /// Завести запись новой раздачи (до `engine::start_hand`).
+    async fn begin_hand_log(
+        &self,
+        hand_id: HandId,
+        table: &Table,
+    ) -> OnchainResult<ArchivedHand> {
+        let mut names = Vec::new();
+        for (idx, seat_opt) in table.seats.iter().enumerate() {
+            if let Some(p) = seat_opt {
+                if let Some(name) = self
+                    .state
+                    .player_names
+                    .get(&p.player_id)
+                    .await
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?
+                {
+                    names.push((idx as SeatIndex, name));
+                }
+            }
+        }
+
+        let tournament_id = self.table_tournament_id(table.id).await?;
+
+        Ok(ArchivedHand::begin(
+            hand_id,
+            table,
+            tournament_id,
+            names,
+            self.now,
+        ))
+    }
+
+    /// Дописать действие в запись текущей раздачи стола.
+    async fn record_hand_action(
+        &mut self,
+        table_id: TableId,
+        street: Street,
+        action: &PlayerAction,
+        auto: bool,
+    ) -> OnchainResult<()> {
+        let Some(mut log) = self
+            .state
+            .hand_logs
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            // Раздача стартовала до появления архива — просто не пишем.
+            return Ok(());
+        };
+
+        log.record_action(
+            street,
+            action.seat,
+            action.player_id,
+            action.kind.clone(),
+            auto,
+        );
+
+        self.state
+            .hand_logs
+            .insert(&table_id, log)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Раздача завершилась: закрываем запись, кладём в архив + индексы
+    /// и публикуем HandFinished.
+    async fn archive_finished_hand(
+        &mut self,
+        table: &Table,
+        snapshot: &HandEngineSnapshot,
+    ) -> OnchainResult<()> {
+        self.record_table_stats(table.id, hand_pot(snapshot)).await?;
+
+        let Some(mut log) = self
+            .state
+            .hand_logs
+            .get(&table.id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        self.state
+            .hand_logs
+            .remove(&table.id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        log.finish(table, snapshot, self.now);
+        let hand_id = log.hand_id;
+
+        self.emit(PokerEvent::HandFinished {
+            table_id: table.id,
+            hand_id,
+            winners: log
+                .winners
+                .iter()
+                .map(|w| (w.player_id, w.amount))
+                .collect(),
+        });
+
+        let position = self
+            .state
+            .table_hand_counts
+            .get(&table.id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or(0);
+        self.state
+            .table_hands
+            .insert(&(table.id, position), hand_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .table_hand_counts
+            .insert(&table.id, position + 1)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        for seat in &log.seats {
+            let position = self
+                .state
+                .player_hand_counts
+                .get(&seat.player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+                .unwrap_or(0);
+            self.state
+                .player_hands
+                .insert(&(seat.player_id, position), hand_id)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            self.state
+                .player_hand_counts
+                .insert(&seat.player_id, position + 1)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        for seat in &log.seats {
+            let mut stats = self.load_player_stats(seat.player_id).await?;
+            stats.record_hand(&log, seat);
+            self.save_player_stats(seat.player_id, stats)?;
+
+            let net = if log.tournament_id.is_none() {
+                seat.final_stack.0 as i64 - seat.starting_stack.0 as i64
+            } else {
+                0
+            };
+            self.update_season_entry(seat.player_id, |e| {
+                e.hands_played += 1;
+                e.net_winnings += net;
+            })
+            .await?;
+        }
+
+        self.state
+            .hand_archive
+            .insert(&hand_id, log)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Статистика стола для лобби: число раздач и суммарный банк.
+    async fn record_table_stats(
+        &mut self,
+        table_id: TableId,
+        pot: Chips,
+    ) -> OnchainResult<()> {
+        let mut stats = self
+            .state
+            .table_stats
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+        stats.record_hand(pot);
+        self.state
+            .table_stats
+            .insert(&table_id, stats)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                  MULTI-CHAIN: ШАРДИНГ СТОЛОВ ПО ЦЕПЯМ
+    // =====================================================================
+
+    async fn handle_open_remote_table(
+        &mut self,
+        cmd: OpenRemoteTableCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_admin().await?;
+        self.ensure_coordinator_chain()?;
+        cmd.rules
+            .validate()
+            .map_err(OnchainError::InvalidTableRules)?;
+
+        let table_id = cmd.table.table_id;
+        if self
+            .state
+            .tables
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .is_some()
+        {
+            return Err(OnchainError::Storage(format!(
+                "table {} already exists",
+                table_id
+            )));
+        }
+
+        let table = build_cash_table(cmd.table);
+
+        // Зеркало стола на координаторе — для лобби и отчётов.
+        self.state
+            .tables
+            .insert(&table_id, table.clone())
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .active_hands
+            .insert(&table_id, None)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        self.save_table_rules(table_id, cmd.rules.clone())?;
+        self.open_table_on_chain(cmd.chain_id, table.clone(), None, cmd.rules)
+            .await?;
+
+        let table_view = self.build_table_view(&table, None).await?;
+        Ok(CommandResponse::TableCreated(table_view))
+    }
+
+    async fn handle_set_table_chains(
+        &mut self,
+        cmd: SetTableChainsCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_admin().await?;
+        self.ensure_coordinator_chain()?;
+
+        self.state.table_chain_pool.set(cmd.chains);
+
+        let table_view = self.empty_table_view("table chains updated");
+        Ok(CommandResponse::TableState(table_view))
+    }
+
+    /// Столы на других цепях открывает только координатор:
+    /// чужой OpenTable цепь стола отклонит.
+    fn ensure_coordinator_chain(&self) -> OnchainResult<()> {
+        if self.chain_id != self.coordinator {
+            return Err(OnchainError::NotCoordinatorChain(self.coordinator));
+        }
+        Ok(())
+    }
+
+    /// Координатор: записать, где живёт стол, и отправить ему OpenTable.
+    async fn open_table_on_chain(
+        &mut self,
+        chain_id: ChainId,
+        table: Table,
+        tournament_id: Option<TournamentId>,
+        rules: TableRules,
+    ) -> OnchainResult<()> {
+        self.state
+            .table_chains
+            .insert(&table.id, chain_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let roles = self.table_roles_for(table.id).await?;
+        self.outgoing.push((
+            chain_id,
+            Message::OpenTable {
+                table,
+                tournament_id,
+                rules,
+                roles,
+            },
+        ));
+        Ok(())
+    }
+
+    /// Цепь, на которой живёт стол (None — на этой).
+    async fn remote_chain_of(
+        &self,
+        table_id: TableId,
+    ) -> OnchainResult<Option<ChainId>> {
+        self.state
+            .table_chains
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Играть можно только за столом, который живёт на этой цепи.
+    async fn ensure_local_table(&self, table_id: TableId) -> OnchainResult<()> {
+        match self.remote_chain_of(table_id).await? {
+            Some(chain) => Err(OnchainError::TableIsRemote {
+                table: table_id,
+                chain,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Координатор этого стола (если стол открыт по сообщению).
+    async fn coordinator_of(
+        &self,
+        table_id: TableId,
+    ) -> OnchainResult<Option<ChainId>> {
+        self.state
+            .table_coordinators
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Цепь стола: после раздачи сообщить стеки цепям игроков и
+    /// координатору, затем выполнить отложенные cash-out'ы и рассадку.
+    async fn report_hand_to_coordinator(
+        &mut self,
+        table: &Table,
+        snapshot: &HandEngineSnapshot,
+    ) -> OnchainResult<()> {
+        self.report_stacks_to_players(table).await?;
+
+        if let Some(coordinator) = self.coordinator_of(table.id).await? {
+            let stacks = seat_stacks(table);
+            let busted = stacks
+                .iter()
+                .filter(|(_, _, stack)| stack.is_zero())
+                .map(|(_, pid, _)| *pid)
+                .collect();
+
+            self.outgoing.push((
+                coordinator,
+                Message::HandFinished {
+                    table_id: table.id,
+                    hand_id: snapshot.hand_id,
+                    stacks,
+                    total_pot: hand_pot(snapshot),
+                    busted,
+                },
+            ));
+        }
+
+        self.apply_pending_cash_outs(table.id).await?;
+        self.apply_pending_seats(table.id).await
+    }
+
+    /// Цепь стола: сообщить стеки после изменений вне раздачи
+    /// координатору и цепям игроков.
+    async fn report_stacks(&mut self, table: &Table) -> OnchainResult<()> {
+        self.report_stacks_to_players(table).await?;
+        if let Some(coordinator) = self.coordinator_of(table.id).await? {
+            self.outgoing.push((
+                coordinator,
+                Message::StacksUpdated {
+                    table_id: table.id,
+                    stacks: seat_stacks(table),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    /// Цепь стола: игрокам, пришедшим со своих цепей, — их текущий стек.
+    async fn report_stacks_to_players(&mut self, table: &Table) -> OnchainResult<()> {
+        if table.config.table_type != TableType::Cash {
+            return Ok(());
+        }
+        for (_, player_id, stack) in seat_stacks(table) {
+            let Some(chain) = self
+                .state
+                .player_chains
+                .get(&player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+            else {
+                continue;
+            };
+            self.outgoing.push((
+                chain,
+                Message::SeatStack {
+                    table_id: table.id,
+                    player_id,
+                    stack,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn apply_pending_seats(&mut self, table_id: TableId) -> OnchainResult<()> {
+        let Some(seats) = self
+            .state
+            .pending_seats
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        self.state
+            .pending_seats
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        // Рассадка пришла до раздачи: стеки в ней устарели.
+        let mut table = self.load_table(table_id).await?;
+        table.seats = merge_seats(&table.seats, seats);
+        self.save_table(table)
+    }
+
+    /// Точка входа для cross-chain сообщений.
+    pub async fn execute_message(
+        &mut self,
+        origin: ChainId,
+        is_bouncing: bool,
+        message: Message,
+    ) -> OnchainResult<()> {
+        if is_bouncing {
+            return self.on_bounced_message(message).await;
+        }
+
+        match message {
+            Message::OpenTable {
+                table,
+                tournament_id: _,
+                rules,
+                roles,
+            } => self.on_open_table(origin, table, rules, roles).await,
+            Message::SyncRoles { table_id, roles } => {
+                self.ensure_from_coordinator(origin, table_id).await?;
+                self.state
+                    .table_roles
+                    .insert(&table_id, roles)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))
+            }
+            Message::SyncSeats { table_id, seats } => {
+                self.on_sync_seats(origin, table_id, seats).await
+            }
+            Message::UpdateStakes { table_id, stakes } => {
+                self.ensure_from_coordinator(origin, table_id).await?;
+                let mut table = self.load_table(table_id).await?;
+                table.config.stakes = stakes;
+                self.save_table(table)
+            }
+            Message::CloseTable { table_id } => {
+                self.ensure_from_coordinator(origin, table_id).await?;
+                self.remove_table_everywhere(table_id)?;
+                self.state
+                    .table_roles
+                    .remove(&table_id)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+                self.state
+                    .table_coordinators
+                    .remove(&table_id)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))
+            }
+            Message::HandFinished {
+                table_id,
+                hand_id: _,
+                stacks,
+                total_pot,
+                busted: _,
+            } => {
+                self.on_remote_hand_finished(origin, table_id, stacks, total_pot)
+                    .await
+            }
+            Message::StacksUpdated { table_id, stacks } => {
+                self.ensure_from_table_chain(origin, table_id).await?;
+                self.update_mirror_stacks(table_id, &stacks).await?;
+                Ok(())
+            }
+            Message::SeatRequest {
+                table_id,
+                player_id,
+                seat_index,
+                buy_in,
+                display_name,
+            } => {
+                self.on_seat_request(
+                    origin,
+                    table_id,
+                    player_id,
+                    seat_index,
+                    buy_in,
+                    display_name,
+                )
+                .await
+            }
+            Message::SeatForwarded {
+                table_id,
+                player_id,
+                player_chain,
+                seat_index,
+                buy_in,
+                display_name,
+            } => {
+                self.ensure_from_coordinator(origin, table_id).await?;
+                self.seat_from_player_chain(
+                    player_chain,
+                    table_id,
+                    player_id,
+                    seat_index,
+                    buy_in,
+                    display_name,
+                )
+                .await
+            }
+            Message::RegisterPlayer {
+                player_id,
+                display_name,
+            } => {
+                self.ensure_coordinator_chain()?;
+                self.handle_register_player(RegisterPlayerCommand {
+                    player_id,
+                    display_name,
+                })
+                .await
+                .map(|_| ())
+            }
+            Message::RotateAccount {
+                player_id,
+                new_account,
+            } => {
+                self.ensure_coordinator_chain()?;
+                self.handle_rotate_account(RotateAccountCommand {
+                    player_id,
+                    new_account,
+                })
+                .await
+                .map(|_| ())
+            }
+            Message::CashOutRequest {
+                table_id,
+                player_id,
+            } => self.on_cash_out_request(origin, table_id, player_id).await,
+            Message::Credit { player_id, amount } => {
+                if origin != self.coordinator {
+                    return Err(OnchainError::UnexpectedBankrollOrigin(player_id));
+                }
+                self.credit_bankroll(player_id, amount).await
+            }
+            Message::SeatRejected {
+                table_id,
+                player_id,
+                refund,
+                reason: _,
+            } => {
+                self.close_seat_claim(origin, player_id, table_id, refund)
+                    .await
+            }
+            Message::CashOut {
+                table_id,
+                player_id,
+                amount,
+            } => {
+                self.close_seat_claim(origin, player_id, table_id, amount)
+                    .await
+            }
+            Message::SeatStack {
+                table_id,
+                player_id,
+                stack,
+            } => self.on_seat_stack(origin, player_id, table_id, stack).await,
+            Message::ChipsReturned {
+                player_id, amount, ..
+            } => self.on_chips_returned(origin, player_id, amount).await,
+        }
+    }
+
+    /// Ответ на отклонённое сообщение: фишки из него возвращаются
+    /// отправителю. Записи обработчика контракт к этому моменту уже
+    /// откатил, а накопленные события и сообщения выбросил.
+    pub fn rejection_replies(
+        origin: ChainId,
+        is_bouncing: bool,
+        message: Message,
+        reason: String,
+    ) -> Vec<(ChainId, Message)> {
+        if is_bouncing {
+            return Vec::new();
+        }
+
+        match message {
+            Message::SeatRequest {
+                table_id,
+                player_id,
+                buy_in,
+                ..
+            } => vec![(
+                origin,
+                Message::SeatRejected {
+                    table_id,
+                    player_id,
+                    refund: buy_in,
+                    reason,
+                },
+            )],
+            Message::SeatForwarded {
+                table_id,
+                player_id,
+                player_chain,
+                buy_in,
+                ..
+            } => vec![(
+                player_chain,
+                Message::SeatRejected {
+                    table_id,
+                    player_id,
+                    refund: buy_in,
+                    reason,
+                },
+            )],
+            Message::CashOut {
+                table_id,
+                player_id,
+                amount,
+            }
+            | Message::SeatRejected {
+                table_id,
+                player_id,
+                refund: amount,
+                ..
+            } if !amount.is_zero() => vec![(
+                origin,
+                Message::ChipsReturned {
+                    table_id,
+                    player_id,
+                    amount,
+                    reason,
+                },
+            )],
+            // Остальные сообщения фишек не несут.
+            _ => Vec::new(),
+        }
+    }
+
+    /// Цепь стола: цепь игрока не приняла его фишки — паркуем их
+    /// в банкролле игрока здесь, как при bounce.
+    async fn on_chips_returned(
+        &mut self,
+        origin: ChainId,
+        player_id: PlayerId,
+        amount: Chips,
+    ) -> OnchainResult<()> {
+        if self
+            .state
+            .player_chains
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            != Some(origin)
+        {
+            return Err(OnchainError::UnexpectedBankrollOrigin(player_id));
+        }
+        self.credit_bankroll(player_id, amount).await
+    }
+
+    async fn ensure_from_coordinator(
+        &self,
+        origin: ChainId,
+        table_id: TableId,
+    ) -> OnchainResult<()> {
+        if self.coordinator_of(table_id).await? != Some(origin) {
+            return Err(OnchainError::UnexpectedMessageOrigin(table_id));
+        }
+        Ok(())
+    }
+
+    async fn ensure_from_table_chain(
+        &self,
+        origin: ChainId,
+        table_id: TableId,
+    ) -> OnchainResult<()> {
+        if self.remote_chain_of(table_id).await? != Some(origin) {
+            return Err(OnchainError::UnexpectedMessageOrigin(table_id));
+        }
+        Ok(())
+    }
+
+    /// Цепь стола: разместить стол, присланный координатором.
+    async fn on_open_table(
+        &mut self,
+        origin: ChainId,
+        table: Table,
+        rules: TableRules,
+        roles: TableRoles,
+    ) -> OnchainResult<()> {
+        let table_id = table.id;
+        if origin != self.coordinator {
+            return Err(OnchainError::UnexpectedMessageOrigin(table_id));
+        }
+
+        if self
+            .state
+            .tables
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .is_some()
+        {
+            // Повторная доставка — стол уже открыт.
+            return Ok(());
+        }
+
+        self.save_table(table)?;
+        self.save_table_rules(table_id, rules)?;
+        self.state
+            .active_hands
+            .insert(&table_id, None)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .table_roles
+            .insert(&table_id, roles)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .table_coordinators
+            .insert(&table_id, origin)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Цепь стола: новая рассадка. Во время раздачи — откладываем.
+    /// Стеки оставшихся за столом игроков берутся живые (`merge_seats`).
+    async fn on_sync_seats(
+        &mut self,
+        origin: ChainId,
+        table_id: TableId,
+        seats: Vec<Option<PlayerAtTable>>,
+    ) -> OnchainResult<()> {
+        self.ensure_from_coordinator(origin, table_id).await?;
+
+        let mut table = self.load_table(table_id).await?;
+        if table.hand_in_progress {
+            return self
+                .state
+                .pending_seats
+                .insert(&table_id, seats)
+                .map_err(|e| OnchainError::Storage(e.to_string()));
+        }
+
+        table.seats = merge_seats(&table.seats, seats);
+        self.save_table(table)
+    }
+
+    /// Координатор: обновить стеки в зеркале стола.
+    async fn update_mirror_stacks(
+        &mut self,
+        table_id: TableId,
+        stacks: &[(SeatIndex, PlayerId, Chips)],
+    ) -> OnchainResult<Table> {
+        let mut table = self.load_table(table_id).await?;
+
+        for slot in table.seats.iter_mut() {
+            *slot = None;
+        }
+        for (seat, player_id, stack) in stacks.iter().copied() {
+            if let Some(slot) = table.seats.get_mut(seat as usize) {
+                *slot = Some(PlayerAtTable::new(player_id, stack));
+            }
+        }
+
+        self.save_table(table.clone())?;
+        Ok(table)
+    }
+
+    /// Координатор: стол на другой цепи доиграл раздачу.
+    ///
+    /// Обновляем зеркало, прогоняем турнирный хук (вылеты, ребалансировка)
+    /// и рассылаем столам изменившуюся рассадку.
+    async fn on_remote_hand_finished(
+        &mut self,
+        origin: ChainId,
+        table_id: TableId,
+        stacks: Vec<(SeatIndex, PlayerId, Chips)>,
+        total_pot: Chips,
+    ) -> OnchainResult<()> {
+        self.ensure_from_table_chain(origin, table_id).await?;
+
+        let table = self.update_mirror_stacks(table_id, &stacks).await?;
+        self.record_table_stats(table_id, total_pot).await?;
+
+        if let Some(tournament_id) = self.table_tournament_id(table_id).await? {
+            self.run_tournament_hook(tournament_id, &table).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Турнирный хук + синхронизация столов, живущих на других цепях.
+    ///
+    /// Хук работает с зеркалами; всё, что он поменял в рассадке удалённых
+    /// столов, рассылаем их цепям (`SyncSeats` / `CloseTable`).
+    async fn run_tournament_hook(
+        &mut self,
+        tournament_id: TournamentId,
+        table: &Table,
+    ) -> OnchainResult<()> {
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let mut before: Vec<(TableId, ChainId, Vec<Option<PlayerId>>)> = Vec::new();
+        for tid in table_ids {
+            if let Some(chain) = self.remote_chain_of(tid).await? {
+                let t = self.load_table(tid).await?;
+                before.push((tid, chain, seating(&t)));
+            }
+        }
+
+        self.handle_tournament_after_hand(tournament_id, table)
+            .await?;
+
+        for (tid, chain, old_seating) in before {
+            let current = self
+                .state
+                .tables
+                .get(&tid)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+            match current {
+                None => {
+                    // Хук убрал пустой стол — закрываем его на цепи стола.
+                    self.state
+                        .table_chains
+                        .remove(&tid)
+                        .map_err(|e| OnchainError::Storage(e.to_string()))?;
+                    self.outgoing
+                        .push((chain, Message::CloseTable { table_id: tid }));
+                }
+                Some(t) if seating(&t) != old_seating => {
+                    self.outgoing.push((
+                        chain,
+                        Message::SyncSeats {
+                            table_id: tid,
+                            seats: t.seats.clone(),
+                        },
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Удалить стол и всё, что к нему привязано на этой цепи.
+    fn remove_table_everywhere(&mut self, table_id: TableId) -> OnchainResult<()> {
+        self.state
+            .tables
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .active_hands
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .time_controllers
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .auto_deal
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .hand_logs
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .pending_seats
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .pending_cash_outs
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .table_rules
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                 БАНКРОЛЛ НА ЦЕПИ ИГРОКА (CROSS-CHAIN SEATS)
+    // =====================================================================
+
+    async fn handle_credit_bankroll(
+        &mut self,
+        cmd: CreditBankrollCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_admin().await?;
+
+        if cmd.player_chain == self.chain_id {
+            self.credit_bankroll(cmd.player_id, cmd.amount).await?;
+        } else {
+            self.outgoing.push((
+                cmd.player_chain,
+                Message::Credit {
+                    player_id: cmd.player_id,
+                    amount: cmd.amount,
+                },
+            ));
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("bankroll credit sent"),
+        ))
+    }
+
+    /// Цепь игрока: списать бай-ин и отправить SeatRequest координатору.
+    /// Садиться может только зарегистрированный игрок.
+    async fn handle_request_seat(
+        &mut self,
+        cmd: RequestSeatCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        if player_id != cmd.player_id {
+            return Err(OnchainError::PlayerIdMismatch);
+        }
+        if cmd.buy_in.is_zero() {
+            return Err(OnchainError::ZeroBuyIn);
+        }
+
+        let key = (player_id, cmd.table_id);
+        if self
+            .state
+            .seat_claims
+            .contains_key(&key)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            return Err(OnchainError::SeatAlreadyClaimed {
+                player: player_id,
+                table: cmd.table_id,
+            });
+        }
+
+        self.debit_bankroll(player_id, cmd.buy_in).await?;
+        self.state
+            .seat_claims
+            .insert(
+                &key,
+                SeatClaim {
+                    table_chain: cmd.table_chain,
+                    stack: cmd.buy_in,
+                },
+            )
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let seat_index = cmd.seat_index as SeatIndex;
+        if self.chain_id == self.coordinator {
+            self.on_seat_request(
+                self.chain_id,
+                cmd.table_id,
+                player_id,
+                seat_index,
+                cmd.buy_in,
+                cmd.display_name,
+            )
+            .await?;
+        } else {
+            self.outgoing.push((
+                self.coordinator,
+                Message::SeatRequest {
+                    table_id: cmd.table_id,
+                    player_id,
+                    seat_index,
+                    buy_in: cmd.buy_in,
+                    display_name: cmd.display_name,
+                },
+            ));
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("seat request sent"),
+        ))
+    }
+
+    /// Цепь игрока: попросить цепь стола вернуть стек.
+    async fn handle_cash_out(
+        &mut self,
+        cmd: CashOutCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.ensure_player_for_signer(cmd.player_id).await?;
+
+        self.outgoing.push((
+            cmd.table_chain,
+            Message::CashOutRequest {
+                table_id: cmd.table_id,
+                player_id,
+            },
+        ));
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("cash out request sent"),
+        ))
+    }
+
+    /// Цепь стола: цепь игрока, подтверждённая координатором. Игрок
+    /// играет за cash-столами с одной цепи.
+    async fn ensure_player_for_chain(
+        &mut self,
+        player_id: PlayerId,
+        player_chain: ChainId,
+    ) -> OnchainResult<()> {
+        match self
+            .state
+            .player_chains
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            Some(chain) if chain != player_chain => Err(OnchainError::PlayerIdMismatch),
+            Some(_) => Ok(()),
+            None => self
+                .state
+                .player_chains
+                .insert(&player_id, player_chain)
+                .map_err(|e| OnchainError::Storage(e.to_string())),
+        }
+    }
+
+    /// Координатор: подписанта `SeatRequest` проверяем по общему реестру
+    /// и пересылаем запрос цепи стола (или сажаем сами, если стол здесь).
+    /// Любая ошибка — SeatRejected с возвратом бай-ина.
+    async fn on_seat_request(
+        &mut self,
+        origin: ChainId,
+        table_id: TableId,
+        player_id: PlayerId,
+        seat_index: SeatIndex,
+        buy_in: Chips,
+        display_name: String,
+    ) -> OnchainResult<()> {
+        self.ensure_coordinator_chain()?;
+        if buy_in.is_zero() {
+            return Err(OnchainError::ZeroBuyIn);
+        }
+        let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
+        if self
+            .state
+            .player_accounts
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            != Some(signer)
+        {
+            return Err(OnchainError::PlayerNotRegistered(player_id));
+        }
+
+        match self.remote_chain_of(table_id).await? {
+            Some(table_chain) => {
+                self.outgoing.push((
+                    table_chain,
+                    Message::SeatForwarded {
+                        table_id,
+                        player_id,
+                        player_chain: origin,
+                        seat_index,
+                        buy_in,
+                        display_name,
+                    },
+                ));
+                Ok(())
+            }
+            None => {
+                self.seat_from_player_chain(
+                    origin,
+                    table_id,
+                    player_id,
+                    seat_index,
+                    buy_in,
+                    display_name,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Цепь стола: посадить за cash-стол игрока с цепи `player_chain`
+    /// на его бай-ин.
+    async fn seat_from_player_chain(
+        &mut self,
+        player_chain: ChainId,
+        table_id: TableId,
+        player_id: PlayerId,
+        seat_index: SeatIndex,
+        buy_in: Chips,
+        display_name: String,
+    ) -> OnchainResult<()> {
+        if buy_in.is_zero() {
+            return Err(OnchainError::ZeroBuyIn);
+        }
+        self.ensure_local_table(table_id).await?;
+        let mut table = self.load_table(table_id).await?;
+        if table.config.table_type != TableType::Cash {
+            return Err(OnchainError::NotCashTable(table_id));
+        }
+        self.ensure_player_for_chain(player_id, player_chain).await?;
+
+        if table
+            .seats
+            .iter()
+            .flatten()
+            .any(|p| p.player_id == player_id)
+        {
+            return Err(OnchainError::SeatAlreadyClaimed {
+                player: player_id,
+                table: table_id,
+            });
+        }
+
+        // Желаемое место, иначе первое свободное.
+        let seat = if (seat_index as usize) < table.seats.len()
+            && table.is_seat_empty(seat_index)
+        {
+            Some(seat_index)
+        } else {
+            table
+                .seats
+                .iter()
+                .position(|s| s.is_none())
+                .map(|idx| idx as SeatIndex)
+        };
+        let seat = seat.ok_or(OnchainError::TableFull(table_id))?;
+
+        table.seats[seat as usize] = Some(PlayerAtTable::new(player_id, buy_in));
+
+        // Имя с чужой цепи может оказаться занятым или невалидным —
+        // посадку из-за этого не отклоняем, просто не меняем имя.
+        if !display_name.is_empty() {
+            let _ = self.claim_display_name(player_id, &display_name).await;
+        }
+
+        self.save_table(table.clone())?;
+
+        self.emit(PokerEvent::PlayerSeated {
+            table_id,
+            seat,
+            player_id,
+        });
+
+        self.report_stacks(&table).await
+    }
+
+    /// Цепь стола: игрок встаёт, стек уходит на его цепь.
+    async fn on_cash_out_request(
+        &mut self,
+        origin: ChainId,
+        table_id: TableId,
+        player_id: PlayerId,
+    ) -> OnchainResult<()> {
+        if self
+            .state
+            .player_chains
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            != Some(origin)
+        {
+            return Err(OnchainError::PlayerIdMismatch);
+        }
+
+        let table = self.load_table(table_id).await?;
+        if table.config.table_type != TableType::Cash {
+            return Err(OnchainError::NotCashTable(table_id));
+        }
+        if table.hand_in_progress {
+            // Встанет после раздачи (см. `apply_pending_cash_outs`).
+            let mut queued = self
+                .state
+                .pending_cash_outs
+                .get(&table_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+                .unwrap_or_default();
+            if !queued.contains(&player_id) {
+                queued.push(player_id);
+            }
+            return self
+                .state
+                .pending_cash_outs
+                .insert(&table_id, queued)
+                .map_err(|e| OnchainError::Storage(e.to_string()));
+        }
+
+        self.cash_out_player(table, player_id, origin).await
+    }
+
+    /// Цепь стола: выполнить cash-out'ы, отложенные до конца раздачи.
+    async fn apply_pending_cash_outs(&mut self, table_id: TableId) -> OnchainResult<()> {
+        let Some(queued) = self
+            .state
+            .pending_cash_outs
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        self.state
+            .pending_cash_outs
+            .remove(&table_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        for player_id in queued {
+            let Some(chain) = self
+                .state
+                .player_chains
+                .get(&player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+            else {
+                continue;
+            };
+            let table = self.load_table(table_id).await?;
+            let seated = table
+                .seats
+                .iter()
+                .flatten()
+                .any(|p| p.player_id == player_id);
+            if seated {
+                self.cash_out_player(table, player_id, chain).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Цепь стола: снять игрока со стола и отправить стек на его цепь.
+    async fn cash_out_player(
+        &mut self,
+        mut table: Table,
+        player_id: PlayerId,
+        player_chain: ChainId,
+    ) -> OnchainResult<()> {
+        let table_id = table.id;
+        let seat = table
+            .seats
+            .iter()
+            .position(|s| s.as_ref().is_some_and(|p| p.player_id == player_id))
+            .ok_or(OnchainError::NoPlayerAtSeat {
+                table: table_id,
+                seat: 255,
+            })?;
+        let amount = table.seats[seat]
+            .take()
+            .map(|p| p.stack)
+            .unwrap_or(Chips::ZERO);
+
+        self.save_table(table.clone())?;
+
+        self.outgoing.push((
+            player_chain,
+            Message::CashOut {
+                table_id,
+                player_id,
+                amount,
+            },
+        ));
+
+        self.report_stacks(&table).await
+    }
+
+    /// Цепь игрока: место, которое держит цепь `origin`. Координатор
+    /// тоже может ответить по месту: он отклоняет SeatRequest до пересылки.
+    async fn seat_claim_from(
+        &self,
+        origin: ChainId,
+        player_id: PlayerId,
+        table_id: TableId,
+    ) -> OnchainResult<SeatClaim> {
+        match self
+            .state
+            .seat_claims
+            .get(&(player_id, table_id))
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            Some(claim) if claim.table_chain == origin || origin == self.coordinator => {
+                Ok(claim)
+            }
+            _ => Err(OnchainError::UnexpectedBankrollOrigin(player_id)),
+        }
+    }
+
+    /// Цепь игрока: SeatRejected или CashOut — место освобождается,
+    /// фишки (не больше записанного стека) возвращаются в банкролл.
+    async fn close_seat_claim(
+        &mut self,
+        origin: ChainId,
+        player_id: PlayerId,
+        table_id: TableId,
+        amount: Chips,
+    ) -> OnchainResult<()> {
+        let claim = self.seat_claim_from(origin, player_id, table_id).await?;
+        if amount.0 > claim.stack.0 {
+            return Err(OnchainError::CashOutExceedsStack {
+                player: player_id,
+                amount,
+                stack: claim.stack,
+            });
+        }
+
+        self.state
+            .seat_claims
+            .remove(&(player_id, table_id))
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.credit_bankroll(player_id, amount).await
+    }
+
+    /// Цепь игрока: запомнить стек, о котором сообщила цепь стола.
+    async fn on_seat_stack(
+        &mut self,
+        origin: ChainId,
+        player_id: PlayerId,
+        table_id: TableId,
+        stack: Chips,
+    ) -> OnchainResult<()> {
+        let mut claim = self.seat_claim_from(origin, player_id, table_id).await?;
+        claim.stack = stack;
+        self.state
+            .seat_claims
+            .insert(&(player_id, table_id), claim)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Вернувшееся (bounced) сообщение: фишки паркуем в банкролле на этой цепи.
+    async fn on_bounced_message(&mut self, message: Message) -> OnchainResult<()> {
+        match message {
+            Message::SeatRequest {
+                table_id,
+                player_id,
+                buy_in,
+                ..
+            } => {
+                self.state
+                    .seat_claims
+                    .remove(&(player_id, table_id))
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+                self.credit_bankroll(player_id, buy_in).await
+            }
+            // Цепь стола недоступна: бай-ин возвращается на цепь игрока.
+            Message::SeatForwarded {
+                table_id,
+                player_id,
+                player_chain,
+                buy_in,
+                ..
+            } => {
+                self.outgoing.push((
+                    player_chain,
+                    Message::SeatRejected {
+                        table_id,
+                        player_id,
+                        refund: buy_in,
+                        reason: "table chain did not accept the seat request".to_string(),
+                    },
+                ));
+                Ok(())
+            }
+            Message::SeatRejected {
+                player_id, refund, ..
+            } => self.credit_bankroll(player_id, refund).await,
+            Message::CashOut {
+                player_id, amount, ..
+            }
+            | Message::Credit { player_id, amount } => {
+                self.credit_bankroll(player_id, amount).await
+            }
+            // Остальные сообщения фишек не несут.
+            _ => Ok(()),
+        }
+    }
+
+    async fn bankroll_of(&self, player_id: PlayerId) -> OnchainResult<Chips> {
+        Ok(self
+            .state
+            .bankrolls
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or(Chips::ZERO))
+    }
+
+    async fn credit_bankroll(
+        &mut self,
+        player_id: PlayerId,
+        amount: Chips,
+    ) -> OnchainResult<()> {
+        let mut balance = self.bankroll_of(player_id).await?;
+        balance += amount;
+        self.state
+            .bankrolls
+            .insert(&player_id, balance)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    async fn debit_bankroll(
+        &mut self,
+        player_id: PlayerId,
+        amount: Chips,
+    ) -> OnchainResult<()> {
+        let mut balance = self.bankroll_of(player_id).await?;
+        if balance.0 < amount.0 {
+            return Err(OnchainError::InsufficientBankroll {
+                player: player_id,
+                needed: amount,
+                available: balance,
+            });
+        }
+        balance -= amount;
+        self.state
+            .bankrolls
+            .insert(&player_id, balance)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                       РОЛИ И ПЕРЕДАЧА ВЛАДЕНИЯ
+    // =====================================================================
+
+    /// Admin выдаёт/отзывает только owner, остальные роли — admin.
+    async fn ensure_can_manage_role(&self, role: Role) -> OnchainResult<AccountOwner> {
+        if role == Role::Admin {
+            self.ensure_owner().await
+        } else {
+            self.ensure_admin().await?;
+            self.signer.ok_or(OnchainError::Unauthenticated)
+        }
+    }
+
+    async fn handle_grant_role(
+        &mut self,
+        cmd: GrantRoleCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let by = self.ensure_can_manage_role(cmd.role).await?;
+        let assignment = RoleAssignment {
+            role: cmd.role,
+            table_id: cmd.table_id,
+        };
+
+        let mut roles = self.account_roles(cmd.account).await?;
+        if !roles.contains(&assignment) {
+            roles.push(assignment);
+            self.state
+                .roles
+                .insert(&cmd.account, roles)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            self.log_role_change(by, cmd.account, RoleChangeKind::Granted(assignment));
+            self.sync_remote_roles(cmd.table_id).await?;
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("role granted"),
+        ))
+    }
+
+    async fn handle_revoke_role(
+        &mut self,
+        cmd: RevokeRoleCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let by = self.ensure_can_manage_role(cmd.role).await?;
+        let assignment = RoleAssignment {
+            role: cmd.role,
+            table_id: cmd.table_id,
+        };
+
+        let mut roles = self.account_roles(cmd.account).await?;
+        let before = roles.len();
+        roles.retain(|a| *a != assignment);
+
+        if roles.len() != before {
+            if roles.is_empty() {
+                self.state
+                    .roles
+                    .remove(&cmd.account)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            } else {
+                self.state
+                    .roles
+                    .insert(&cmd.account, roles)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            }
+            self.log_role_change(by, cmd.account, RoleChangeKind::Revoked(assignment));
+            self.sync_remote_roles(cmd.table_id).await?;
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("role revoked"),
+        ))
+    }
+
+    /// Шаг 1: owner предлагает нового владельца. Повторный вызов
+    /// заменяет предыдущее предложение.
+    async fn handle_propose_owner(
+        &mut self,
+        cmd: ProposeOwnerCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let by = self.ensure_owner().await?;
+
+        self.state.pending_owner.set(Some(cmd.new_owner));
+        self.log_role_change(by, cmd.new_owner, RoleChangeKind::OwnershipProposed);
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("ownership transfer proposed"),
+        ))
+    }
+
+    /// Шаг 2: предложенный аккаунт принимает владение.
+    async fn handle_accept_ownership(
+        &mut self,
+        _cmd: AcceptOwnershipCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
+        if *self.state.pending_owner.get() != Some(signer) {
+            return Err(OnchainError::NoPendingOwnership);
+        }
+
+        let previous = self.app_owner().await;
+        self.state.owner.set(Some(signer));
+        self.state.pending_owner.set(None);
+        self.log_role_change(
+            signer,
+            signer,
+            RoleChangeKind::OwnershipAccepted { previous },
+        );
+        self.sync_remote_roles(None).await?;
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("ownership accepted"),
+        ))
+    }
+
+    async fn account_roles(
+        &self,
+        account: AccountOwner,
+    ) -> OnchainResult<Vec<RoleAssignment>> {
+        Ok(self
+            .state
+            .roles
+            .get(&account)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default())
+    }
+
+    /// Координатор: роли, действующие на столе `table_id`
+    /// (без области или с областью этого стола).
+    async fn table_roles_for(&self, table_id: TableId) -> OnchainResult<TableRoles> {
+        let accounts = self
+            .state
+            .roles
+            .indices()
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let mut grants = Vec::new();
+        for account in accounts {
+            for assignment in self.account_roles(account).await? {
+                if assignment.table_id.is_none() || assignment.table_id == Some(table_id) {
+                    grants.push((account, assignment));
+                }
+            }
+        }
+
+        Ok(TableRoles {
+            owner: self.app_owner().await,
+            grants,
+        })
+    }
+
+    /// Координатор: разослать роли цепям удалённых столов —
+    /// одному столу или (`None`) всем.
+    async fn sync_remote_roles(&mut self, table_id: Option<TableId>) -> OnchainResult<()> {
+        let table_ids = match table_id {
+            Some(table_id) => vec![table_id],
+            None => self
+                .state
+                .table_chains
+                .indices()
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?,
+        };
+
+        for table_id in table_ids {
+            let Some(chain) = self.remote_chain_of(table_id).await? else {
+                continue;
+            };
+            let roles = self.table_roles_for(table_id).await?;
+            self.outgoing
+                .push((chain, Message::SyncRoles { table_id, roles }));
+        }
+        Ok(())
+    }
+
+    fn log_role_change(
+        &mut self,
+        by: AccountOwner,
+        account: AccountOwner,
+        kind: RoleChangeKind,
+    ) {
+        self.state.role_log.push(RoleChange {
+            at: self.now,
+            by,
+            account,
+            kind,
+        });
+    }
+
+    // =====================================================================
+    //                     ВАРИАНТЫ ИГРЫ И ЛИМИТЫ СТАВОК
+    // =====================================================================
+
+    async fn handle_create_table_with_rules(
+        &mut self,
+        cmd: CreateTableWithRulesCommand,
+    ) -> OnchainResult<CommandResponse> {
+        cmd.rules
+            .validate()
+            .map_err(OnchainError::InvalidTableRules)?;
+
+        let table_id = cmd.table.table_id;
+        let response = self.handle_create_table(cmd.table).await?;
+        self.save_table_rules(table_id, cmd.rules)?;
+        Ok(response)
+    }
+
+    async fn load_table_rules(&self, table_id: TableId) -> OnchainResult<TableRules> {
+        Ok(self
+            .state
+            .table_rules
+            .get(&table_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default())
+    }
+
+    /// Правила по умолчанию не храним — отсутствие записи и есть NL Hold'em.
+    fn save_table_rules(&mut self, table_id: TableId, rules: TableRules) -> OnchainResult<()> {
+        if rules == TableRules::default() {
+            return Ok(());
+        }
+        self.state
+            .table_rules
+            .insert(&table_id, rules)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Проверить размер ставки по лимиту стола до того, как отдать её движку.
+    ///
+    /// Возвращает действие с подставленной суммой: на лимитных столах
+    /// `Bet` / `Raise` без суммы означают стандартную ставку.
+    async fn check_bet_limits(
+        &self,
+        table: &Table,
+        snapshot: &HandEngineSnapshot,
+        action: &PlayerAction,
+    ) -> OnchainResult<PlayerActionKind> {
+        let rules = self.load_table_rules(table.id).await?;
+        let Some(player) = table.seats.get(action.seat as usize).and_then(|s| s.as_ref()) else {
+            return Ok(action.kind.clone());
+        };
+
+        let bets_this_street = self
+            .state
+            .hand_logs
+            .get(&table.id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .map_or(0, |log| log.bets_on(table.street));
+
+        let mut pot = Chips::ZERO;
+        for amount in snapshot.contributions.values() {
+            pot += *amount;
+        }
+        let max_bet = table
+            .seats
+            .iter()
+            .flatten()
+            .map(|p| p.current_bet.0)
+            .max()
+            .unwrap_or(0);
+
+        let round = BettingRound {
+            pot,
+            max_bet: Chips(max_bet),
+            player_bet: player.current_bet,
+            player_stack: player.stack,
+            street: table.street,
+            bets_this_street,
+        };
+
+        let kind = resolve_limit_amount(&rules, action.kind.clone(), &round);
+        check_bet_size(&rules, &kind, &round).map_err(|reason| OnchainError::IllegalBet {
+            table: table.id,
+            reason,
+        })?;
+        Ok(kind)
+    }
+
+    /// Шоудаун для правил, которых не знает движок: откатываем его
+    /// выплаты (стек = стартовый − вклад) и делим банки своей оценкой рук.
+    async fn settle_variant_showdown(
+        &mut self,
+        table: &mut Table,
+        contributions: &HashMap<SeatIndex, Chips>,
+    ) -> OnchainResult<()> {
+        let rules = self.load_table_rules(table.id).await?;
+        if !rules.settles_on_chain() {
+            return Ok(());
+        }
+
+        let Some(log) = self
+            .state
+            .hand_logs
+            .get(&table.id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        let live: Vec<SeatIndex> = log
+            .seats
+            .iter()
+            .filter(|s| {
+                table
+                    .seats
+                    .get(s.seat as usize)
+                    .and_then(|p| p.as_ref())
+                    .is_some_and(|p| p.player_id == s.player_id && !is_folded(p))
+            })
+            .map(|s| s.seat)
+            .collect();
+
+        let awards = if live.len() < 2 {
+            // Без шоудауна банк забирает последний оставшийся. Движок прав,
+            // если не было анте с баттона, которого он не видел.
+            if rules.button_ante.is_none() {
+                return Ok(());
+            }
+            let total: u64 = contributions.values().map(|c| c.0).sum();
+            live.iter().map(|seat| (*seat, total)).collect()
+        } else {
+            // Руку не оценить — стеки не трогаем, раздача завершается ошибкой.
+            self.showdown_awards(table, &log, &rules, contributions)?
+        };
+
+        for seat in &log.seats {
+            let Some(Some(player)) = table.seats.get_mut(seat.seat as usize) else {
+                continue;
+            };
+            if player.player_id != seat.player_id {
+                continue;
+            }
+            let contributed = contributions.get(&seat.seat).map_or(0, |c| c.0);
+            let award = awards.get(&seat.seat).copied().unwrap_or(0);
+            player.stack = Chips(seat.starting_stack.0.saturating_sub(contributed) + award);
+        }
+
+        Ok(())
+    }
+
+    /// Выигрыши по местам: оценка рук по правилам варианта и делёжка
+    /// основного и побочных банков. Нечитаемая карта или рука, которую
+    /// нельзя оценить, — ошибка: молча отдать банк другим нельзя.
+    fn showdown_awards(
+        &self,
+        table: &Table,
+        log: &ArchivedHand,
+        rules: &TableRules,
+        contributions: &HashMap<SeatIndex, Chips>,
+    ) -> OnchainResult<HashMap<SeatIndex, u64>> {
+        let unsupported = |what: String| OnchainError::VariantNotSupported(what);
+        let board = readable_cards(&table.board)?;
+        let mut entries = Vec::new();
+        for seat in &log.seats {
+            let player = table
+                .seats
+                .get(seat.seat as usize)
+                .and_then(|s| s.as_ref())
+                .filter(|p| p.player_id == seat.player_id);
+            let hand = match player.filter(|p| !is_folded(p)) {
+                Some(p) => {
+                    let cards = hole_cards(p).ok_or_else(|| {
+                        unsupported(format!("seat {} has no hole cards", seat.seat))
+                    })?;
+                    let hole = readable_cards(&cards)?;
+                    let value = best_hand(rules.variant, &hole, &board).ok_or_else(|| {
+                        unsupported(format!("cannot evaluate the hand at seat {}", seat.seat))
+                    })?;
+                    Some(value)
+                }
+                None => None,
+            };
+            entries.push(PotEntry {
+                seat: seat.seat,
+                contributed: contributions.get(&seat.seat).map_or(0, |c| c.0),
+                hand,
+            });
+        }
+
+        let occupied: Vec<SeatIndex> = log.seats.iter().map(|s| s.seat).collect();
+        let order = seats_from_button(&occupied, table.dealer_button);
+        Ok(split_pots(&entries, &order))
+    }
+
+    // =====================================================================
+    //                    ИДЕНТИЧНОСТЬ И ОТОБРАЖАЕМЫЕ ИМЕНА
+    // =====================================================================
+
+    /// Явная регистрация: связать подписанта с player_id и занять имя.
+    /// Регистрация на цепи игрока дублируется в общий реестр координатора,
+    /// иначе сесть за cash-стол не получится.
+    async fn handle_register_player(
+        &mut self,
+        cmd: RegisterPlayerCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let signer = self.signer.ok_or(OnchainError::Unauthenticated)?;
+
+        if self
+            .state
+            .account_players
+            .get(&signer)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .is_some()
+        {
+            return Err(OnchainError::AccountAlreadyLinked);
+        }
+        self.ensure_account_not_retired(signer).await?;
+        if self
+            .state
+            .player_accounts
+            .get(&cmd.player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .is_some()
+        {
+            return Err(OnchainError::PlayerAlreadyRegistered(cmd.player_id));
+        }
+
+        self.claim_display_name(cmd.player_id, &cmd.display_name)
+            .await?;
+        self.ensure_player_for_signer(cmd.player_id).await?;
+
+        let profile = self.load_or_create_profile(cmd.player_id).await?;
+        self.state
+            .player_profiles
+            .insert(&cmd.player_id, profile)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        if self.chain_id != self.coordinator {
+            self.outgoing.push((
+                self.coordinator,
+                Message::RegisterPlayer {
+                    player_id: cmd.player_id,
+                    display_name: cmd.display_name,
+                },
+            ));
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("player registered"),
+        ))
+    }
+
+    async fn handle_set_display_name(
+        &mut self,
+        cmd: SetDisplayNameCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        if player_id != cmd.player_id {
+            return Err(OnchainError::PlayerIdMismatch);
+        }
+
+        self.claim_display_name(player_id, &cmd.display_name).await?;
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("display name updated"),
+        ))
+    }
+
+    /// Перенести player_id на новый аккаунт. Подписывает старый аккаунт;
+    /// новый не должен быть привязан ни к какому игроку. Старый аккаунт
+    /// уходит в `retired_accounts` и привязать его снова нельзя.
+    async fn handle_rotate_account(
+        &mut self,
+        cmd: RotateAccountCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let old_account = self.signer.ok_or(OnchainError::Unauthenticated)?;
+        let player_id = self.signer_player_id().await?;
+        if player_id != cmd.player_id {
+            return Err(OnchainError::PlayerIdMismatch);
+        }
+
+        if self
+            .state
+            .account_players
+            .get(&cmd.new_account)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .is_some()
+        {
+            return Err(OnchainError::AccountAlreadyLinked);
+        }
+        self.ensure_account_not_retired(cmd.new_account).await?;
+
+        self.state
+            .account_players
+            .remove(&old_account)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .retired_accounts
+            .insert(&old_account, player_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .account_players
+            .insert(&cmd.new_account, player_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .player_accounts
+            .insert(&player_id, cmd.new_account)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        if self.chain_id != self.coordinator {
+            self.outgoing.push((
+                self.coordinator,
+                Message::RotateAccount {
+                    player_id,
+                    new_account: cmd.new_account,
+                },
+            ));
+        }
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("account rotated"),
+        ))
+    }
+
+    /// Проверить и занять имя за игроком; старое имя освобождается.
+    async fn claim_display_name(
+        &mut self,
+        player_id: PlayerId,
+        name: &str,
+    ) -> OnchainResult<()> {
+        let current = self
+            .state
+            .player_names
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        if current.as_deref() == Some(name) {
+            return Ok(());
+        }
+
+        validate_display_name(name).map_err(OnchainError::InvalidDisplayName)?;
+        self.backfill_name_index().await?;
+
+        let key = normalize_name(name);
+        let holder = self
+            .state
+            .name_index
+            .get(&key)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        if holder.is_some_and(|pid| pid != player_id) {
+            return Err(OnchainError::DisplayNameTaken(name.to_string()));
+        }
+
+        // Старый ключ освобождаем, только если он наш: до индекса два
+        // игрока могли занять имена, совпадающие без учёта регистра.
+        if let Some(old) = current {
+            let old_key = normalize_name(&old);
+            if self
+                .state
+                .name_index
+                .get(&old_key)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+                == Some(player_id)
+            {
+                self.state
+                    .name_index
+                    .remove(&old_key)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            }
+        }
+        self.state
+            .name_index
+            .insert(&key, player_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .player_names
+            .insert(&player_id, name.to_string())
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Один раз внести в `name_index` имена, занятые до его появления.
+    /// При совпадении без учёта регистра ключ достаётся меньшему player_id.
+    async fn backfill_name_index(&mut self) -> OnchainResult<()> {
+        if *self.state.name_index_backfilled.get() {
+            return Ok(());
+        }
+
+        let mut players = self
+            .state
+            .player_names
+            .indices()
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        players.sort_unstable();
+        for player_id in players {
+            let Some(name) = self
+                .state
+                .player_names
+                .get(&player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+            else {
+                continue;
+            };
+            let key = normalize_name(&name);
+            if self
+                .state
+                .name_index
+                .contains_key(&key)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+            {
+                continue;
+            }
+            self.state
+                .name_index
+                .insert(&key, player_id)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        self.state.name_index_backfilled.set(true);
+        Ok(())
+    }
+
+    // =====================================================================
+    //                      ПРОФИЛИ И СТАТИСТИКА ИГРОКОВ
+    // =====================================================================
+
+    async fn handle_update_profile(
+        &mut self,
+        cmd: UpdateProfileCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.ensure_player_for_signer(cmd.player_id).await?;
+
+        let mut profile = self.load_or_create_profile(player_id).await?;
+
+        if let Some(url) = cmd.avatar_url {
+            if !url.is_empty()
+                && (url.len() > MAX_AVATAR_URL_LEN || !url.starts_with("https://"))
+            {
+                return Err(OnchainError::InvalidProfile(format!(
+                    "avatar url must be https:// and at most {MAX_AVATAR_URL_LEN} bytes"
+                )));
+            }
+            profile.avatar_url = (!url.is_empty()).then_some(url);
+        }
+
+        if let Some(country) = cmd.country {
+            let valid = country.is_empty()
+                || (country.len() == 2
+                    && country.bytes().all(|b| b.is_ascii_uppercase()));
+            if !valid {
+                return Err(OnchainError::InvalidProfile(format!(
+                    "country must be an ISO 3166-1 alpha-2 code, got {country:?}"
+                )));
+            }
+            profile.country = (!country.is_empty()).then_some(country);
+        }
+
+        if let Some(preferences) = cmd.preferences {
+            profile.preferences = preferences;
+        }
+
+        self.state
+            .player_profiles
+            .insert(&player_id, profile)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("profile updated"),
+        ))
+    }
+
+    async fn handle_set_tournament_payouts(
+        &mut self,
+        cmd: SetTournamentPayoutsCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+        self.apply_tournament_schedule(cmd.tournament_id).await?;
+
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+
+        self.state
+            .tournament_payouts
+            .insert(&cmd.tournament_id, cmd.payouts)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let view = self.build_tournament_view(&tournament, table_ids).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    async fn handle_set_level_duration(
+        &mut self,
+        cmd: SetLevelDurationCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+
+        if cmd.duration_secs == 0 {
+            self.state
+                .tournament_level_durations
+                .remove(&cmd.tournament_id)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        } else {
+            self.state
+                .tournament_level_durations
+                .insert(&cmd.tournament_id, cmd.duration_secs)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let view = self.build_tournament_view(&tournament, table_ids).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    async fn load_or_create_profile(
+        &mut self,
+        player_id: PlayerId,
+    ) -> OnchainResult<PlayerProfile> {
+        Ok(self
+            .state
+            .player_profiles
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_else(|| PlayerProfile::new(self.now)))
+    }
+
+    /// Статистика игрока; при первом появлении заодно заводим профиль.
+    async fn load_player_stats(&mut self, player_id: PlayerId) -> OnchainResult<PlayerStats> {
+        let stats = self
+            .state
+            .player_stats
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        if let Some(stats) = stats {
+            return Ok(stats);
+        }
+
+        let profile = self.load_or_create_profile(player_id).await?;
+        self.state
+            .player_profiles
+            .insert(&player_id, profile)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        Ok(PlayerStats::default())
+    }
+
+    fn save_player_stats(
+        &mut self,
+        player_id: PlayerId,
+        stats: PlayerStats,
+    ) -> OnchainResult<()> {
+        self.state
+            .player_stats
+            .insert(&player_id, stats)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Записать финиш игрока в турнире: место, приз по `tournament_payouts`,
+    /// статистика. Повторный финиш того же игрока игнорируется.
+    async fn record_tournament_finish(
+        &mut self,
+        tournament_id: TournamentId,
+        player_id: PlayerId,
+        place: u32,
+        entrants: u32,
+    ) -> OnchainResult<()> {
+        let prize = self.place_prize(tournament_id, player_id, place).await?;
+        self.record_finish_with_prize(tournament_id, player_id, place, entrants, prize)
+            .await
+    }
+
+    /// Приз игрока за место: по сделке, если он в ней, иначе по `tournament_payouts`.
+    async fn place_prize(
+        &self,
+        tournament_id: TournamentId,
+        player_id: PlayerId,
+        place: u32,
+    ) -> OnchainResult<Chips> {
+        let deal_prize = self
+            .state
+            .locked_deals
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .and_then(|deal| deal.prize(player_id, place));
+
+        if let Some(prize) = deal_prize {
+            return Ok(prize);
+        }
+
+        Ok(self
+            .state
+            .tournament_payouts
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .and_then(|p| p.get(place.saturating_sub(1) as usize).copied())
+            .unwrap_or(Chips::ZERO))
+    }
+
+    async fn record_finish_with_prize(
+        &mut self,
+        tournament_id: TournamentId,
+        player_id: PlayerId,
+        place: u32,
+        entrants: u32,
+        prize: Chips,
+    ) -> OnchainResult<()> {
+        let mut results = self
+            .state
+            .tournament_results
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        if results.iter().any(|f| f.player_id == player_id) {
+            return Ok(());
+        }
+
+        let ticket = match self.satellite_of(tournament_id).await? {
+            Some(satellite) if place <= satellite.seats => {
+                self.issue_ticket(player_id, tournament_id, satellite.target)
+                    .await?;
+                Some(satellite.target)
+            }
+            _ => None,
+        };
+
+        results.push(TournamentFinish {
+            player_id,
+            place,
+            prize,
+            at: self.now,
+            ticket,
+        });
+        self.state
+            .tournament_results
+            .insert(&tournament_id, results)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        // Платные турниры рассчитываются через банкролл.
+        if !prize.is_zero() && self.tournament_buy_in(tournament_id).await?.is_some() {
+            self.credit_bankroll(player_id, prize).await?;
+        }
+
+        let mut stats = self.load_player_stats(player_id).await?;
+        stats.record_tournament_finish(prize);
+        self.save_player_stats(player_id, stats)?;
+
+        let points = tournament_points(entrants, place);
+        self.update_season_entry(player_id, |e| {
+            e.tournament_points += points;
+            e.net_winnings += prize.0 as i64;
+        })
+        .await
+    }
+
+    // =====================================================================
+    //                         СЕЗОННЫЕ РЕЙТИНГИ
+    // =====================================================================
+
+    /// Начать новый сезон (admin). Текущий, если идёт, архивируется досрочно.
+    async fn handle_start_season(
+        &mut self,
+        cmd: StartSeasonCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_admin().await?;
+
+        if cmd.duration_secs == 0 {
+            return Err(OnchainError::InvalidSeason(
+                "duration must be positive".to_string(),
+            ));
+        }
+
+        let next_id = match self.state.current_season.get().clone() {
+            Some(season) => {
+                self.archive_season(season.clone()).await?;
+                season.season_id + 1
+            }
+            None => 1,
+        };
+
+        self.state
+            .current_season
+            .set(Some(Season::new(next_id, self.now, cmd.duration_secs)));
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view(&format!("season {next_id} started")),
+        ))
+    }
+
+    /// Если сезон истёк по времени цепи — архивировать и открыть следующий.
+    async fn roll_season_if_due(&mut self) -> OnchainResult<()> {
+        let Some(season) = self.state.current_season.get().clone() else {
+            return Ok(());
+        };
+        if !season.is_over(self.now) {
+            return Ok(());
+        }
+
+        let next = season.next(self.now);
+        self.archive_season(season).await?;
+        self.state.current_season.set(Some(next));
+        Ok(())
+    }
+
+    /// Перенести итоговую таблицу сезона в архив и очистить текущую.
+    async fn archive_season(&mut self, season: Season) -> OnchainResult<()> {
+        let mut standings = Vec::new();
+        for player_id in self
+            .state
+            .season_standings
+            .indices()
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            if let Some(entry) = self
+                .state
+                .season_standings
+                .get(&player_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?
+            {
+                standings.push((player_id, entry));
+            }
+        }
+
+        let season_id = season.season_id;
+        self.state
+            .season_archive
+            .insert(&season_id, ArchivedSeason { season, standings })
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state.season_standings.clear();
+        Ok(())
+    }
+
+    /// Обновить строку игрока в текущем сезоне (если сезон запущен).
+    async fn update_season_entry(
+        &mut self,
+        player_id: PlayerId,
+        update: impl FnOnce(&mut SeasonEntry),
+    ) -> OnchainResult<()> {
+        self.roll_season_if_due().await?;
+        if self.state.current_season.get().is_none() {
+            return Ok(());
+        }
+
+        let mut entry = self
+            .state
+            .season_standings
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+        update(&mut entry);
+        self.state
+            .season_standings
+            .insert(&player_id, entry)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                         SIT-AND-GO И СПИНЫ
+    // =====================================================================
+
+    async fn handle_create_sng_template(
+        &mut self,
+        cmd: CreateSngTemplateCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+
+        validate_template(cmd.players, cmd.config.table_size, &cmd.spin)
+            .map_err(OnchainError::InvalidSngTemplate)?;
+
+        if self
+            .state
+            .sng_templates
+            .contains_key(&cmd.template_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            return Err(OnchainError::SngTemplateAlreadyExists(cmd.template_id));
+        }
+
+        let mut template = SngTemplate {
+            template_id: cmd.template_id,
+            config: cmd.config,
+            players: cmd.players,
+            buy_in: cmd.buy_in,
+            spin: cmd.spin,
+            current: None,
+            launched: 0,
+        };
+        let tournament = self.open_sng_tournament(&mut template).await?;
+        self.save_sng_template(template)?;
+
+        let view = self.build_tournament_view(&tournament, Vec::new()).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    async fn handle_remove_sng_template(
+        &mut self,
+        cmd: RemoveSngTemplateCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+
+        let template = self
+            .state
+            .sng_templates
+            .get(&cmd.template_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .ok_or(OnchainError::SngTemplateNotFound(cmd.template_id))?;
+
+        if let Some(tournament_id) = template.current {
+            let tournament = self.load_tournament(tournament_id).await?;
+            if !tournament.registrations.is_empty() {
+                return Err(OnchainError::InvalidSngTemplate(format!(
+                    "tournament {tournament_id} already has registrations"
+                )));
+            }
+            self.remove_tournament(tournament_id)?;
+        }
+
+        self.state
+            .sng_templates
+            .remove(&cmd.template_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        Ok(CommandResponse::TableState(
+            self.empty_table_view("sit-and-go template removed"),
+        ))
+    }
+
+    fn save_sng_template(&mut self, template: SngTemplate) -> OnchainResult<()> {
+        self.state
+            .sng_templates
+            .insert(&template.template_id, template)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Открыть очередной турнир шаблона под свободным tournament_id.
+    async fn open_sng_tournament(
+        &mut self,
+        template: &mut SngTemplate,
+    ) -> OnchainResult<Tournament> {
+        let tournament_id = self.next_tournament_id().await?;
+
+        let mut config = template.config.clone();
+        config.name = format!("{} #{}", template.config.name, template.launched + 1);
+
+        let tournament = Tournament::new(tournament_id, 0, config)?;
+
+        self.state
+            .tournaments
+            .insert(&tournament_id, tournament.clone())
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .tournament_tables
+            .insert(&tournament_id, Vec::new())
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .tournament_templates
+            .insert(&tournament_id, template.template_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        if !template.buy_in.is_zero() {
+            self.state
+                .tournament_buy_ins
+                .insert(&tournament_id, template.buy_in)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        template.current = Some(tournament_id);
+        Ok(tournament)
+    }
+
+    /// Турнир шаблона набрал игроков — стартуем его и открываем следующий.
+    async fn start_sng_if_full(
+        &mut self,
+        tournament_id: TournamentId,
+        tournament: &Tournament,
+    ) -> OnchainResult<Option<CommandResponse>> {
+        let Some(template_id) = self
+            .state
+            .tournament_templates
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let Some(mut template) = self
+            .state
+            .sng_templates
+            .get(&template_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        if (tournament.registrations.len() as u32) < template.players {
+            return Ok(None);
+        }
+
+        let response = self.start_tournament(tournament_id).await?;
+
+        template.launched += 1;
+        self.open_sng_tournament(&mut template).await?;
+        self.save_sng_template(template)?;
+
+        Ok(Some(response))
+    }
+
+    /// Призовой фонд при старте: бай-ины × участники, для спина — ещё
+    /// множитель, разыгранный из данных старта. Призовые по местам ставим
+    /// из шаблона, если директор не задал их сам. Переполнение фонда —
+    /// ошибка, и тогда ничего не записано.
+    async fn settle_prize_pool(
+        &mut self,
+        tournament_id: TournamentId,
+        entrants: u32,
+        entropy: &StartEntropy,
+    ) -> OnchainResult<()> {
+        let buy_in = self
+            .tournament_buy_in(tournament_id)
+            .await?
+            .unwrap_or(Chips::ZERO);
+        let overflow = || OnchainError::PrizePoolOverflow(tournament_id);
+        let mut prize_pool = buy_in
+            .0
+            .checked_mul(u64::from(entrants))
+            .map(Chips)
+            .ok_or_else(overflow)?;
+        let mut spin_multiplier = None;
+
+        let template_id = self
+            .state
+            .tournament_templates
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        let template = match template_id {
+            Some(id) => self
+                .state
+                .sng_templates
+                .get(&id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?,
+            None => None,
+        };
+
+        if let Some(template) = template {
+            if template.is_spin() {
+                let multiplier =
+                    draw_multiplier(&template.spin, entropy.seed(tournament_id, SPIN_SALT));
+                prize_pool = prize_pool
+                    .0
+                    .checked_mul(u64::from(multiplier))
+                    .map(Chips)
+                    .ok_or_else(overflow)?;
+                self.state
+                    .spin_multipliers
+                    .insert(&tournament_id, multiplier)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+                spin_multiplier = Some(multiplier);
+            }
+
+            let has_payouts = self
+                .state
+                .tournament_payouts
+                .contains_key(&tournament_id)
+                .await
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            if !has_payouts {
+                self.state
+                    .tournament_payouts
+                    .insert(&tournament_id, template.payouts(prize_pool))
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            }
+        }
+
+        if let Some(mut satellite) = self.satellite_of(tournament_id).await? {
+            let target_buy_in = self
+                .tournament_buy_in(satellite.target)
+                .await?
+                .unwrap_or(Chips::ZERO);
+            let (seats, remainder) = satellite_seats(prize_pool, target_buy_in);
+
+            // Билетные места — без фишек, остаток — следующему месту.
+            let mut payouts = vec![Chips::ZERO; seats as usize];
+            payouts.push(remainder);
+            self.state
+                .tournament_payouts
+                .insert(&tournament_id, payouts)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+            satellite.seats = seats;
+            self.state
+                .satellites
+                .insert(&tournament_id, satellite)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        self.emit(PokerEvent::TournamentStarted {
+            tournament_id,
+            prize_pool,
+            spin_multiplier,
+        });
+        Ok(())
+    }
+
+    async fn tournament_buy_in(
+        &self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<Option<Chips>> {
+        self.state
+            .tournament_buy_ins
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Следующий свободный tournament_id (для турниров, открытых приложением).
+    async fn next_tournament_id(&self) -> OnchainResult<TournamentId> {
+        let ids = self
+            .state
+            .tournaments
+            .indices()
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        Ok(ids.into_iter().max().map_or(1, |max| max + 1))
+    }
+
+    /// Удалить турнир, у которого ещё нет столов.
+    fn remove_tournament(&mut self, tournament_id: TournamentId) -> OnchainResult<()> {
+        self.state
+            .tournaments
+            .remove(&tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .tournament_tables
+            .remove(&tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .tournament_templates
+            .remove(&tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.state
+            .tournament_buy_ins
+            .remove(&tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    // =====================================================================
+    //                        САТЕЛЛИТЫ И БИЛЕТЫ
+    // =====================================================================
+
+    async fn handle_set_satellite(
+        &mut self,
+        cmd: SetSatelliteCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+
+        if cmd.tournament_id == cmd.target_tournament_id {
+            return Err(OnchainError::InvalidSatellite(
+                "a tournament cannot be its own target".to_string(),
+            ));
+        }
+
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+        if tournament.status != TournamentStatus::Registering {
+            return Err(OnchainError::InvalidSatellite(format!(
+                "tournament {} has already started",
+                cmd.tournament_id
+            )));
+        }
+
+        let target = self.load_tournament(cmd.target_tournament_id).await?;
+        if target.status != TournamentStatus::Registering {
+            return Err(OnchainError::InvalidSatellite(format!(
+                "target tournament {} is not registering",
+                cmd.target_tournament_id
+            )));
+        }
+        if self
+            .tournament_buy_in(cmd.target_tournament_id)
+            .await?
+            .is_none()
+        {
+            return Err(OnchainError::InvalidSatellite(format!(
+                "target tournament {} has no buy-in",
+                cmd.target_tournament_id
+            )));
+        }
+
+        self.state
+            .satellites
+            .insert(
+                &cmd.tournament_id,
+                SatelliteConfig {
+                    target: cmd.target_tournament_id,
+                    seats: 0,
+                },
+            )
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let view = self.build_tournament_view(&tournament, table_ids).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    async fn satellite_of(
+        &self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<Option<SatelliteConfig>> {
+        self.state
+            .satellites
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    async fn load_tickets(&self, player_id: PlayerId) -> OnchainResult<Vec<Ticket>> {
+        Ok(self
+            .state
+            .tickets
+            .get(&player_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default())
+    }
+
+    fn save_tickets(&mut self, player_id: PlayerId, tickets: Vec<Ticket>) -> OnchainResult<()> {
+        self.state
+            .tickets
+            .insert(&player_id, tickets)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Выдать билет и сразу попробовать зарегистрировать игрока в цели.
+    async fn issue_ticket(
+        &mut self,
+        player_id: PlayerId,
+        source: TournamentId,
+        target: TournamentId,
+    ) -> OnchainResult<()> {
+        let mut tickets = self.load_tickets(player_id).await?;
+        tickets.push(Ticket {
+            target,
+            source,
+            issued_at: self.now,
+            used: false,
+        });
+        self.save_tickets(player_id, tickets)?;
+
+        self.apply_tournament_schedule(target).await?;
+        if self.ensure_registration_open(target).await.is_err() {
+            return Ok(());
+        }
+        let Some(mut tournament) = self
+            .state
+            .tournaments
+            .get(&target)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        // Не получилось (уже зарегистрирован, мест нет) — билет остаётся.
+        if tournament.register_player(player_id).is_err() {
+            return Ok(());
+        }
+
+        self.redeem_ticket(player_id, target).await?;
+        self.state
+            .tournaments
+            .insert(&target, tournament.clone())
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        self.start_sng_if_full(target, &tournament).await?;
+        Ok(())
+    }
+
+    /// Погасить неиспользованный билет в турнир. false — билета нет.
+    async fn redeem_ticket(
+        &mut self,
+        player_id: PlayerId,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<bool> {
+        let mut tickets = self.load_tickets(player_id).await?;
+        let Some(ticket) = tickets
+            .iter_mut()
+            .find(|t| t.target == tournament_id && !t.used)
+        else {
+            return Ok(false);
+        };
+        ticket.used = true;
+        self.save_tickets(player_id, tickets)?;
+        Ok(true)
+    }
+
+    /// Вход в турнир: билетом, если он есть, иначе бай-ином из банкролла.
+    async fn pay_entry(
+        &mut self,
+        player_id: PlayerId,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<()> {
+        if self.redeem_ticket(player_id, tournament_id).await? {
+            return Ok(());
+        }
+        if let Some(buy_in) = self.tournament_buy_in(tournament_id).await? {
+            self.debit_bankroll(player_id, buy_in).await?;
+        }
+        Ok(())
+    }
+
+    /// Вернуть вход: билетом, если регистрация была по билету, иначе бай-ином.
+    async fn refund_entry(
+        &mut self,
+        player_id: PlayerId,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<()> {
+        let mut tickets = self.load_tickets(player_id).await?;
+        if let Some(ticket) = tickets
+            .iter_mut()
+            .find(|t| t.target == tournament_id && t.used)
+        {
+            ticket.used = false;
+            return self.save_tickets(player_id, tickets);
+        }
+        if let Some(buy_in) = self.tournament_buy_in(tournament_id).await? {
+            self.credit_bankroll(player_id, buy_in).await?;
+        }
+        Ok(())
+    }
+
+    // =====================================================================
+    //                       СДЕЛКИ ЗА ФИНАЛЬНЫМ СТОЛОМ
+    // =====================================================================
+
+    async fn handle_propose_deal(
+        &mut self,
+        cmd: ProposeDealCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+
+        if tournament.status != TournamentStatus::Running {
+            return Err(OnchainError::InvalidDeal(format!(
+                "tournament {} is not running",
+                cmd.tournament_id
+            )));
+        }
+        if self
+            .state
+            .locked_deals
+            .contains_key(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        {
+            return Err(OnchainError::InvalidDeal(
+                "a deal is already locked in".to_string(),
+            ));
+        }
+
+        let stacks = remaining_stacks(&tournament);
+        if !stacks.iter().any(|(pid, _)| *pid == player_id) {
+            return Err(OnchainError::InvalidDeal(format!(
+                "player {player_id} is not among the remaining players"
+            )));
+        }
+
+        // Оставшиеся призовые: места 1..=n, где n — живые игроки.
+        let prizes: Vec<Chips> = self
+            .state
+            .tournament_payouts
+            .get(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default()
+            .into_iter()
+            .take(stacks.len())
+            .collect();
+
+        let shares = compute_deal(cmd.kind, &stacks, &prizes, cmd.play_for)
+            .map_err(OnchainError::InvalidDeal)?;
+
+        let proposal = DealProposal {
+            proposer: player_id,
+            kind: cmd.kind,
+            play_for: cmd.play_for,
+            shares,
+            accepted: vec![player_id],
+            proposed_at: self.now,
+        };
+        self.state
+            .deal_proposals
+            .insert(&cmd.tournament_id, proposal)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        self.tournament_state(&tournament).await
+    }
+
+    async fn handle_accept_deal(
+        &mut self,
+        cmd: DealVoteCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        let mut tournament = self.load_tournament(cmd.tournament_id).await?;
+        let mut proposal = self.load_deal_proposal(cmd.tournament_id).await?;
+
+        if !proposal.shares.iter().any(|s| s.player_id == player_id) {
+            return Err(OnchainError::InvalidDeal(format!(
+                "player {player_id} is not part of the proposed deal"
+            )));
+        }
+
+        // Сыграна раздача или кто-то вылетел — доли посчитаны по старым стекам.
+        let stacks = remaining_stacks(&tournament);
+        let unchanged = tournament.status == TournamentStatus::Running
+            && stacks.len() == proposal.shares.len()
+            && proposal
+                .shares
+                .iter()
+                .all(|s| {
+                    stacks
+                        .iter()
+                        .any(|(pid, stack)| *pid == s.player_id && stack.0 == s.stack.0)
+                });
+        if !unchanged {
+            return Err(OnchainError::InvalidDeal(
+                "stacks changed since the proposal, propose again".to_string(),
+            ));
+        }
+
+        if !proposal.accepted.contains(&player_id) {
+            proposal.accepted.push(player_id);
+        }
+
+        if !proposal.is_unanimous() {
+            self.state
+                .deal_proposals
+                .insert(&cmd.tournament_id, proposal)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            return self.tournament_state(&tournament).await;
+        }
+
+        self.state
+            .deal_proposals
+            .remove(&cmd.tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let deal = LockedDeal {
+            kind: proposal.kind,
+            play_for: proposal.play_for,
+            shares: proposal.shares,
+            agreed_at: self.now,
+        };
+        let play_for = deal.play_for;
+        let mut by_stack: Vec<DealShare> = deal.shares.clone();
+        self.state
+            .locked_deals
+            .insert(&cmd.tournament_id, deal)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        // Делим всё — турнир заканчивается, места по стекам.
+        if play_for.is_zero() {
+            by_stack.sort_by(|a, b| {
+                b.stack
+                    .0
+                    .cmp(&a.stack.0)
+                    .then(a.player_id.cmp(&b.player_id))
+            });
+            let entrants = tournament.registrations.len() as u32;
+            for (i, share) in by_stack.iter().enumerate() {
+                self.record_tournament_finish(
+                    cmd.tournament_id,
+                    share.player_id,
+                    i as u32 + 1,
+                    entrants,
+                )
+                .await?;
+            }
+
+            tournament.status = TournamentStatus::Finished;
+            self.state
+                .tournaments
+                .insert(&cmd.tournament_id, tournament.clone())
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        }
+
+        self.tournament_state(&tournament).await
+    }
+
+    async fn handle_reject_deal(
+        &mut self,
+        cmd: DealVoteCommand,
+    ) -> OnchainResult<CommandResponse> {
+        let player_id = self.signer_player_id().await?;
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+        let proposal = self.load_deal_proposal(cmd.tournament_id).await?;
+
+        if !proposal.shares.iter().any(|s| s.player_id == player_id) {
+            return Err(OnchainError::InvalidDeal(format!(
+                "player {player_id} is not part of the proposed deal"
+            )));
+        }
+
+        self.state
+            .deal_proposals
+            .remove(&cmd.tournament_id)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        self.tournament_state(&tournament).await
+    }
+
+    async fn load_deal_proposal(
+        &self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<DealProposal> {
+        self.state
+            .deal_proposals
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .ok_or(OnchainError::DealNotFound(tournament_id))
+    }
+
+    async fn tournament_state(&self, tournament: &Tournament) -> OnchainResult<CommandResponse> {
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&tournament.id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let view = self.build_tournament_view(tournament, table_ids).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    // =====================================================================
+    //                       РУКА-В-РУКУ НА ПУЗЫРЕ
+    // =====================================================================
+
+    async fn load_hand_for_hand(
+        &self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<Option<HandForHand>> {
+        self.state
+            .hand_for_hand
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    /// Стол уже сыграл раздачу текущего раунда и ждёт остальных.
+    async fn waits_hand_for_hand(&self, table_id: TableId) -> OnchainResult<bool> {
+        let Some(tournament_id) = self.table_tournament_id(table_id).await? else {
+            return Ok(false);
+        };
+        Ok(self
+            .load_hand_for_hand(tournament_id)
+            .await?
+            .is_some_and(|hfh| hfh.table_done(table_id)))
+    }
+
+    /// Платных мест: до последнего ненулевого приза, у сателлита — не меньше билетов.
+    async fn paid_places(&self, tournament_id: TournamentId) -> OnchainResult<u32> {
+        let payouts = self
+            .state
+            .tournament_payouts
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+        let paid = payouts
+            .iter()
+            .rposition(|p| !p.is_zero())
+            .map_or(0, |i| i as u32 + 1);
+        let seats = self
+            .satellite_of(tournament_id)
+            .await?
+            .map_or(0, |s| s.seats);
+        Ok(paid.max(seats))
+    }
+
+    /// Включить рука-в-руку на пузыре, закрыть доигранный раунд и
+    /// раздать места его вылетевшим, выключить после пузыря.
+    async fn update_hand_for_hand(
+        &mut self,
+        tournament_id: TournamentId,
+        tournament: &Tournament,
+        hand_for_hand: Option<HandForHand>,
+        dealing_tables: &[TableId],
+        entrants: u32,
+    ) -> OnchainResult<()> {
+        let alive = tournament
+            .registrations
+            .values()
+            .filter(|r| !r.is_busted)
+            .count() as u32;
+        let paid = self.paid_places(tournament_id).await?;
+        let finished = tournament.status == TournamentStatus::Finished;
+        // Суммы по сделке зафиксированы — их не делим и пузырь не играем.
+        let deal_locked = self
+            .state
+            .locked_deals
+            .contains_key(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let Some(mut hfh) = hand_for_hand else {
+            if !finished && !deal_locked && on_bubble(alive, paid) {
+                self.state
+                    .hand_for_hand
+                    .insert(&tournament_id, HandForHand::new(remaining_stacks(tournament)))
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            }
+            return Ok(());
+        };
+
+        if !finished && !hfh.round_complete(dealing_tables) {
+            return self
+                .state
+                .hand_for_hand
+                .insert(&tournament_id, hfh)
+                .map_err(|e| OnchainError::Storage(e.to_string()));
+        }
+
+        // Равные стартовые стеки делят призы своих мест поровну,
+        // нечётные фишки — по порядку player_id.
+        for (places, players) in hfh.placements(alive) {
+            if deal_locked || players.len() == 1 {
+                for (place, player_id) in places.iter().zip(&players) {
+                    self.record_tournament_finish(tournament_id, *player_id, *place, entrants)
+                        .await?;
+                }
+                continue;
+            }
+
+            let mut total = 0u64;
+            for (place, player_id) in places.iter().zip(&players) {
+                total += self.place_prize(tournament_id, *player_id, *place).await?.0;
+            }
+            let count = players.len() as u64;
+            for (i, player_id) in players.iter().enumerate() {
+                let prize = Chips(total / count + u64::from((i as u64) < total % count));
+                self.record_finish_with_prize(tournament_id, *player_id, places[0], entrants, prize)
+                    .await?;
+            }
+        }
+
+        if finished || alive <= paid {
+            self.state
+                .hand_for_hand
+                .remove(&tournament_id)
+                .map_err(|e| OnchainError::Storage(e.to_string()))
+        } else {
+            hfh.next_round(remaining_stacks(tournament));
+            self.state
+                .hand_for_hand
+                .insert(&tournament_id, hfh)
+                .map_err(|e| OnchainError::Storage(e.to_string()))
+        }
+    }
+
+    // =====================================================================
+    //                        РАСПИСАНИЕ ТУРНИРОВ
+    // =====================================================================
+
+    async fn handle_schedule_tournament(
+        &mut self,
+        cmd: ScheduleTournamentCommand,
+    ) -> OnchainResult<CommandResponse> {
+        self.ensure_role(Role::TournamentDirector, None).await?;
+
+        let tournament = self.load_tournament(cmd.tournament_id).await?;
+        if tournament.status != TournamentStatus::Registering {
+            return Err(OnchainError::InvalidSchedule(format!(
+                "tournament {} is not registering",
+                cmd.tournament_id
+            )));
+        }
+
+        let reg_close_at = cmd.reg_close_at.unwrap_or(cmd.start_at);
+        if cmd.start_at <= self.now {
+            return Err(OnchainError::InvalidSchedule(
+                "start time is in the past".to_string(),
+            ));
+        }
+        if reg_close_at > cmd.start_at {
+            return Err(OnchainError::InvalidSchedule(
+                "registration must close no later than the start".to_string(),
+            ));
+        }
+        if cmd.min_players < 2 {
+            return Err(OnchainError::InvalidSchedule(
+                "min_players must be at least 2".to_string(),
+            ));
+        }
+
+        self.state
+            .tournament_schedules
+            .insert(
+                &cmd.tournament_id,
+                TournamentSchedule {
+                    start_at: cmd.start_at,
+                    reg_close_at,
+                    min_players: cmd.min_players,
+                    state: ScheduleState::Scheduled,
+                },
+            )
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        let table_ids = self
+            .state
+            .tournament_tables
+            .get(&cmd.tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+            .unwrap_or_default();
+
+        let view = self.build_tournament_view(&tournament, table_ids).await?;
+        Ok(CommandResponse::TournamentState(view))
+    }
+
+    /// Применить расписание: закрыть регистрацию, стартовать или
+    /// отменить турнир, если его время уже пришло.
+    async fn apply_tournament_schedule(
+        &mut self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<()> {
+        let Some(mut schedule) = self
+            .state
+            .tournament_schedules
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        let Some(mut tournament) = self
+            .state
+            .tournaments
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        if tournament.status != TournamentStatus::Registering {
+            // Директор стартовал или закрыл турнир вручную.
+            return Ok(());
+        }
+
+        let registered = tournament.registrations.len() as u32;
+        match schedule.step(self.now, registered) {
+            ScheduleStep::Nothing => return Ok(()),
+            ScheduleStep::CloseRegistration => {
+                schedule.state = ScheduleState::RegistrationClosed;
+            }
+            ScheduleStep::Start => {
+                // Расписание отмечаем только после удачного старта.
+                self.start_tournament(tournament_id).await?;
+                schedule.state = ScheduleState::Started;
+            }
+            ScheduleStep::Cancel => {
+                schedule.state = ScheduleState::Cancelled;
 
-                        if let Some(tournament_id) =
-                            self.table_tournament_id(table.id).await?
-                        {
-                            self.handle_tournament_after_hand(
-                                tournament_id,
-                                &table,
-                            )
-                            .await?;
-                        }
+                let players: Vec<PlayerId> =
+                    tournament.registrations.keys().copied().collect();
+                for player_id in players {
+                    self.refund_entry(player_id, tournament_id).await?;
+                }
 
-                        let table_view = self
-                            .build_table_view(&table, Some(&snapshot_after))
-                            .await?;
-                        map_hand_status_to_response(
-                            finished_status,
-                            table_view,
-                        )
-                    }
-                };
+                tournament.status = TournamentStatus::Finished;
+                self.state
+                    .tournaments
+                    .insert(&tournament_id, tournament)
+                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            }
+        }
 
-                Ok(response)
+        self.save_schedule(tournament_id, schedule)
+    }
+
+    fn save_schedule(
+        &mut self,
+        tournament_id: TournamentId,
+        schedule: TournamentSchedule,
+    ) -> OnchainResult<()> {
+        self.state
+            .tournament_schedules
+            .insert(&tournament_id, schedule)
+            .map_err(|e| OnchainError::Storage(e.to_string()))
+    }
+
+    async fn ensure_registration_open(&self, tournament_id: TournamentId) -> OnchainResult<()> {
+        let schedule = self
+            .state
+            .tournament_schedules
+            .get(&tournament_id)
+            .await
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        match schedule {
+            Some(s) if !s.registration_open() => {
+                Err(OnchainError::RegistrationClosed(tournament_id))
             }
+            _ => Ok(()),
         }
     }
 
@@ -643,6 +4686,16 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: TournamentCommand,
     ) -> OnchainResult<CommandResponse> {
+        let tournament_id = match &cmd {
+            TournamentCommand::CreateTournament(c) => c.tournament_id,
+            TournamentCommand::RegisterPlayer(c) => c.tournament_id,
+            TournamentCommand::UnregisterPlayer(c) => c.tournament_id,
+            TournamentCommand::StartTournament(c) => c.tournament_id,
+            TournamentCommand::AdvanceLevel(c) => c.tournament_id,
+            TournamentCommand::CloseTournament(c) => c.tournament_id,
+        };
+        self.apply_tournament_schedule(tournament_id).await?;
+
         match cmd {
             TournamentCommand::CreateTournament(c) => {
                 self.handle_create_tournament(c).await
@@ -669,7 +4722,7 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: CreateTournamentCommand,
     ) -> OnchainResult<CommandResponse> {
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TournamentDirector, None).await?;
 
         if self
             .state
@@ -716,22 +4769,31 @@ impl<'a> PokerOrchestrator<'a> {
     ) -> OnchainResult<CommandResponse> {
         let player_id = self.ensure_player_for_signer(cmd.player_id).await?;
 
+        if !cmd.display_name.is_empty() {
+            self.claim_display_name(player_id, &cmd.display_name).await?;
+        }
+
+        self.ensure_registration_open(cmd.tournament_id).await?;
+
         let mut tournament = self
             .load_tournament(cmd.tournament_id)
             .await?;
 
         tournament.register_player(player_id)?;
 
+        // Билет или бай-ин из банкролла (ошибка — до любых записей).
+        self.pay_entry(player_id, cmd.tournament_id).await?;
+
         self.state
             .tournaments
             .insert(&cmd.tournament_id, tournament.clone())
             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
-        if !cmd.display_name.is_empty() {
-            self.state
-                .player_names
-                .insert(&player_id, cmd.display_name.clone())
-                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        if let Some(response) = self
+            .start_sng_if_full(cmd.tournament_id, &tournament)
+            .await?
+        {
+            return Ok(response);
         }
 
         let table_ids = self
@@ -778,6 +4840,8 @@ impl<'a> PokerOrchestrator<'a> {
             .into());
         }
 
+        self.refund_entry(player_id, cmd.tournament_id).await?;
+
         self.state
             .tournaments
             .insert(&cmd.tournament_id, tournament.clone())
@@ -800,35 +4864,57 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: StartTournamentCommand,
     ) -> OnchainResult<CommandResponse> {
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TournamentDirector, None).await?;
+        self.start_tournament(cmd.tournament_id).await
+    }
 
+    /// Рассадить игроков и перевести турнир в Running.
+    /// Авторизацию делает вызывающий (директор или авто-старт SNG).
+    async fn start_tournament(
+        &mut self,
+        tournament_id: TournamentId,
+    ) -> OnchainResult<CommandResponse> {
         let mut tournament = self
-            .load_tournament(cmd.tournament_id)
+            .load_tournament(tournament_id)
             .await?;
 
         let config = &tournament.config;
         let max_seats = config.table_size;
 
         // Все зарегистрированные игроки.
-        let registrations = tournament.registrations.clone();
-        let mut player_ids: Vec<PlayerId> =
-            registrations.keys().cloned().collect();
-        player_ids.sort_unstable();
+        let player_ids: Vec<PlayerId> =
+            tournament.registrations.keys().cloned().collect();
+
+        // Минимум столов с равной рассадкой; меньше двух за столом — не стартуем.
+        let sizes = table_sizes(player_ids.len(), max_seats)
+            .map_err(OnchainError::InvalidTournamentSeating)?;
+
+        // Простая схема: кодируем table_id из tournament_id + локального индекса.
+        let tables: Vec<(TableId, usize)> = sizes
+            .into_iter()
+            .enumerate()
+            .map(|(idx, size)| {
+                (((tournament_id as u64) << 32 | (idx as u64)) as TableId, size)
+            })
+            .collect();
+        let new_table_ids: Vec<TableId> = tables.iter().map(|(id, _)| *id).collect();
+
+        // Высота блока, время и состав участников при регистрации неизвестны.
+        let entropy = StartEntropy::new(
+            *self.state.base_seed.get(),
+            self.block_height.0,
+            self.now,
+            player_ids.clone(),
+        );
 
-        let mut new_table_ids = Vec::new();
-        let mut tables_to_insert = Vec::new();
+        // Жеребьёвку можно перепроверить по записанным входным данным.
+        let seed = entropy.seed(tournament_id, SEAT_DRAW_SALT);
+        let draw = draw_seats(&player_ids, &tables, max_seats, seed);
 
-        let mut chunk_index: u32 = 0;
-        for chunk in player_ids.chunks(max_seats as usize) {
-            if chunk.is_empty() {
-                continue;
-            }
+        let mut tables_to_insert = Vec::new();
 
-            // Простая схема: кодируем table_id из tournament_id + локального индекса.
-            let table_id: TableId =
-                ((cmd.tournament_id as u64) << 32 | (chunk_index as u64))
-                    as TableId;
-            chunk_index += 1;
+        for (chunk_index, table_draw) in draw.iter().enumerate() {
+            let table_id = table_draw.table_id;
 
             let stakes =
                 stakes_for_tournament_level(config, tournament.current_level);
@@ -843,30 +4929,76 @@ impl<'a> PokerOrchestrator<'a> {
 
             let mut table = Table::new(
                 table_id,
-                format!("T#{}/{}", cmd.tournament_id, chunk_index),
+                format!("T#{}/{}", tournament_id, chunk_index + 1),
                 table_config,
             );
 
-            for (seat_idx, pid) in chunk.iter().enumerate() {
+            for (seat_idx, pid) in &table_draw.seats {
                 if let Some(reg) = tournament.registrations.get_mut(pid) {
                     let stack = reg.total_chips;
 
                     reg.table_id = Some(table_id);
-                    reg.seat_index = Some(seat_idx as SeatIndex);
+                    reg.seat_index = Some(*seat_idx);
 
                     let pat = PlayerAtTable::new(*pid, stack);
-                    if let Some(slot) = table.seats.get_mut(seat_idx) {
+                    if let Some(slot) = table.seats.get_mut(*seat_idx as usize) {
                         *slot = Some(pat);
                     }
                 }
             }
 
-            new_table_ids.push(table_id);
+            // Движок двигает кнопку в начале раздачи, поэтому ставим её на
+            // предыдущее занятое место — в первой раздаче она окажется на
+            // разыгранном.
+            let occupied: Vec<SeatIndex> = table_draw.seats.iter().map(|(s, _)| *s).collect();
+            table.dealer_button = occupied
+                .iter()
+                .position(|s| *s == table_draw.button)
+                .map(|i| occupied[(i + occupied.len() - 1) % occupied.len()]);
+
             tables_to_insert.push(table);
         }
 
-        for table in tables_to_insert.into_iter() {
+        // Переводим турнир в Running через доменный метод и считаем фонд
+        // до записи столов: ошибка здесь не должна оставить полстарта.
+        // now_ts = 0 (для dev/теста); при реальном запуске можно прокинуть реальное время.
+        tournament.start(0)?;
+        let entrants = tournament.registrations.len() as u32;
+        self.settle_prize_pool(tournament_id, entrants, &entropy)
+            .await?;
+
+        self.state
+            .seat_draws
+            .insert(
+                &tournament_id,
+                SeatDraw {
+                    seed,
+                    inputs: entropy.clone(),
+                    drawn_at: self.now,
+                    tables: draw,
+                },
+            )
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        // Пул цепей для столов: пустой — всё остаётся на координаторе.
+        let chain_pool = self.state.table_chain_pool.get().clone();
+
+        for (idx, table) in tables_to_insert.into_iter().enumerate() {
             let id = table.id;
+
+            if !chain_pool.is_empty() {
+                let chain = chain_pool[idx % chain_pool.len()];
+                if chain != self.chain_id {
+                    self.open_table_on_chain(
+                        chain,
+                        table.clone(),
+                        Some(tournament_id),
+                        TableRules::default(),
+                    )
+                    .await?;
+                }
+            }
+
             self.state
                 .tables
                 .insert(&id, table)
@@ -879,22 +5011,23 @@ impl<'a> PokerOrchestrator<'a> {
 
             self.state
                 .table_tournament
-                .insert(&id, cmd.tournament_id)
+                .insert(&id, tournament_id)
                 .map_err(|e| OnchainError::Storage(e.to_string()))?;
         }
 
-        // Переводим турнир в Running через доменный метод.
-        // now_ts = 0 (для dev/теста); при реальном запуске можно прокинуть реальное время.
-        tournament.start(0)?;
-
         self.state
             .tournaments
-            .insert(&cmd.tournament_id, tournament.clone())
+            .insert(&tournament_id, tournament.clone())
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+
+        self.state
+            .tournament_level_started
+            .insert(&tournament_id, self.now)
             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
         self.state
             .tournament_tables
-            .insert(&cmd.tournament_id, new_table_ids.clone())
+            .insert(&tournament_id, new_table_ids.clone())
             .map_err(|e| OnchainError::Storage(e.to_string()))?;
 
         let view =
@@ -907,7 +5040,7 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: AdvanceLevelCommand,
     ) -> OnchainResult<CommandResponse> {
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TournamentDirector, None).await?;
 
         let mut tournament = self
             .load_tournament(cmd.tournament_id)
@@ -923,6 +5056,14 @@ impl<'a> PokerOrchestrator<'a> {
             .is_some()
         {
             tournament.current_level = next_level;
+            self.state
+                .tournament_level_started
+                .insert(&cmd.tournament_id, self.now)
+                .map_err(|e| OnchainError::Storage(e.to_string()))?;
+            self.emit(PokerEvent::LevelChanged {
+                tournament_id: cmd.tournament_id,
+                level: next_level,
+            });
         } else {
             // Нет следующего уровня – просто возвращаем текущее состояние.
         }
@@ -949,6 +5090,16 @@ impl<'a> PokerOrchestrator<'a> {
                 if table.config.table_type == TableType::Tournament {
                     table.config.stakes = stakes.clone();
                     self.save_table(table)?;
+
+                    if let Some(chain) = self.remote_chain_of(table_id).await? {
+                        self.outgoing.push((
+                            chain,
+                            Message::UpdateStakes {
+                                table_id,
+                                stakes: stakes.clone(),
+                            },
+                        ));
+                    }
                 }
             }
         }
@@ -966,7 +5117,7 @@ impl<'a> PokerOrchestrator<'a> {
         &mut self,
         cmd: CloseTournamentCommand,
     ) -> OnchainResult<CommandResponse> {
-        self.ensure_admin().await?;
+        self.ensure_role(Role::TournamentDirector, None).await?;
 
         let mut tournament = self
             .load_tournament(cmd.tournament_id)
@@ -1002,7 +5153,7 @@ impl<'a> PokerOrchestrator<'a> {
     async fn handle_tournament_after_hand(
         &mut self,
         tournament_id: TournamentId,
-        _table: &Table,
+        finished_table: &Table,
     ) -> OnchainResult<()> {
         // 1. Загружаем турнир и проверяем статус.
         let mut tournament = self.load_tournament(tournament_id).await?;
@@ -1087,6 +5238,15 @@ impl<'a> PokerOrchestrator<'a> {
         }
 
         // 6. Отмечаем bust в Tournament + убираем игроков со столов.
+        //    Место вылетевшего = число живых игроков до его вылета.
+        let entrants = tournament.registrations.len() as u32;
+        let mut alive = tournament
+            .registrations
+            .values()
+            .filter(|r| !r.is_busted)
+            .count() as u32;
+        let mut finishes: Vec<(PlayerId, u32)> = Vec::new();
+
         for player_id in busted_candidates.into_iter() {
             // Убираем игрока со стола, если он там ещё числится.
             if let Some((tid, seat, _stack)) = player_locations.get(&player_id).copied() {
@@ -1098,19 +5258,48 @@ impl<'a> PokerOrchestrator<'a> {
             }
 
             // Помечаем вылет в доменной модели турнира.
-            if let Err(err) = tournament.mark_player_busted(player_id) {
-                match err {
-                    // Защитный кейс: домен не даёт выбить последнего живого игрока.
-                    TournamentError::CannotBustLastPlayer { .. } => {
-                        // Просто игнорируем этот конкретный вызов.
-                    }
-                    other => {
-                        return Err(OnchainError::Tournament(other));
-                    }
+            match tournament.mark_player_busted(player_id) {
+                Ok(_) => {
+                    finishes.push((player_id, alive));
+                    alive = alive.saturating_sub(1);
+                }
+                // Защитный кейс: домен не даёт выбить последнего живого игрока.
+                Err(TournamentError::CannotBustLastPlayer { .. }) => {
+                    // Просто игнорируем этот конкретный вызов.
                 }
+                Err(other) => {
+                    return Err(OnchainError::Tournament(other));
+                }
+            }
+        }
+
+        // Турнир завершился — последний живой игрок занимает первое место.
+        if tournament.status == TournamentStatus::Finished {
+            let mut survivors = tournament
+                .registrations
+                .iter()
+                .filter(|(_, r)| !r.is_busted)
+                .map(|(pid, _)| *pid);
+            if let (Some(winner), None) = (survivors.next(), survivors.next()) {
+                finishes.push((winner, 1));
             }
         }
 
+        // Рука-в-руку: места вылетевших ждут конца раунда.
+        let mut hand_for_hand = self.load_hand_for_hand(tournament_id).await?;
+        if let Some(hfh) = hand_for_hand.as_mut() {
+            hfh.mark_done(finished_table.id);
+            let (winner, busted): (Vec<_>, Vec<_>) =
+                finishes.into_iter().partition(|(_, place)| *place == 1);
+            hfh.busted.extend(busted.into_iter().map(|(pid, _)| pid));
+            finishes = winner;
+        }
+
+        for (player_id, place) in finishes {
+            self.record_tournament_finish(tournament_id, player_id, place, entrants)
+                .await?;
+        }
+
         // После возможных вылетов домен сам проверит,
         // не нужно ли завершить турнир (check_and_finish_if_needed внутри).
 
@@ -1176,34 +5365,36 @@ impl<'a> PokerOrchestrator<'a> {
 
         // 8. Чистим пустые столы и сохраняем обновлённые.
         let mut new_table_ids: Vec<TableId> = Vec::new();
+        let mut dealing_tables: Vec<TableId> = Vec::new();
 
         for (tid, table) in tables.into_iter() {
             if table.seated_count() == 0 {
                 // Полностью пустой стол — убираем из стораджа и индексов турнира.
-                self.state
-                    .tables
-                    .remove(&tid)
-                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
-                self.state
-                    .active_hands
-                    .remove(&tid)
-                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
+                self.remove_table_everywhere(tid)?;
                 self.state
                     .table_tournament
                     .remove(&tid)
                     .map_err(|e| OnchainError::Storage(e.to_string()))?;
-                self.state
-                    .time_controllers
-                    .remove(&tid)
-                    .map_err(|e| OnchainError::Storage(e.to_string()))?;
                 continue;
             }
 
             // Стол живой — сохраняем его обратно.
+            if eligible_players_count(&table) >= 2 {
+                dealing_tables.push(tid);
+            }
             self.save_table(table)?;
             new_table_ids.push(tid);
         }
 
+        self.update_hand_for_hand(
+            tournament_id,
+            &tournament,
+            hand_for_hand,
+            &dealing_tables,
+            entrants,
+        )
+        .await?;
+
         // 9. Обновляем mapping: турнир → его столы.
         self.state
             .tournament_tables
@@ -1224,6 +5415,32 @@ impl<'a> PokerOrchestrator<'a> {
     //                               HELPERS
     // =====================================================================
 
+    /// Записать событие в event_log и отложить для Linera-стрима.
+    fn emit(&mut self, event: PokerEvent) {
+        self.state.event_log.push(EventRecord {
+            at: self.now,
+            event: event.clone(),
+        });
+        self.events.push(event);
+    }
+
+    /// Если после действия открылась новая улица — событие StreetDealt.
+    fn emit_street_if_dealt(
+        &mut self,
+        table: &Table,
+        hand_id: HandId,
+        board_before: usize,
+    ) {
+        if table.board.len() > board_before {
+            self.emit(PokerEvent::StreetDealt {
+                table_id: table.id,
+                hand_id,
+                street: table.street,
+                board: table.board.clone(),
+            });
+        }
+    }
+
     async fn load_table(&self, id: TableId) -> OnchainResult<Table> {
         self.state
             .tables
@@ -1440,6 +5657,155 @@ impl<'a> PokerOrchestrator<'a> {
     }
 }
 
+/// Карты в виде (ранг, масть); нечитаемая карта — ошибка, а не пропуск.
+fn readable_cards(cards: &[Card]) -> OnchainResult<Vec<RankSuit>> {
+    cards
+        .iter()
+        .map(|card| {
+            rank_suit(card).ok_or_else(|| {
+                OnchainError::VariantNotSupported(format!(
+                    "unreadable card {}",
+                    serde_json::to_string(card).unwrap_or_default()
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Доложить каждому игроку раздачи `count` закрытых карт из колоды движка.
+///
+/// API колоды закрыт, поэтому работаем через serde: снимаем карты
+/// с конца `deck.cards` и дописываем их в `hole_cards` игроков.
+/// Снятые карты из колоды пропадают, так что на борд они не попадут.
+fn deal_extra_hole_cards(
+    table: &mut Table,
+    engine: &mut HandEngine,
+    count: usize,
+) -> OnchainResult<()> {
+    let unsupported = |what: &str| OnchainError::VariantNotSupported(what.to_string());
+
+    let mut deck = serde_json::to_value(&engine.deck)
+        .map_err(|e| OnchainError::Storage(e.to_string()))?;
+    let cards = deck
+        .get_mut("cards")
+        .and_then(|c| c.as_array_mut())
+        .ok_or_else(|| unsupported("engine deck has no `cards` list"))?;
+
+    for slot in table.seats.iter_mut() {
+        let Some(player) = slot else {
+            continue;
+        };
+        let mut value = serde_json::to_value(&*player)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        let hole = match value.get_mut("hole_cards") {
+            Some(JsonValue::Array(hole)) => hole,
+            // null — игрок не участвует в раздаче.
+            Some(JsonValue::Null) => continue,
+            _ => return Err(unsupported("player has no `hole_cards` list")),
+        };
+        for _ in 0..count {
+            hole.push(cards.pop().ok_or_else(|| unsupported("deck is exhausted"))?);
+        }
+        *player = serde_json::from_value(value)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+    }
+
+    engine.deck =
+        serde_json::from_value(deck).map_err(|e| OnchainError::Storage(e.to_string()))?;
+    Ok(())
+}
+
+/// Убрать из раздачи карты младше `min_rank` (шорт-дек).
+///
+/// Колода движка всегда полная: выкидываем младшие карты из
+/// `deck.cards`, а такие же карты на руках меняем на верхние
+/// из оставшейся (уже перемешанной) колоды.
+fn strip_low_cards(
+    table: &mut Table,
+    engine: &mut HandEngine,
+    min_rank: u8,
+) -> OnchainResult<()> {
+    let unsupported = |what: &str| OnchainError::VariantNotSupported(what.to_string());
+    let is_low = |v: &JsonValue| -> OnchainResult<bool> {
+        serde_json::from_value::<Card>(v.clone())
+            .ok()
+            .and_then(|c| rank_suit(&c))
+            .map(|(rank, _)| rank < min_rank)
+            .ok_or_else(|| unsupported("engine card has no readable rank"))
+    };
+
+    let mut deck = serde_json::to_value(&engine.deck)
+        .map_err(|e| OnchainError::Storage(e.to_string()))?;
+    let cards = deck
+        .get_mut("cards")
+        .and_then(|c| c.as_array_mut())
+        .ok_or_else(|| unsupported("engine deck has no `cards` list"))?;
+    let mut kept = Vec::with_capacity(cards.len());
+    for card in cards.drain(..) {
+        if !is_low(&card)? {
+            kept.push(card);
+        }
+    }
+    *cards = kept;
+
+    for slot in table.seats.iter_mut() {
+        let Some(player) = slot else {
+            continue;
+        };
+        let mut value = serde_json::to_value(&*player)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+        let hole = match value.get_mut("hole_cards") {
+            Some(JsonValue::Array(hole)) => hole,
+            Some(JsonValue::Null) => continue,
+            _ => return Err(unsupported("player has no `hole_cards` list")),
+        };
+        for card in hole.iter_mut() {
+            if is_low(card)? {
+                *card = cards.pop().ok_or_else(|| unsupported("deck is exhausted"))?;
+            }
+        }
+        *player = serde_json::from_value(value)
+            .map_err(|e| OnchainError::Storage(e.to_string()))?;
+    }
+
+    engine.deck =
+        serde_json::from_value(deck).map_err(|e| OnchainError::Storage(e.to_string()))?;
+    Ok(())
+}
+
+/// Анте с баттона: снимаем со стека и засчитываем во вклад игрока.
+/// Движок этого анте не видит, поэтому банк потом делит
+/// `settle_variant_showdown`.
+fn post_button_ante(
+    table: &mut Table,
+    engine: &mut HandEngine,
+    ante: Chips,
+) -> OnchainResult<()> {
+    let unsupported = |what: &str| OnchainError::VariantNotSupported(what.to_string());
+    let button = table
+        .dealer_button
+        .ok_or_else(|| unsupported("hand started without a dealer button"))?;
+    let Some(Some(player)) = table.seats.get_mut(button as usize) else {
+        return Err(unsupported("dealer button is on an empty seat"));
+    };
+    let posted = ante.0.min(player.stack.0);
+    player.stack = Chips(player.stack.0 - posted);
+    *engine.contributions.entry(button).or_insert(Chips::ZERO) += Chips(posted);
+    Ok(())
+}
+
+/// Максимальная длина ссылки на аватар в профиле.
+const MAX_AVATAR_URL_LEN: usize = 256;
+
+/// Банк раздачи = сумма всех вложений игроков.
+fn hand_pot(snapshot: &HandEngineSnapshot) -> Chips {
+    let mut pot = Chips::ZERO;
+    for amount in snapshot.contributions.values() {
+        pot += *amount;
+    }
+    pot
+}
+
 /// Маппинг внешнего ante-типа из API в доменную модель.
 fn map_ante_type(api: AnteTypeApi) -> AnteType {
     match api {
@@ -1449,6 +5815,105 @@ fn map_ante_type(api: AnteTypeApi) -> AnteType {
     }
 }
 
+/// Собрать cash-стол из команды создания.
+fn build_cash_table(cmd: CreateTableCommand) -> Table {
+    let stakes = TableStakes::new(
+        cmd.small_blind,
+        cmd.big_blind,
+        map_ante_type(cmd.ante_type),
+        cmd.ante,
+    );
+
+    let config = TableConfig {
+        max_seats: cmd.max_seats,
+        table_type: TableType::Cash,
+        stakes,
+        allow_straddle: false,
+        allow_run_it_twice: false,
+    };
+
+    Table::new(cmd.table_id, cmd.name, config)
+}
+
+/// Рассадка от координатора поверх текущей: состав и места — из `incoming`,
+/// а у игроков, которые уже сидят за столом, остаётся живой стек (зеркало
+/// координатора может отставать на раздачу).
+fn merge_seats(
+    current: &[Option<PlayerAtTable>],
+    incoming: Vec<Option<PlayerAtTable>>,
+) -> Vec<Option<PlayerAtTable>> {
+    incoming
+        .into_iter()
+        .map(|slot| {
+            slot.map(|player| {
+                current
+                    .iter()
+                    .flatten()
+                    .find(|p| p.player_id == player.player_id)
+                    .cloned()
+                    .unwrap_or(player)
+            })
+        })
+        .collect()
+}
+
+/// Стеки по местам — для отчётов координатору.
+fn seat_stacks(table: &Table) -> Vec<(SeatIndex, PlayerId, Chips)> {
+    table
+        .seats
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, s)| {
+            s.as_ref().map(|p| (idx as SeatIndex, p.player_id, p.stack))
+        })
+        .collect()
+}
+
+/// Рассадка стола (кто где сидит) — чтобы понять, изменилась ли она.
+fn seating(table: &Table) -> Vec<Option<PlayerId>> {
+    table
+        .seats
+        .iter()
+        .map(|s| s.as_ref().map(|p| p.player_id))
+        .collect()
+}
+
+/// Сколько игроков за столом могут играть следующую раздачу: есть фишки
+/// и игрок не в sit-out.
+fn eligible_players_count(table: &Table) -> usize {
+    table
+        .seats
+        .iter()
+        .filter_map(|s| s.as_ref())
+        .filter(|p| !p.stack.is_zero() && !is_sitting_out(p))
+        .count()
+}
+
+fn next_auto_deal_time(now: Timestamp, delay_secs: u64) -> Timestamp {
+    now.saturating_add(TimeDelta::from_secs(delay_secs))
+}
+
+/// Пора ли сдавать авто-раздачу в момент `now`. Когда за столом набралось
+/// двое, запускает отсчёт паузы; когда играть некому или раздача
+/// стартует — сбрасывает `next_hand_at`.
+fn auto_deal_due(cfg: &mut AutoDealConfig, table: &Table, now: Timestamp) -> bool {
+    if eligible_players_count(table) < 2 {
+        cfg.next_hand_at = None;
+        return false;
+    }
+
+    let delay_secs = cfg.delay_secs;
+    let due_at = *cfg
+        .next_hand_at
+        .get_or_insert_with(|| next_auto_deal_time(now, delay_secs));
+    if now < due_at {
+        return false;
+    }
+
+    cfg.next_hand_at = None;
+    true
+}
+
 /// Вытянуть стейки для уровня турнира из BlindStructure.
 fn stakes_for_tournament_level(
     config: &TournamentConfig,
@@ -1469,3 +5934,285 @@ fn stakes_for_tournament_level(
         blind.ante,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_with_players(count: u8) -> Table {
+        let config = TableConfig {
+            max_seats: 6,
+            table_type: TableType::Cash,
+            stakes: TableStakes::new(Chips(5), Chips(10), AnteType::None, Chips::ZERO),
+            allow_straddle: false,
+            allow_run_it_twice: false,
+        };
+        let mut table = Table::new(1, "Test".to_string(), config);
+        for seat in 0..count {
+            table.seats[seat as usize] =
+                Some(PlayerAtTable::new(u64::from(seat) + 1, Chips(1_000)));
+        }
+        table
+    }
+
+    fn start(table: &mut Table) -> HandEngine {
+        let mut rng = RngSeed::from_u64(7).to_rng();
+        engine::start_hand(table, &mut rng, 1).expect("hand starts")
+    }
+
+    fn hole_ranks(table: &Table) -> Vec<Vec<u8>> {
+        table
+            .seats
+            .iter()
+            .flatten()
+            .map(|player| {
+                let value = serde_json::to_value(player).expect("player serializes");
+                serde_json::from_value::<Vec<Card>>(value["hole_cards"].clone())
+                    .expect("player holds cards")
+                    .iter()
+                    .map(|c| rank_suit(c).expect("readable card").0)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn deck_ranks(engine: &HandEngine) -> Vec<u8> {
+        let deck = serde_json::to_value(&engine.deck).expect("deck serializes");
+        serde_json::from_value::<Vec<Card>>(deck["cards"].clone())
+            .expect("deck has cards")
+            .iter()
+            .map(|c| rank_suit(c).expect("readable card").0)
+            .collect()
+    }
+
+    /// Раскладку `deck.cards` и `hole_cards`, а также запись карт в
+    /// `test_support::card`, сверяем с настоящей сериализацией движка.
+    #[test]
+    fn engine_serde_layout_round_trips() {
+        const RANKS: &[u8; 13] = b"23456789TJQKA";
+        const SUITS: &[u8; 4] = b"shdc";
+
+        let mut table = table_with_players(3);
+        let engine = start(&mut table);
+
+        let deck = serde_json::to_value(&engine.deck).expect("deck serializes");
+        let mut dealt: Vec<Card> =
+            serde_json::from_value(deck["cards"].clone()).expect("deck has `cards`");
+        assert!(!dealt.is_empty());
+        for player in table.seats.iter().flatten() {
+            let value = serde_json::to_value(player).expect("player serializes");
+            let hole: Vec<Card> = serde_json::from_value(value["hole_cards"].clone())
+                .expect("player has `hole_cards`");
+            assert_eq!(hole.len(), 2);
+            assert_eq!(hole_cards(player).map(|h| h.len()), Some(2));
+            dealt.extend(hole);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for engine_card in &dealt {
+            let (rank, suit) = rank_suit(engine_card).expect("readable card");
+            assert!(seen.insert((rank, suit)), "card dealt twice");
+
+            let text = format!(
+                "{}{}",
+                RANKS[usize::from(rank - 2)] as char,
+                SUITS[usize::from(suit)] as char
+            );
+            assert_eq!(
+                serde_json::to_value(crate::test_support::card(&text)).expect("serializes"),
+                serde_json::to_value(engine_card).expect("serializes"),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn short_deck_strips_low_cards_everywhere() {
+        let mut table = table_with_players(6);
+        let mut engine = start(&mut table);
+        strip_low_cards(&mut table, &mut engine, 6).expect("engine fields present");
+
+        let hands = hole_ranks(&table);
+        assert_eq!(hands.len(), 6);
+        assert!(hands.iter().all(|h| h.len() == 2 && h.iter().all(|r| *r >= 6)));
+
+        // 36 карт шорт-дека минус 12 на руках.
+        let deck = deck_ranks(&engine);
+        assert_eq!(deck.len(), 36 - 12);
+        assert!(deck.iter().all(|r| *r >= 6));
+    }
+
+    #[test]
+    fn omaha_deals_four_hole_cards() {
+        let mut table = table_with_players(3);
+        let mut engine = start(&mut table);
+        let before = deck_ranks(&engine).len();
+        deal_extra_hole_cards(&mut table, &mut engine, 2).expect("engine fields present");
+
+        assert!(hole_ranks(&table).iter().all(|h| h.len() == 4));
+        assert_eq!(deck_ranks(&engine).len(), before - 6);
+    }
+
+    #[test]
+    fn button_ante_needs_a_button() {
+        let mut table = table_with_players(2);
+        let mut engine = start(&mut table);
+        table.dealer_button = None;
+        assert!(matches!(
+            post_button_ante(&mut table, &mut engine, Chips(10)),
+            Err(OnchainError::VariantNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn button_ante_is_added_to_the_button_contribution() {
+        let mut table = table_with_players(3);
+        let mut engine = start(&mut table);
+        let button = table.dealer_button.expect("engine moves the button");
+        let stack = table.seats[button as usize].as_ref().map(|p| p.stack);
+        let contributed = engine.contributions.get(&button).copied().unwrap_or(Chips::ZERO);
+
+        post_button_ante(&mut table, &mut engine, Chips(10)).expect("button is seated");
+
+        assert_eq!(
+            table.seats[button as usize].as_ref().map(|p| p.stack.0),
+            stack.map(|s| s.0 - 10)
+        );
+        assert_eq!(engine.contributions[&button].0, contributed.0 + 10);
+    }
+
+    #[test]
+    fn readable_cards_keep_every_card_in_order() {
+        let deck = crate::test_support::full_deck();
+        let read = readable_cards(&deck).expect("engine cards are readable");
+        assert_eq!(read.len(), deck.len());
+        assert_eq!(
+            readable_cards(&crate::test_support::cards("Ah Kd")).expect("readable"),
+            vec![(14, 1), (13, 2)]
+        );
+    }
+
+    fn auto_deal(delay_secs: u64, next_hand_at: Option<Timestamp>) -> AutoDealConfig {
+        AutoDealConfig {
+            enabled: true,
+            delay_secs,
+            next_hand_at,
+        }
+    }
+
+    fn sit_out(table: &mut Table, seat: usize) {
+        if let Some(player) = table.seats[seat].as_mut() {
+            player.status = serde_json::from_value(serde_json::json!("SittingOut"))
+                .expect("engine has a sit-out status");
+        }
+        assert!(table.seats[seat].as_ref().is_some_and(is_sitting_out));
+    }
+
+    #[test]
+    fn auto_deal_waits_out_the_delay_then_deals() {
+        let table = table_with_players(2);
+        let start = Timestamp::from(1_000_000);
+        let mut cfg = auto_deal(30, None);
+
+        assert!(!auto_deal_due(&mut cfg, &table, start));
+        let due_at = Timestamp::from(31_000_000);
+        assert_eq!(cfg.next_hand_at, Some(due_at));
+
+        assert!(!auto_deal_due(&mut cfg, &table, Timestamp::from(30_999_999)));
+        assert_eq!(cfg.next_hand_at, Some(due_at));
+
+        assert!(auto_deal_due(&mut cfg, &table, due_at));
+        assert_eq!(cfg.next_hand_at, None);
+    }
+
+    #[test]
+    fn auto_deal_without_delay_deals_at_once() {
+        let table = table_with_players(2);
+        let mut cfg = auto_deal(0, None);
+        assert!(auto_deal_due(&mut cfg, &table, Timestamp::from(5)));
+        assert_eq!(cfg.next_hand_at, None);
+    }
+
+    #[test]
+    fn auto_deal_pauses_without_two_active_players() {
+        let mut table = table_with_players(3);
+        sit_out(&mut table, 0);
+        if let Some(player) = table.seats[1].as_mut() {
+            player.stack = Chips::ZERO;
+        }
+        assert_eq!(eligible_players_count(&table), 1);
+
+        let mut cfg = auto_deal(30, Some(Timestamp::from(10)));
+        assert!(!auto_deal_due(&mut cfg, &table, Timestamp::from(20)));
+        assert_eq!(cfg.next_hand_at, None);
+    }
+
+    #[test]
+    fn sitting_out_players_are_not_eligible() {
+        let mut table = table_with_players(3);
+        assert_eq!(eligible_players_count(&table), 3);
+        sit_out(&mut table, 2);
+        assert_eq!(eligible_players_count(&table), 2);
+    }
+
+    fn chain(digit: char) -> ChainId {
+        digit.to_string().repeat(64).parse().expect("valid chain id")
+    }
+
+    #[test]
+    fn forwarded_seat_rejection_refunds_the_player_chain() {
+        let coordinator = chain('a');
+        let player_chain = chain('b');
+        let replies = PokerOrchestrator::rejection_replies(
+            coordinator,
+            false,
+            Message::SeatForwarded {
+                table_id: 1,
+                player_id: 7,
+                player_chain,
+                seat_index: 0,
+                buy_in: Chips(500),
+                display_name: String::new(),
+            },
+            "table 1 is not a cash table".to_string(),
+        );
+
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].0, player_chain);
+        assert!(matches!(
+            replies[0].1,
+            Message::SeatRejected {
+                player_id: 7,
+                refund: Chips(500),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn only_player_messages_carry_the_signer() {
+        let request = Message::SeatRequest {
+            table_id: 1,
+            player_id: 7,
+            seat_index: 0,
+            buy_in: Chips(500),
+            display_name: String::new(),
+        };
+        let forwarded = Message::SeatForwarded {
+            table_id: 1,
+            player_id: 7,
+            player_chain: chain('b'),
+            seat_index: 0,
+            buy_in: Chips(500),
+            display_name: String::new(),
+        };
+
+        assert!(request.is_authenticated());
+        assert!(!forwarded.is_authenticated());
+        assert!(!Message::CashOutRequest {
+            table_id: 1,
+            player_id: 7,
+        }
+        .is_authenticated());
+    }
+}
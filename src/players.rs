@@ -0,0 +1,153 @@
+//! Профили игроков и их статистика за всё время.
+//!
+//! Профиль создаётся при первом появлении игрока (первая раздача или
+//! явное обновление профиля). Статистика копится при архивировании
+//! каждой раздачи (`PlayerStats::record_hand`) и по итогам турниров
+//! (`PlayerStats::record_tournament_finish`).
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::hand::Street;
+use poker_engine::engine::actions::PlayerActionKind;
+
+use crate::hand_archive::{ArchivedHand, ArchivedSeat};
+
+/// Настройки клиента, которые игрок хранит on-chain.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerPreferences {
+    /// Не показывать проигравшие руки на шоудауне.
+    pub auto_muck: bool,
+    pub four_color_deck: bool,
+    /// Авто-пополнение стека до бай-ина на cash-столах.
+    pub auto_rebuy: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub avatar_url: Option<String>,
+    /// Код страны ISO 3166-1 alpha-2.
+    pub country: Option<String>,
+    pub created_at: Timestamp,
+    pub preferences: PlayerPreferences,
+}
+
+impl PlayerProfile {
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            avatar_url: None,
+            country: None,
+            created_at: now,
+            preferences: PlayerPreferences::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub hands_played: u64,
+    /// Раздачи, где игрок добровольно вложился префлоп (VPIP).
+    pub vpip_hands: u64,
+    /// Раздачи с рейзом префлоп (PFR).
+    pub pfr_hands: u64,
+    /// Ставки и рейзы на всех улицах (числитель AF).
+    pub aggressive_actions: u64,
+    /// Коллы на всех улицах (знаменатель AF).
+    pub calls: u64,
+    pub showdowns: u64,
+    pub showdowns_won: u64,
+    /// Чистый результат в фишках на cash-столах.
+    pub net_cash: i64,
+    /// Чистый результат в турнирных фишках.
+    pub net_tournament: i64,
+    pub tournaments_played: u64,
+    /// Финиши в призах.
+    pub tournament_cashes: u64,
+    pub tournament_winnings: Chips,
+}
+
+impl PlayerStats {
+    /// Учесть раздачу для игрока на месте `seat`.
+    pub fn record_hand(&mut self, hand: &ArchivedHand, seat: &ArchivedSeat) {
+        self.hands_played += 1;
+
+        let actions = hand
+            .actions
+            .iter()
+            .filter(|a| a.player_id == seat.player_id);
+
+        let mut vpip = false;
+        let mut pfr = false;
+        for a in actions {
+            let preflop = matches!(a.street, Street::Preflop);
+            match a.kind {
+                PlayerActionKind::Call => {
+                    self.calls += 1;
+                    vpip |= preflop;
+                }
+                PlayerActionKind::Bet(_)
+                | PlayerActionKind::Raise(_)
+                | PlayerActionKind::AllIn => {
+                    self.aggressive_actions += 1;
+                    vpip |= preflop;
+                    pfr |= preflop;
+                }
+                PlayerActionKind::Fold | PlayerActionKind::Check => {}
+            }
+        }
+        self.vpip_hands += vpip as u64;
+        self.pfr_hands += pfr as u64;
+
+        if seat.showdown_cards.is_some() {
+            self.showdowns += 1;
+            if hand.winners.iter().any(|w| w.player_id == seat.player_id) {
+                self.showdowns_won += 1;
+            }
+        }
+
+        let net = seat.final_stack.0 as i64 - seat.starting_stack.0 as i64;
+        if hand.tournament_id.is_some() {
+            self.net_tournament += net;
+        } else {
+            self.net_cash += net;
+        }
+    }
+
+    /// Учесть финиш в турнире (`prize` — ноль, если вне призов).
+    pub fn record_tournament_finish(&mut self, prize: Chips) {
+        self.tournaments_played += 1;
+        if !prize.is_zero() {
+            self.tournament_cashes += 1;
+            self.tournament_winnings += prize;
+        }
+    }
+
+    /// VPIP в процентах.
+    pub fn vpip(&self) -> f64 {
+        percent(self.vpip_hands, self.hands_played)
+    }
+
+    /// PFR в процентах.
+    pub fn pfr(&self) -> f64 {
+        percent(self.pfr_hands, self.hands_played)
+    }
+
+    /// Aggression factor = (ставки + рейзы) / коллы.
+    /// None — коллов ещё не было.
+    pub fn aggression_factor(&self) -> Option<f64> {
+        (self.calls > 0).then(|| self.aggressive_actions as f64 / self.calls as f64)
+    }
+
+    /// Доля выигранных шоудаунов, в процентах.
+    pub fn showdown_win_rate(&self) -> f64 {
+        percent(self.showdowns_won, self.showdowns)
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    part as f64 * 100.0 / total as f64
+}
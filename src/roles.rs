@@ -0,0 +1,126 @@
+//! Роли и журнал их изменений.
+//!
+//! Владелец приложения (`PokerState::owner`) может всё и роль не хранит.
+//! Остальные права выдаются через `GrantRole` / `RevokeRole` и лежат в
+//! `PokerState::roles`. Admin покрывает все роли ниже себя; менеджер
+//! стола может быть ограничен одним столом.
+//!
+//! Цепь стола, открытого координатором, своих ролей не хранит: координатор
+//! присылает ей `TableRoles` с `OpenTable` и после каждого изменения ролей.
+
+use linera_sdk::linera_base_types::{AccountOwner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::TableId;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    /// Всё, кроме управления админами и передачи владения.
+    Admin,
+    /// Турниры: создание, старт, уровни, призовые, закрытие.
+    TournamentDirector,
+    /// Столы: создание, стеки, авто-раздача, снятие игроков.
+    TableManager,
+    /// Бот-дилер: старт раздач и тики таймеров.
+    DealerBot,
+}
+
+/// Роли, которым разрешены StartHand и TickTable. Без одной из них
+/// (или владения приложением) эти команды отклоняются.
+pub const DEALER_ROLES: [Role; 2] = [Role::DealerBot, Role::TableManager];
+
+/// Выданная роль. `table_id` сужает роль до одного стола
+/// (имеет смысл для TableManager и DealerBot).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub table_id: Option<TableId>,
+}
+
+impl RoleAssignment {
+    /// Покрывает ли эта запись роль `role` на столе `table_id`.
+    pub fn grants(&self, role: Role, table_id: Option<TableId>) -> bool {
+        if self.role == Role::Admin {
+            return true;
+        }
+        self.role == role && (self.table_id.is_none() || self.table_id == table_id)
+    }
+}
+
+/// Роли на столе, живущем на другой цепи: копия с координатора
+/// (`OpenTable` / `SyncRoles`), по ней цепь стола проверяет права.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TableRoles {
+    pub owner: Option<AccountOwner>,
+    pub grants: Vec<(AccountOwner, RoleAssignment)>,
+}
+
+impl TableRoles {
+    pub fn allows(&self, account: AccountOwner, role: Role, table_id: Option<TableId>) -> bool {
+        self.owner == Some(account)
+            || self
+                .grants
+                .iter()
+                .any(|(holder, a)| *holder == account && a.grants(role, table_id))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RoleChangeKind {
+    Granted(RoleAssignment),
+    Revoked(RoleAssignment),
+    OwnershipProposed,
+    OwnershipAccepted { previous: Option<AccountOwner> },
+}
+
+/// Запись журнала `PokerState::role_log`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoleChange {
+    pub at: Timestamp,
+    /// Кто сделал изменение.
+    pub by: AccountOwner,
+    /// Чьи права изменились (для передачи владения — новый владелец).
+    pub account: AccountOwner,
+    pub kind: RoleChangeKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(byte: u8) -> AccountOwner {
+        AccountOwner::Address20([byte; 20])
+    }
+
+    fn grant(holder: u8, role: Role, table_id: Option<TableId>) -> (AccountOwner, RoleAssignment) {
+        (account(holder), RoleAssignment { role, table_id })
+    }
+
+    fn can_deal(roles: &TableRoles, holder: u8, table_id: TableId) -> bool {
+        DEALER_ROLES
+            .iter()
+            .any(|role| roles.allows(account(holder), *role, Some(table_id)))
+    }
+
+    #[test]
+    fn dealing_needs_a_dealer_role() {
+        let roles = TableRoles {
+            owner: Some(account(1)),
+            grants: vec![
+                grant(2, Role::DealerBot, Some(7)),
+                grant(3, Role::TableManager, None),
+                grant(4, Role::TournamentDirector, None),
+                grant(5, Role::Admin, None),
+            ],
+        };
+
+        assert!(can_deal(&roles, 1, 7), "owner");
+        assert!(can_deal(&roles, 2, 7), "dealer bot on its table");
+        assert!(!can_deal(&roles, 2, 8), "dealer bot on another table");
+        assert!(can_deal(&roles, 3, 8), "table manager");
+        assert!(!can_deal(&roles, 4, 7), "tournament director");
+        assert!(can_deal(&roles, 5, 7), "admin");
+        // Раньше StartHand/TickTable мог прислать любой подписант.
+        assert!(!can_deal(&roles, 9, 7), "player without a role");
+    }
+}
@@ -0,0 +1,470 @@
+//! Правила стола поверх движка: вариант игры и лимиты ставок.
+//!
+//! Движок знает только безлимитный холдем. Всё, что отличает другие
+//! варианты, on-chain слой делает сам: докладывает закрытые карты после
+//! `engine::start_hand`, проверяет размер ставки до `engine::apply_action`
+//! и пересчитывает банки на шоудауне (см. `crate::showdown`).
+//!
+//! Лимитные структуры ставок для движка выглядят как обычный NL: ставка
+//! по-прежнему `Bet(Chips)` / `Raise(Chips)`, а лимит — это лишь
+//! ограничение допустимых сумм, которое проверяет `check_bet_size`.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::hand::Street;
+use poker_engine::domain::SeatIndex;
+use poker_engine::engine::actions::PlayerActionKind;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameVariant {
+    /// Безлимитный техасский холдем (то, что умеет движок).
+    #[default]
+    Holdem,
+    /// Пот-лимит Омаха: четыре закрытые карты, ровно две из них + три с борда.
+    Omaha,
+    /// Шорт-дек (6+): колода без двоек–пятёрок, флеш старше фулл-хауса,
+    /// туз замыкает стрит A-6-7-8-9.
+    ShortDeck,
+}
+
+impl GameVariant {
+    /// Сколько закрытых карт получает игрок.
+    pub fn hole_cards(self) -> usize {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck => 2,
+            GameVariant::Omaha => 4,
+        }
+    }
+
+    pub fn is_pot_limit(self) -> bool {
+        matches!(self, GameVariant::Omaha)
+    }
+
+    /// Младший ранг в колоде варианта.
+    pub fn min_rank(self) -> u8 {
+        match self {
+            GameVariant::ShortDeck => 6,
+            GameVariant::Holdem | GameVariant::Omaha => 2,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameVariant::Holdem => "Hold'em",
+            GameVariant::Omaha => "Omaha",
+            GameVariant::ShortDeck => "6+ Hold'em",
+        }
+    }
+}
+
+/// Структура ставок.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    PotLimit,
+    /// Фиксированный лимит: `small_bet` на префлопе и флопе, `big_bet`
+    /// на тёрне и ривере; не больше `max_bets` ставок за улицу
+    /// (ставка + рейзы, большой блайнд считается первой ставкой).
+    FixedLimit {
+        small_bet: Chips,
+        big_bet: Chips,
+        max_bets: u8,
+    },
+    /// Спред-лимит: ставка или прибавка рейза — от `min_bet` до `max_bet`.
+    SpreadLimit { min_bet: Chips, max_bet: Chips },
+}
+
+impl BettingStructure {
+    pub fn label(self) -> &'static str {
+        match self {
+            BettingStructure::NoLimit => "No Limit",
+            BettingStructure::PotLimit => "Pot Limit",
+            BettingStructure::FixedLimit { .. } => "Limit",
+            BettingStructure::SpreadLimit { .. } => "Spread Limit",
+        }
+    }
+
+    /// Проверить параметры структуры. Err — человекочитаемая причина.
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            BettingStructure::NoLimit | BettingStructure::PotLimit => Ok(()),
+            BettingStructure::FixedLimit {
+                small_bet,
+                big_bet,
+                max_bets,
+            } => {
+                if small_bet.0 == 0 || big_bet.0 < small_bet.0 {
+                    return Err("fixed limit needs 0 < small_bet <= big_bet".to_string());
+                }
+                if max_bets == 0 {
+                    return Err("fixed limit needs max_bets >= 1".to_string());
+                }
+                Ok(())
+            }
+            BettingStructure::SpreadLimit { min_bet, max_bet } => {
+                if min_bet.0 == 0 || max_bet.0 < min_bet.0 {
+                    return Err("spread limit needs 0 < min_bet <= max_bet".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Правила стола. Хранятся в `PokerState::table_rules`;
+/// отсутствие записи = правила по умолчанию (NL Hold'em).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableRules {
+    pub variant: GameVariant,
+    #[serde(default)]
+    pub betting: BettingStructure,
+    /// Анте только с баттона (вместо анте со всех); обычно вместе
+    /// с `AnteType::None` в ставках стола.
+    #[serde(default)]
+    pub button_ante: Option<Chips>,
+}
+
+impl TableRules {
+    /// Действующая структура ставок: пот-лимитные варианты без явной
+    /// структуры играются в пот-лимит.
+    pub fn betting(&self) -> BettingStructure {
+        if self.betting == BettingStructure::NoLimit && self.variant.is_pot_limit() {
+            BettingStructure::PotLimit
+        } else {
+            self.betting
+        }
+    }
+
+    pub fn is_limit(&self) -> bool {
+        matches!(
+            self.betting(),
+            BettingStructure::FixedLimit { .. } | BettingStructure::SpreadLimit { .. }
+        )
+    }
+
+    /// Банк делит on-chain слой, а не движок: варианты со своей оценкой
+    /// рук и анте с баттона, которого движок не знает.
+    pub fn settles_on_chain(&self) -> bool {
+        self.variant != GameVariant::Holdem || self.button_ante.is_some()
+    }
+
+    /// Проверить правила целиком. Err — человекочитаемая причина.
+    pub fn validate(&self) -> Result<(), String> {
+        self.betting.validate()?;
+        if self.button_ante.is_some_and(|a| a.0 == 0) {
+            return Err("button ante must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Название игры для истории раздач, например "Hold'em Limit".
+    pub fn label(&self) -> String {
+        format!("{} {}", self.variant.name(), self.betting().label())
+    }
+}
+
+/// Ставки на улице в момент хода — всё, что нужно для проверки лимита.
+#[derive(Clone, Debug)]
+pub struct BettingRound {
+    /// Весь банк раздачи, включая ставки текущей улицы.
+    pub pot: Chips,
+    /// Самая большая ставка на улице.
+    pub max_bet: Chips,
+    /// Ставка игрока на улице.
+    pub player_bet: Chips,
+    pub player_stack: Chips,
+    pub street: Street,
+    /// Ставок и рейзов на улице до этого действия (с большим блайндом на префлопе).
+    pub bets_this_street: u8,
+}
+
+impl BettingRound {
+    /// Максимум, до которого можно поставить/повысить в пот-лимите:
+    /// текущая ставка + банк после колла.
+    pub fn pot_limit_max(&self) -> Chips {
+        let to_call = self.max_bet.0.saturating_sub(self.player_bet.0);
+        Chips(self.max_bet.0 + self.pot.0 + to_call)
+    }
+}
+
+/// Итоговая ставка игрока на улице после действия (None — ставка не растёт).
+pub fn requested_total(kind: &PlayerActionKind, round: &BettingRound) -> Option<Chips> {
+    match kind {
+        PlayerActionKind::Bet(amount) => Some(Chips(round.player_bet.0 + amount.0)),
+        PlayerActionKind::Raise(to) => Some(*to),
+        PlayerActionKind::AllIn => Some(Chips(round.player_bet.0 + round.player_stack.0)),
+        PlayerActionKind::Fold | PlayerActionKind::Check | PlayerActionKind::Call => None,
+    }
+}
+
+/// Почему ставка не проходит по лимиту стола.
+#[derive(Clone, Debug, Error)]
+pub enum BetSizeError {
+    #[error("bet to {requested:?} exceeds the maximum of {max:?}")]
+    AboveLimit { max: Chips, requested: Chips },
+
+    #[error("bet to {requested:?} is below the minimum of {min:?}")]
+    BelowMinimum { min: Chips, requested: Chips },
+
+    #[error("fixed limit allows only a bet to {expected:?}, got {requested:?}")]
+    NotFixedSize { expected: Chips, requested: Chips },
+
+    #[error("betting is capped at {max_bets} bets per street")]
+    CapReached { max_bets: u8 },
+}
+
+/// Ставка лимитного стола на текущей улице.
+fn fixed_bet_size(street: Street, small_bet: Chips, big_bet: Chips) -> Chips {
+    if matches!(street, Street::Preflop | Street::Flop) {
+        small_bet
+    } else {
+        big_bet
+    }
+}
+
+/// Подставить стандартную сумму в `Bet` / `Raise` без суммы (нулевой)
+/// на лимитных столах: фиксированную ставку или минимум спреда.
+pub fn resolve_limit_amount(
+    rules: &TableRules,
+    kind: PlayerActionKind,
+    round: &BettingRound,
+) -> PlayerActionKind {
+    let step = match rules.betting() {
+        BettingStructure::FixedLimit {
+            small_bet, big_bet, ..
+        } => fixed_bet_size(round.street, small_bet, big_bet),
+        BettingStructure::SpreadLimit { min_bet, .. } => min_bet,
+        BettingStructure::NoLimit | BettingStructure::PotLimit => return kind,
+    };
+    let to = Chips(round.max_bet.0 + step.0);
+
+    match kind {
+        PlayerActionKind::Bet(amount) if amount.0 == 0 => {
+            PlayerActionKind::Bet(Chips(to.0.saturating_sub(round.player_bet.0)))
+        }
+        PlayerActionKind::Raise(amount) if amount.0 == 0 => PlayerActionKind::Raise(to),
+        other => other,
+    }
+}
+
+/// Проверить ставку по правилам стола.
+pub fn check_bet_size(
+    rules: &TableRules,
+    kind: &PlayerActionKind,
+    round: &BettingRound,
+) -> Result<(), BetSizeError> {
+    let Some(total) = requested_total(kind, round) else {
+        return Ok(());
+    };
+
+    // Олл-ин на колл или меньше — всегда можно.
+    let all_in = matches!(kind, PlayerActionKind::AllIn);
+    if all_in && total.0 <= round.max_bet.0 {
+        return Ok(());
+    }
+
+    match rules.betting() {
+        BettingStructure::NoLimit => Ok(()),
+        BettingStructure::PotLimit => {
+            let max = round.pot_limit_max();
+            if total.0 > max.0 {
+                return Err(BetSizeError::AboveLimit {
+                    max,
+                    requested: total,
+                });
+            }
+            Ok(())
+        }
+        BettingStructure::FixedLimit {
+            small_bet,
+            big_bet,
+            max_bets,
+        } => {
+            if round.bets_this_street >= max_bets {
+                return Err(BetSizeError::CapReached { max_bets });
+            }
+            let step = fixed_bet_size(round.street, small_bet, big_bet);
+            let expected = Chips(round.max_bet.0 + step.0);
+            // Короткий олл-ин меньше полной ставки допустим.
+            if total.0 != expected.0 && !(all_in && total.0 < expected.0) {
+                return Err(BetSizeError::NotFixedSize {
+                    expected,
+                    requested: total,
+                });
+            }
+            Ok(())
+        }
+        BettingStructure::SpreadLimit { min_bet, max_bet } => {
+            let max = Chips(round.max_bet.0 + max_bet.0);
+            let min = Chips(round.max_bet.0 + min_bet.0);
+            if total.0 > max.0 {
+                return Err(BetSizeError::AboveLimit {
+                    max,
+                    requested: total,
+                });
+            }
+            if total.0 < min.0 && !all_in {
+                return Err(BetSizeError::BelowMinimum {
+                    min,
+                    requested: total,
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Места по порядку действия от кнопки: первое — слева от дилера.
+/// Нужно для нечётных фишек при делёжке банка.
+pub fn seats_from_button(
+    occupied: &[SeatIndex],
+    dealer_button: Option<SeatIndex>,
+) -> Vec<SeatIndex> {
+    let mut seats = occupied.to_vec();
+    seats.sort_unstable();
+    if let Some(button) = dealer_button {
+        let pos = seats.iter().position(|s| *s > button).unwrap_or(0);
+        seats.rotate_left(pos);
+    }
+    seats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(betting: BettingStructure) -> TableRules {
+        TableRules {
+            betting,
+            ..TableRules::default()
+        }
+    }
+
+    fn round(street: Street, pot: u64, max_bet: u64, player_bet: u64, stack: u64) -> BettingRound {
+        BettingRound {
+            pot: Chips(pot),
+            max_bet: Chips(max_bet),
+            player_bet: Chips(player_bet),
+            player_stack: Chips(stack),
+            street,
+            bets_this_street: 1,
+        }
+    }
+
+    const FIXED: BettingStructure = BettingStructure::FixedLimit {
+        small_bet: Chips(10),
+        big_bet: Chips(20),
+        max_bets: 4,
+    };
+
+    #[test]
+    fn pot_limit_max_includes_the_pending_call() {
+        // Банк 80, соперник поставил 20: колл 20 делает банк 120,
+        // значит рейз максимум до 20 + 120 = 140.
+        let round = round(Street::Flop, 100, 20, 0, 1_000);
+        assert_eq!(round.pot_limit_max(), Chips(140));
+
+        let pot_limit = rules(BettingStructure::PotLimit);
+        assert!(check_bet_size(&pot_limit, &PlayerActionKind::Raise(Chips(140)), &round).is_ok());
+        assert!(matches!(
+            check_bet_size(&pot_limit, &PlayerActionKind::Raise(Chips(141)), &round),
+            Err(BetSizeError::AboveLimit { max: Chips(140), .. })
+        ));
+    }
+
+    #[test]
+    fn pot_limit_max_counts_own_bet_already_in() {
+        // Большой блайнд 10 уже внутри, рейз до 30: на колл нужно 20.
+        let round = round(Street::Preflop, 45, 30, 10, 1_000);
+        assert_eq!(round.pot_limit_max(), Chips(30 + 45 + 20));
+    }
+
+    #[test]
+    fn fixed_limit_accepts_only_the_fixed_step() {
+        let fixed = rules(FIXED);
+        let flop = round(Street::Flop, 60, 10, 0, 1_000);
+        assert!(check_bet_size(&fixed, &PlayerActionKind::Raise(Chips(20)), &flop).is_ok());
+        assert!(matches!(
+            check_bet_size(&fixed, &PlayerActionKind::Raise(Chips(30)), &flop),
+            Err(BetSizeError::NotFixedSize { expected: Chips(20), .. })
+        ));
+
+        // На тёрне шаг — большая ставка.
+        let turn = round(Street::Turn, 60, 0, 0, 1_000);
+        assert!(check_bet_size(&fixed, &PlayerActionKind::Bet(Chips(20)), &turn).is_ok());
+        assert!(check_bet_size(&fixed, &PlayerActionKind::Bet(Chips(10)), &turn).is_err());
+    }
+
+    #[test]
+    fn fixed_limit_caps_bets_per_street() {
+        let fixed = rules(FIXED);
+        let mut capped = round(Street::Flop, 100, 40, 30, 1_000);
+        capped.bets_this_street = 4;
+        assert!(matches!(
+            check_bet_size(&fixed, &PlayerActionKind::Raise(Chips(50)), &capped),
+            Err(BetSizeError::CapReached { max_bets: 4 })
+        ));
+        // Колл после кэпа по-прежнему возможен.
+        assert!(check_bet_size(&fixed, &PlayerActionKind::Call, &capped).is_ok());
+
+        capped.bets_this_street = 3;
+        assert!(check_bet_size(&fixed, &PlayerActionKind::Raise(Chips(50)), &capped).is_ok());
+    }
+
+    #[test]
+    fn fixed_limit_allows_short_all_in() {
+        let fixed = rules(FIXED);
+        // До полного рейза (до 30) не хватает: олл-ин до 25 допустим.
+        let short = round(Street::Flop, 40, 20, 0, 25);
+        assert!(check_bet_size(&fixed, &PlayerActionKind::AllIn, &short).is_ok());
+        // Олл-ин меньше колла — тоже.
+        let tiny = round(Street::Flop, 40, 20, 0, 15);
+        assert!(check_bet_size(&fixed, &PlayerActionKind::AllIn, &tiny).is_ok());
+        // Олл-ин больше фиксированного рейза — нет.
+        let deep = round(Street::Flop, 40, 20, 0, 100);
+        assert!(matches!(
+            check_bet_size(&fixed, &PlayerActionKind::AllIn, &deep),
+            Err(BetSizeError::NotFixedSize { expected: Chips(30), .. })
+        ));
+    }
+
+    #[test]
+    fn spread_limit_bounds_the_raise_increment() {
+        let spread = rules(BettingStructure::SpreadLimit {
+            min_bet: Chips(10),
+            max_bet: Chips(50),
+        });
+        let round = round(Street::Turn, 100, 20, 0, 1_000);
+
+        assert!(check_bet_size(&spread, &PlayerActionKind::Raise(Chips(30)), &round).is_ok());
+        assert!(check_bet_size(&spread, &PlayerActionKind::Raise(Chips(70)), &round).is_ok());
+        assert!(matches!(
+            check_bet_size(&spread, &PlayerActionKind::Raise(Chips(71)), &round),
+            Err(BetSizeError::AboveLimit { max: Chips(70), .. })
+        ));
+        assert!(matches!(
+            check_bet_size(&spread, &PlayerActionKind::Raise(Chips(29)), &round),
+            Err(BetSizeError::BelowMinimum { min: Chips(30), .. })
+        ));
+
+        // Короткий олл-ин ниже минимума разрешён.
+        let short = BettingRound {
+            player_stack: Chips(25),
+            ..round
+        };
+        assert!(check_bet_size(&spread, &PlayerActionKind::AllIn, &short).is_ok());
+    }
+
+    #[test]
+    fn resolve_limit_amount_fills_in_the_step() {
+        let fixed = rules(FIXED);
+        let flop = round(Street::Flop, 60, 10, 0, 1_000);
+        assert!(matches!(
+            resolve_limit_amount(&fixed, PlayerActionKind::Raise(Chips(0)), &flop),
+            PlayerActionKind::Raise(Chips(20))
+        ));
+    }
+}
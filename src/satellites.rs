@@ -0,0 +1,42 @@
+//! Сателлиты и билеты.
+//!
+//! Сателлит (`PokerState::satellites`) вместо фишек раздаёт билеты в
+//! целевой турнир. При старте призовой фонд делится на бай-ин цели:
+//! столько первых мест получают билет, остаток уходит следующему месту
+//! деньгами. Билет лежит в `PokerState::tickets` и при выдаче сразу
+//! пробует зарегистрировать владельца в цели; если не вышло (регистрация
+//! закрыта и т.п.), билет остаётся и гасится при обычной регистрации.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::TournamentId;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SatelliteConfig {
+    pub target: TournamentId,
+    /// Сколько билетов разыгрывается; считается при старте сателлита.
+    pub seats: u32,
+}
+
+/// Билет в турнир `target`, выигранный в `source`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ticket {
+    pub target: TournamentId,
+    pub source: TournamentId,
+    pub issued_at: Timestamp,
+    /// Билет потрачен на регистрацию (при разрегистрации возвращается).
+    pub used: bool,
+}
+
+/// Билетов и остаток деньгами для фонда `prize_pool`
+/// при бай-ине цели `target_buy_in`.
+pub fn satellite_seats(prize_pool: Chips, target_buy_in: Chips) -> (u32, Chips) {
+    if target_buy_in.is_zero() {
+        return (0, prize_pool);
+    }
+    let seats = prize_pool.0 / target_buy_in.0;
+    let remainder = prize_pool.0 - seats * target_buy_in.0;
+    (seats.min(u32::MAX as u64) as u32, Chips(remainder))
+}
@@ -0,0 +1,166 @@
+//! Расписание турниров: старт и закрытие регистрации по времени.
+//!
+//! Фронт присылает время строками (`UiTournamentConfig.start_time`,
+//! `reg_close_time`); `parse_time` превращает их в `Timestamp` цепи.
+//! Если эти поля есть в конфиге `createTournament`, турнир сразу ставится
+//! в расписание (`config_schedule`).
+//! Таймеров у контракта нет, поэтому расписание применяется лениво:
+//! первая операция над турниром после нужного момента закрывает
+//! регистрацию, стартует турнир или отменяет его с возвратом бай-инов.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleState {
+    /// Регистрация открыта.
+    Scheduled,
+    RegistrationClosed,
+    Started,
+    /// Не набралось `min_players` к старту, бай-ины возвращены.
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TournamentSchedule {
+    pub start_at: Timestamp,
+    /// Не позже `start_at`.
+    pub reg_close_at: Timestamp,
+    pub min_players: u32,
+    pub state: ScheduleState,
+}
+
+/// Что нужно сделать с турниром в момент `now`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleStep {
+    Nothing,
+    CloseRegistration,
+    Start,
+    Cancel,
+}
+
+impl TournamentSchedule {
+    pub fn registration_open(&self) -> bool {
+        self.state == ScheduleState::Scheduled
+    }
+
+    pub fn step(&self, now: Timestamp, registered: u32) -> ScheduleStep {
+        match self.state {
+            ScheduleState::Started | ScheduleState::Cancelled => ScheduleStep::Nothing,
+            _ if now >= self.start_at => {
+                if registered < self.min_players.max(2) {
+                    ScheduleStep::Cancel
+                } else {
+                    ScheduleStep::Start
+                }
+            }
+            ScheduleState::Scheduled if now >= self.reg_close_at => {
+                ScheduleStep::CloseRegistration
+            }
+            _ => ScheduleStep::Nothing,
+        }
+    }
+}
+
+/// Разобрать время из UI: RFC 3339 (`2026-10-18T19:00:00Z`,
+/// `2026-10-18T22:00:00+03:00`, дробные секунды допускаются)
+/// или число секунд Unix-времени.
+pub fn parse_time(value: &str) -> Result<Timestamp, String> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Timestamp::from(secs.saturating_mul(1_000_000)));
+    }
+
+    let err = || format!("invalid time {value:?}: expected RFC 3339 or unix seconds");
+    let num = |s: &str| s.parse::<i64>().map_err(|_| err());
+
+    let (date, rest) = value.split_once(['T', 't', ' ']).ok_or_else(err)?;
+    let mut date_parts = date.splitn(3, '-');
+    let (Some(y), Some(m), Some(d)) = (date_parts.next(), date_parts.next(), date_parts.next())
+    else {
+        return Err(err());
+    };
+    let (year, month, day) = (num(y)?, num(m)?, num(d)?);
+
+    // Смещение часового пояса: Z, +hh:mm или -hh:mm.
+    let (time, offset_secs) = if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else if let Some(pos) = rest.rfind(['+', '-']) {
+        let (time, offset) = rest.split_at(pos);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..].split_once(':').ok_or_else(err)?;
+        (time, sign * (num(oh)? * 3600 + num(om)? * 60))
+    } else {
+        return Err(err());
+    };
+
+    let time = time.split('.').next().unwrap_or(time);
+    let mut time_parts = time.splitn(3, ':');
+    let (Some(h), Some(mi), Some(s)) = (time_parts.next(), time_parts.next(), time_parts.next())
+    else {
+        return Err(err());
+    };
+    let (hour, minute, second) = (num(h)?, num(mi)?, num(s)?);
+
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return Err(err());
+    }
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+        - offset_secs;
+    let secs = u64::try_from(secs).map_err(|_| err())?;
+    Ok(Timestamp::from(secs.saturating_mul(1_000_000)))
+}
+
+/// Время старта и закрытия регистрации из JSON-конфига турнира
+/// (`start_time` / `reg_close_time`). None — старт не задан.
+pub fn config_schedule(
+    config: &JsonValue,
+) -> Result<Option<(Timestamp, Option<Timestamp>)>, String> {
+    let field = |key: &str| config.get(key).and_then(JsonValue::as_str);
+    let Some(start_time) = field("start_time") else {
+        return Ok(None);
+    };
+    let start_at = parse_time(start_time)?;
+    let reg_close_at = field("reg_close_time").map(parse_time).transpose()?;
+    Ok(Some((start_at, reg_close_at)))
+}
+
+/// Дней от 1970-01-01 до даты (алгоритм Howard Hinnant, пролептический григорианский).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_schedule_reads_ui_times() {
+        let config = serde_json::json!({
+            "name": "Sunday",
+            "start_time": "2026-10-18T19:00:00Z",
+            "reg_close_time": "2026-10-18T22:00:00+03:00",
+        });
+        let (start_at, reg_close_at) = config_schedule(&config).unwrap().unwrap();
+        assert_eq!(start_at, Timestamp::from(1_792_350_000 * 1_000_000));
+        assert_eq!(reg_close_at, Some(start_at));
+
+        assert!(config_schedule(&serde_json::json!({ "start_time": null }))
+            .unwrap()
+            .is_none());
+        assert!(config_schedule(&serde_json::json!({ "start_time": "tomorrow" })).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+//! Сезоны и сезонные рейтинги.
+//!
+//! Сезон — отрезок времени цепи фиксированной длины. Пока сезон идёт,
+//! `PokerState::season_standings` копит результаты игроков инкрементально
+//! (после каждой раздачи и каждого финиша в турнире). Когда время цепи
+//! выходит за `ends_at`, первая же запись результата архивирует итоговую
+//! таблицу в `season_archive` и открывает следующий сезон той же длины.
+
+use linera_sdk::linera_base_types::{TimeDelta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::PlayerId;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Season {
+    pub season_id: u32,
+    pub started_at: Timestamp,
+    pub ends_at: Timestamp,
+    pub duration_secs: u64,
+}
+
+impl Season {
+    pub fn new(season_id: u32, now: Timestamp, duration_secs: u64) -> Self {
+        Self {
+            season_id,
+            started_at: now,
+            ends_at: now.saturating_add(TimeDelta::from_secs(duration_secs)),
+            duration_secs,
+        }
+    }
+
+    pub fn is_over(&self, now: Timestamp) -> bool {
+        now >= self.ends_at
+    }
+
+    /// Следующий сезон той же длины, начиная с `now`.
+    pub fn next(&self, now: Timestamp) -> Self {
+        Self::new(self.season_id + 1, now, self.duration_secs)
+    }
+}
+
+/// Результаты игрока за сезон.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SeasonEntry {
+    /// Чистый результат на cash-столах + турнирные призы.
+    pub net_winnings: i64,
+    pub tournament_points: u64,
+    pub hands_played: u64,
+}
+
+/// Итоговая таблица завершённого сезона.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedSeason {
+    pub season: Season,
+    pub standings: Vec<(PlayerId, SeasonEntry)>,
+}
+
+/// Очки за финиш в турнире: по одному за каждого пережитого соперника
+/// плюс один за участие.
+pub fn tournament_points(entrants: u32, place: u32) -> u64 {
+    entrants.saturating_sub(place) as u64 + 1
+}
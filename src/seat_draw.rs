@@ -0,0 +1,211 @@
+//! Жеребьёвка мест при старте турнира.
+//!
+//! Число столов — минимальное, игроки делятся между ними поровну
+//! (`table_sizes`: столы отличаются не больше чем на одного игрока).
+//! Игроки перемешиваются генератором из `StartEntropy::seed(tournament_id,
+//! SEAT_DRAW_SALT)` — закоммиченный `base_seed` вместе с высотой блока,
+//! временем старта и составом участников — и рассаживаются по этим
+//! размерам. Номера мест и кнопка на каждом столе тоже тянутся случайно.
+//! Результат вместе со входными данными пишется в `PokerState::seat_draws`,
+//! так что жеребьёвку можно повторить.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::{PlayerId, SeatIndex, TableId};
+
+use crate::sng::{mix64, StartEntropy};
+
+/// Соль, чтобы жеребьёвка не совпадала с розыгрышем множителя спина.
+pub const SEAT_DRAW_SALT: u64 = 0x5EA7_D2A3;
+
+/// Рассадка одного стола.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableDraw {
+    pub table_id: TableId,
+    /// (место, игрок) по возрастанию места.
+    pub seats: Vec<(SeatIndex, PlayerId)>,
+    /// Место, на котором кнопка в первой раздаче.
+    pub button: SeatIndex,
+}
+
+/// Запись жеребьёвки для аудита.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SeatDraw {
+    pub seed: u64,
+    /// Из чего получен `seed`.
+    pub inputs: StartEntropy,
+    pub drawn_at: Timestamp,
+    pub tables: Vec<TableDraw>,
+}
+
+/// Детерминированный поток чисел из seed'а (SplitMix64).
+struct DrawRng(u64);
+
+impl DrawRng {
+    fn next(&mut self) -> u64 {
+        self.0 = mix64(self.0);
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Размеры столов для `players` игроков при `max_seats` местах:
+/// минимум столов, размеры различаются не больше чем на один.
+/// Err — если за каждым столом не набирается двух игроков.
+pub fn table_sizes(players: usize, max_seats: u8) -> Result<Vec<usize>, String> {
+    let max_seats = usize::from(max_seats);
+    if max_seats < 2 {
+        return Err(format!("table size {max_seats} is less than 2"));
+    }
+    if players < 2 {
+        return Err(format!("{players} registered, at least 2 are needed"));
+    }
+
+    let tables = players.div_ceil(max_seats);
+    let base = players / tables;
+    let larger = players % tables;
+    if base < 2 {
+        return Err(format!(
+            "{players} players cannot be seated at least two per table across {tables} tables"
+        ));
+    }
+
+    Ok((0..tables)
+        .map(|i| base + usize::from(i < larger))
+        .collect())
+}
+
+/// Разыграть места для `player_ids` за столами `tables` (id, число игроков
+/// из `table_sizes`) по `max_seats` мест.
+pub fn draw_seats(
+    player_ids: &[PlayerId],
+    tables: &[(TableId, usize)],
+    max_seats: u8,
+    seed: u64,
+) -> Vec<TableDraw> {
+    let mut rng = DrawRng(seed);
+
+    // Порядок регистрации не влияет на результат.
+    let mut players = player_ids.to_vec();
+    players.sort_unstable();
+    rng.shuffle(&mut players);
+
+    let mut rest = players.as_slice();
+    tables
+        .iter()
+        .map(|(table_id, size)| {
+            let (players, tail) = rest.split_at((*size).min(rest.len()));
+            rest = tail;
+
+            let mut seat_numbers: Vec<SeatIndex> = (0..usize::from(max_seats))
+                .map(|s| s as SeatIndex)
+                .collect();
+            rng.shuffle(&mut seat_numbers);
+
+            let mut seats: Vec<(SeatIndex, PlayerId)> = seat_numbers
+                .into_iter()
+                .zip(players.iter().copied())
+                .collect();
+            seats.sort_unstable_by_key(|(seat, _)| *seat);
+
+            let button = seats
+                .get(rng.below(seats.len().max(1)))
+                .map_or(0, |(seat, _)| *seat);
+            TableDraw {
+                table_id: *table_id,
+                seats,
+                button,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_seats_everyone_once_with_button_on_occupied_seat() {
+        let players: Vec<PlayerId> = (1..=13).collect();
+        let tables = [(100, 7), (101, 6)];
+        let draw = draw_seats(&players, &tables, 9, 42);
+
+        let mut seated: Vec<PlayerId> = draw
+            .iter()
+            .flat_map(|t| t.seats.iter().map(|(_, pid)| *pid))
+            .collect();
+        seated.sort_unstable();
+        assert_eq!(seated, players);
+
+        for (table, (table_id, size)) in draw.iter().zip(tables) {
+            assert_eq!(table.table_id, table_id);
+            assert_eq!(table.seats.len(), size);
+            assert!(table.seats.iter().all(|(seat, _)| *seat < 9));
+            assert!(table.seats.iter().any(|(seat, _)| *seat == table.button));
+        }
+    }
+
+    #[test]
+    fn draw_depends_on_seed_only() {
+        let players: Vec<PlayerId> = (1..=9).collect();
+        let mut shuffled = players.clone();
+        shuffled.reverse();
+        let tables = [(1, 9)];
+
+        let a = draw_seats(&players, &tables, 9, 7);
+        let b = draw_seats(&shuffled, &tables, 9, 7);
+        let c = draw_seats(&players, &tables, 9, 8);
+        assert_eq!(a[0].seats, b[0].seats);
+        assert_eq!(a[0].button, b[0].button);
+        assert!(a[0].seats != c[0].seats || a[0].button != c[0].button);
+    }
+
+    #[test]
+    fn table_sizes_around_a_full_table() {
+        assert_eq!(table_sizes(8, 9), Ok(vec![8]));
+        assert_eq!(table_sizes(9, 9), Ok(vec![9]));
+        assert_eq!(table_sizes(10, 9), Ok(vec![5, 5]));
+        assert_eq!(table_sizes(17, 9), Ok(vec![9, 8]));
+        assert_eq!(table_sizes(18, 9), Ok(vec![9, 9]));
+        assert_eq!(table_sizes(19, 9), Ok(vec![7, 6, 6]));
+        assert_eq!(table_sizes(2, 2), Ok(vec![2]));
+    }
+
+    #[test]
+    fn table_sizes_refuses_a_lone_player() {
+        assert!(table_sizes(0, 9).is_err());
+        assert!(table_sizes(1, 9).is_err());
+        assert!(table_sizes(5, 1).is_err());
+        // Хедз-ап столы: третьему игроку не с кем сесть.
+        assert!(table_sizes(3, 2).is_err());
+        assert!(table_sizes(4, 2).is_ok());
+    }
+
+    #[test]
+    fn table_sizes_are_balanced() {
+        for max_seats in 2..=10u8 {
+            for players in 2..=100 {
+                let Ok(sizes) = table_sizes(players, max_seats) else {
+                    continue;
+                };
+                let max = usize::from(max_seats);
+                assert_eq!(sizes.iter().sum::<usize>(), players);
+                assert_eq!(sizes.len(), players.div_ceil(max));
+                assert!(sizes.iter().all(|s| (2..=max).contains(s)));
+                let (lo, hi) = (sizes.iter().min(), sizes.iter().max());
+                assert!(hi.zip(lo).is_some_and(|(hi, lo)| hi - lo <= 1));
+            }
+        }
+    }
+}
@@ -1,13 +1,17 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_graphql::{
-    EmptySubscription, Enum, Json, Object, Request, Response, Schema, SimpleObject,
+    EmptySubscription, Enum, Error as GqlError, ErrorExtensions, InputObject, InputValueError,
+    InputValueResult, Json, Object, Request, Response, Result as GqlResult, Scalar,
+    ScalarType, Schema, SimpleObject, Value as GqlValue,
 };
 use linera_sdk::{
-    linera_base_types::WithServiceAbi,
-    views::{View, ViewStorageContext},
+    linera_base_types::{AccountOwner, ChainId, WithServiceAbi},
+    views::{View, ViewError},
     Service, ServiceRuntime,
 };
 use serde_json::Value as JsonValue;
@@ -17,7 +21,6 @@ use poker_engine::api::commands::{
     AnteTypeApi,
     Command as EngineCommand,
     CreateTableCommand,
-    PlayerActionCommand,
     SeatPlayerCommand,
     StartHandCommand,
     TableCommand,
@@ -35,16 +38,59 @@ use poker_engine::api::commands::{
 use poker_engine::api::dto::{PlayerAtTableDto, TableViewDto, TournamentViewDto};
 use poker_engine::domain::card::Card;
 use poker_engine::domain::chips::Chips;
-use poker_engine::domain::table::Table;
-use poker_engine::domain::tournament::TournamentConfig;
-use poker_engine::domain::{PlayerId, SeatIndex, TableId, TournamentId};
-use poker_engine::engine::actions::{PlayerAction, PlayerActionKind};
-
-use poker_onchain::{HandEngineSnapshot, Operation, PokerAbi, PokerState};
+use poker_engine::domain::table::{Table, TableType};
+use poker_engine::domain::tournament::{Tournament, TournamentConfig};
+use poker_engine::domain::{HandId, PlayerId, TableId, TournamentId};
+use poker_engine::engine::actions::PlayerActionKind;
+
+use poker_onchain::commands::{
+    AcceptOwnershipCommand,
+    ActCommand,
+    CashOutCommand,
+    CreateSngTemplateCommand,
+    CreateTableWithRulesCommand,
+    CreditBankrollCommand,
+    DealVoteCommand,
+    GrantRoleCommand,
+    OnchainCommand,
+    OpenRemoteTableCommand,
+    ProposeDealCommand,
+    ProposeOwnerCommand,
+    RegisterPlayerCommand,
+    RemoveSngTemplateCommand,
+    RequestSeatCommand,
+    RevokeRoleCommand,
+    RotateAccountCommand,
+    ScheduleTournamentCommand,
+    SetAutoDealCommand,
+    SetDisplayNameCommand,
+    SetLevelDurationCommand,
+    SetSatelliteCommand,
+    SetTableChainsCommand,
+    SetTournamentPayoutsCommand,
+    StartSeasonCommand,
+    UpdateProfileCommand,
+};
+use poker_onchain::deals::{compute_deal, remaining_stacks, DealKind, DealShare};
+use poker_onchain::events::{EventRecord, PokerEvent};
+use poker_onchain::hand_archive::{page_positions, ArchivedHand};
+use poker_onchain::hand_export::to_pokerstars_text;
+use poker_onchain::identity::normalize_name;
+use poker_onchain::players::{PlayerPreferences, PlayerProfile, PlayerStats};
+use poker_onchain::roles::{Role, RoleAssignment, RoleChange, RoleChangeKind};
+use poker_onchain::rules::{BettingStructure, GameVariant, TableRules};
+use poker_onchain::satellites::Ticket;
+use poker_onchain::schedule::{config_schedule, parse_time, ScheduleState};
+use poker_onchain::seasons::SeasonEntry;
+use poker_onchain::sng::SpinMultiplier;
+use poker_onchain::standings::{average_stack, live_standings, next_level_at, Standing};
+use poker_onchain::{
+    HandEngineSnapshot, Operation, PokerAbi, PokerState, TableStats, TournamentFinish,
+};
 use poker_onchain::utils::build_tournament_view;
 
 pub struct PokerService {
-    state: PokerState,
+    state: Arc<PokerState>,
     runtime: Arc<ServiceRuntime<Self>>,
 }
 
@@ -63,23 +109,21 @@ impl Service for PokerService {
             .expect("Failed to load PokerState for service");
 
         PokerService {
-            state,
+            state: Arc::new(state),
             runtime: Arc::new(runtime),
         }
     }
 
     /// ВАЖНО: теперь handle_query работает с async-graphql Request/Response.
+    /// Состояние загружено один раз в `new` и общее для всех резолверов.
     async fn handle_query(&self, request: Request) -> Response {
-        let storage_context = self.runtime.root_view_storage_context();
-
         let schema = Schema::build(
             QueryRoot {
-                runtime: self.runtime.clone(),
-                storage_context: storage_context.clone(),
+                state: self.state.clone(),
             },
             MutationRoot {
                 runtime: self.runtime.clone(),
-                storage_context,
+                state: self.state.clone(),
             },
             EmptySubscription,
         )
@@ -89,6 +133,64 @@ impl Service for PokerService {
     }
 }
 
+// ============================================================================
+//                          64-БИТНЫЕ СКАЛЯРЫ
+// ============================================================================
+
+/// Беззнаковое 64-битное число (id, фишки, время).
+///
+/// В GraphQL передаётся строкой: JS-клиенты не держат 64 бита в Number,
+/// а встроенный `Int` — всего лишь i32. На вход принимаем и строку,
+/// и неотрицательное целое.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct U64(u64);
+
+#[Scalar(name = "U64")]
+impl ScalarType for U64 {
+    fn parse(value: GqlValue) -> InputValueResult<Self> {
+        match &value {
+            GqlValue::String(s) => s
+                .parse::<u64>()
+                .map(U64)
+                .map_err(|_| InputValueError::custom(format!("invalid U64: {s:?}"))),
+            GqlValue::Number(n) => n
+                .as_u64()
+                .map(U64)
+                .ok_or_else(|| InputValueError::custom(format!("invalid U64: {n}"))),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> GqlValue {
+        GqlValue::String(self.0.to_string())
+    }
+}
+
+/// Знаковое 64-битное число (дельта стека), тоже строкой.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct I64(i64);
+
+#[Scalar(name = "I64")]
+impl ScalarType for I64 {
+    fn parse(value: GqlValue) -> InputValueResult<Self> {
+        match &value {
+            GqlValue::String(s) => s
+                .parse::<i64>()
+                .map(I64)
+                .map_err(|_| InputValueError::custom(format!("invalid I64: {s:?}"))),
+            GqlValue::Number(n) => n
+                .as_i64()
+                .map(I64)
+                .ok_or_else(|| InputValueError::custom(format!("invalid I64: {n}"))),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> GqlValue {
+        GqlValue::String(self.0.to_string())
+    }
+}
+
 // ============================================================================
 //                               GQL DTO ТИПЫ
 // ============================================================================
@@ -101,26 +203,30 @@ struct GqlCard {
 
 #[derive(SimpleObject, Clone)]
 struct GqlPlayerAtTable {
-    player_id: i64,
+    player_id: U64,
     display_name: String,
     seat_index: i32,
-    stack: i64,
-    current_bet: i64,
+    stack: U64,
+    current_bet: U64,
     status: String,
     hole_cards: Option<Vec<GqlCard>>,
 }
 
 #[derive(SimpleObject, Clone)]
 struct GqlTableView {
-    table_id: i64,
+    table_id: U64,
     name: String,
+    variant: GqlGameVariant,
+    betting: GqlBettingKind,
+    /// Анте только с баттона (None — обычные анте по `ante`).
+    button_ante: Option<U64>,
     max_seats: i32,
-    small_blind: i64,
-    big_blind: i64,
-    ante: i64,
+    small_blind: U64,
+    big_blind: U64,
+    ante: U64,
     street: String,
     dealer_button: Option<i32>,
-    total_pot: i64,
+    total_pot: U64,
     board: Vec<GqlCard>,
     players: Vec<GqlPlayerAtTable>,
     hand_in_progress: bool,
@@ -129,7 +235,7 @@ struct GqlTableView {
 
 #[derive(SimpleObject, Clone)]
 struct GqlTournamentView {
-    tournament_id: i64,
+    tournament_id: U64,
     name: String,
     status: String,
     current_level: i32,
@@ -137,9 +243,453 @@ struct GqlTournamentView {
     tables_running: i32,
 }
 
+#[derive(SimpleObject)]
+struct GqlStanding {
+    rank: i32,
+    player_id: U64,
+    display_name: Option<String>,
+    chips: U64,
+    table_id: Option<U64>,
+    seat_index: Option<i32>,
+}
+
+#[derive(SimpleObject)]
+struct GqlTournamentFinish {
+    place: i32,
+    player_id: U64,
+    display_name: Option<String>,
+    prize: U64,
+    /// Билет в этот турнир (место в сателлите).
+    ticket_tournament_id: Option<U64>,
+    finished_at: U64,
+}
+
+/// Живая таблица турнира.
+#[derive(SimpleObject)]
+struct GqlTournamentStandings {
+    tournament_id: U64,
+    name: String,
+    status: String,
+    current_level: i32,
+    small_blind: U64,
+    big_blind: U64,
+    ante: U64,
+    /// Время цепи в микросекундах; None — турнир не стартовал.
+    level_started_at: Option<U64>,
+    /// None — длительность уровня не задана или уровень последний.
+    next_level_at: Option<U64>,
+    players_registered: i32,
+    players_remaining: i32,
+    average_stack: U64,
+    prize_pool: U64,
+    /// Призовые по местам, от первого.
+    payouts: Vec<U64>,
+    standings: Vec<GqlStanding>,
+    /// Вылетевшие, от лучшего места к худшему.
+    eliminated: Vec<GqlTournamentFinish>,
+}
+
+/// Турнир глазами одного игрока.
+#[derive(SimpleObject)]
+struct GqlMyTournament {
+    tournament_id: U64,
+    player_id: U64,
+    status: String,
+    chips: U64,
+    /// Место по фишкам среди живых; None — игрок вылетел.
+    rank: Option<i32>,
+    players_remaining: i32,
+    table_id: Option<U64>,
+    seat_index: Option<i32>,
+    finish: Option<GqlTournamentFinish>,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlHandSeat {
+    seat_index: i32,
+    player_id: U64,
+    display_name: String,
+    starting_stack: U64,
+    final_stack: U64,
+    showdown_cards: Option<Vec<GqlCard>>,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlHandAction {
+    street: String,
+    seat_index: i32,
+    player_id: U64,
+    action: String,
+    amount: Option<U64>,
+    auto: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlHandWinner {
+    seat_index: i32,
+    player_id: U64,
+    amount: U64,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlHandHistory {
+    hand_id: U64,
+    table_id: U64,
+    table_name: String,
+    tournament_id: Option<U64>,
+    variant: GqlGameVariant,
+    small_blind: U64,
+    big_blind: U64,
+    ante: U64,
+    dealer_button: Option<i32>,
+    /// Время цепи в микросекундах.
+    started_at: U64,
+    finished_at: Option<U64>,
+    seats: Vec<GqlHandSeat>,
+    actions: Vec<GqlHandAction>,
+    board: Vec<GqlCard>,
+    winners: Vec<GqlHandWinner>,
+    total_pot: U64,
+    side_pots: Vec<U64>,
+}
+
+/// Страница раздач (курсор = hand_id последней раздачи на странице).
+#[derive(SimpleObject)]
+struct GqlHandHistoryPage {
+    hands: Vec<GqlHandHistory>,
+    next_cursor: Option<U64>,
+    has_more: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlEventWinner {
+    player_id: U64,
+    amount: U64,
+}
+
+/// Событие ленты в плоском виде: `kind` + поля, заполненные по типу.
+#[derive(SimpleObject, Clone)]
+struct GqlPokerEvent {
+    /// Позиция в ленте — курсор для следующего запроса.
+    index: U64,
+    /// Время блока в микросекундах.
+    at: U64,
+    kind: String,
+    table_id: Option<U64>,
+    tournament_id: Option<U64>,
+    hand_id: Option<U64>,
+    seat_index: Option<i32>,
+    player_id: Option<U64>,
+    action: Option<String>,
+    amount: Option<U64>,
+    auto: Option<bool>,
+    street: Option<String>,
+    board: Option<Vec<GqlCard>>,
+    winners: Option<Vec<GqlEventWinner>>,
+    dealer_button: Option<i32>,
+    level: Option<i32>,
+    /// Множитель спина (TournamentStarted).
+    multiplier: Option<i32>,
+}
+
+#[derive(SimpleObject)]
+struct GqlEventPage {
+    events: Vec<GqlPokerEvent>,
+    /// Передать как `after` в следующий запрос (None — лента пуста).
+    next_cursor: Option<U64>,
+}
+
+/// Лёгкая карточка стола для лобби (без мест и карт).
+#[derive(SimpleObject, Clone)]
+struct GqlLobbyTable {
+    table_id: U64,
+    name: String,
+    table_type: GqlTableType,
+    variant: GqlGameVariant,
+    betting: GqlBettingKind,
+    max_seats: i32,
+    players: i32,
+    free_seats: i32,
+    small_blind: U64,
+    big_blind: U64,
+    ante: U64,
+    hand_in_progress: bool,
+    hands_played: U64,
+    avg_pot: U64,
+}
+
+#[derive(SimpleObject)]
+struct GqlLobbyPage {
+    tables: Vec<GqlLobbyTable>,
+    /// Передать как `after` в следующий запрос.
+    next_cursor: Option<String>,
+    has_more: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlPlayerPreferences {
+    auto_muck: bool,
+    four_color_deck: bool,
+    auto_rebuy: bool,
+}
+
+#[derive(InputObject)]
+struct GqlPlayerPreferencesInput {
+    auto_muck: bool,
+    four_color_deck: bool,
+    auto_rebuy: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+struct GqlPlayerStats {
+    hands_played: U64,
+    /// Проценты.
+    vpip: f64,
+    pfr: f64,
+    /// None — коллов ещё не было.
+    aggression_factor: Option<f64>,
+    showdowns: U64,
+    showdowns_won: U64,
+    showdown_win_rate: f64,
+    net_cash: I64,
+    net_tournament: I64,
+    tournaments_played: U64,
+    tournament_cashes: U64,
+    tournament_winnings: U64,
+}
+
+#[derive(SimpleObject)]
+struct GqlPlayer {
+    player_id: U64,
+    display_name: Option<String>,
+    avatar_url: Option<String>,
+    country: Option<String>,
+    /// Время цепи в микросекундах.
+    created_at: U64,
+    preferences: GqlPlayerPreferences,
+    stats: GqlPlayerStats,
+}
+
+#[derive(SimpleObject)]
+struct GqlLeaderboardEntry {
+    rank: i32,
+    player_id: U64,
+    display_name: Option<String>,
+    value: I64,
+}
+
+#[derive(SimpleObject)]
+struct GqlSeason {
+    season_id: U64,
+    /// Время цепи в микросекундах.
+    started_at: U64,
+    ends_at: U64,
+    duration_secs: U64,
+}
+
+#[derive(SimpleObject)]
+struct GqlRoleAssignment {
+    role: GqlRole,
+    /// None — роль на все столы.
+    table_id: Option<U64>,
+}
+
+#[derive(SimpleObject)]
+struct GqlRoleChange {
+    index: U64,
+    /// Время цепи в микросекундах.
+    at: U64,
+    by: String,
+    account: String,
+    /// GRANTED / REVOKED / OWNERSHIP_PROPOSED / OWNERSHIP_ACCEPTED.
+    kind: String,
+    role: Option<GqlRole>,
+    table_id: Option<U64>,
+    previous_owner: Option<String>,
+}
+
+#[derive(SimpleObject)]
+struct GqlSpinMultiplier {
+    multiplier: i32,
+    weight: i32,
+}
+
+#[derive(InputObject)]
+struct GqlSpinMultiplierInput {
+    multiplier: i32,
+    weight: i32,
+}
+
+#[derive(SimpleObject)]
+struct GqlSngTemplate {
+    template_id: U64,
+    name: String,
+    players: i32,
+    buy_in: U64,
+    /// Пусто — обычный SNG.
+    spin: Vec<GqlSpinMultiplier>,
+    /// Открытый турнир, куда идёт регистрация.
+    current_tournament_id: Option<U64>,
+    registered: i32,
+    launched: i32,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlScheduleState {
+    Scheduled,
+    RegistrationClosed,
+    Started,
+    Cancelled,
+}
+
+#[derive(SimpleObject)]
+struct GqlTournamentSchedule {
+    /// Время цепи в микросекундах.
+    start_at: U64,
+    reg_close_at: U64,
+    min_players: i32,
+    state: GqlScheduleState,
+}
+
+#[derive(SimpleObject)]
+struct GqlSatellite {
+    target_tournament_id: U64,
+    /// Число билетов; 0 до старта сателлита.
+    seats: i32,
+}
+
+#[derive(SimpleObject)]
+struct GqlTicket {
+    target_tournament_id: U64,
+    source_tournament_id: U64,
+    issued_at: U64,
+    used: bool,
+}
+
+impl From<Ticket> for GqlTicket {
+    fn from(t: Ticket) -> Self {
+        Self {
+            target_tournament_id: U64(t.target),
+            source_tournament_id: U64(t.source),
+            issued_at: U64(t.issued_at.micros()),
+            used: t.used,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct GqlDrawnSeat {
+    seat: i32,
+    player_id: U64,
+}
+
+#[derive(SimpleObject)]
+struct GqlTableDraw {
+    table_id: U64,
+    button: i32,
+    seats: Vec<GqlDrawnSeat>,
+}
+
+/// Жеребьёвка мест: seed из `base_seed`, высоты блока и времени старта
+/// и состава участников (всё это отдаётся для перепроверки).
+#[derive(SimpleObject)]
+struct GqlSeatDraw {
+    seed: U64,
+    base_seed: U64,
+    block_height: U64,
+    started_at: U64,
+    entrants: Vec<U64>,
+    drawn_at: U64,
+    tables: Vec<GqlTableDraw>,
+}
+
+/// Раунд рука-в-руку на пузыре.
+#[derive(SimpleObject)]
+struct GqlHandForHand {
+    round: i32,
+    /// Столы, доигравшие раздачу раунда и ждущие остальных.
+    waiting_tables: Vec<U64>,
+    /// Вылетевшие в раунде (места — после его конца).
+    pending_busts: Vec<U64>,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlDealKind {
+    Icm,
+    ChipChop,
+}
+
+impl From<GqlDealKind> for DealKind {
+    fn from(kind: GqlDealKind) -> Self {
+        match kind {
+            GqlDealKind::Icm => DealKind::Icm,
+            GqlDealKind::ChipChop => DealKind::ChipChop,
+        }
+    }
+}
+
+impl From<DealKind> for GqlDealKind {
+    fn from(kind: DealKind) -> Self {
+        match kind {
+            DealKind::Icm => GqlDealKind::Icm,
+            DealKind::ChipChop => GqlDealKind::ChipChop,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct GqlDealShare {
+    player_id: U64,
+    stack: U64,
+    amount: U64,
+    accepted: bool,
+}
+
+/// Сделка: расчёт, открытое предложение или принятая.
+#[derive(SimpleObject)]
+struct GqlDeal {
+    kind: GqlDealKind,
+    play_for: U64,
+    shares: Vec<GqlDealShare>,
+    proposer: Option<U64>,
+    /// Все согласились, суммы зафиксированы.
+    locked: bool,
+}
+
+fn deal_to_gql(
+    kind: DealKind,
+    play_for: Chips,
+    shares: Vec<DealShare>,
+    accepted: &[PlayerId],
+    proposer: Option<PlayerId>,
+    locked: bool,
+) -> GqlDeal {
+    GqlDeal {
+        kind: kind.into(),
+        play_for: chips_to_gql(play_for),
+        shares: shares
+            .into_iter()
+            .map(|s| GqlDealShare {
+                player_id: U64(s.player_id),
+                stack: chips_to_gql(s.stack),
+                amount: chips_to_gql(s.amount),
+                accepted: locked || accepted.contains(&s.player_id),
+            })
+            .collect(),
+        proposer: proposer.map(U64),
+        locked,
+    }
+}
+
+#[derive(SimpleObject)]
+struct GqlOwnership {
+    owner: Option<String>,
+    pending_owner: Option<String>,
+}
+
 #[derive(SimpleObject)]
 struct SummaryGql {
-    total_hands_played: i64,
+    total_hands_played: U64,
     tables_count: i32,
     tournaments_count: i32,
 }
@@ -154,6 +704,72 @@ struct MutationAck {
 //                       GQL ENUMЫ ДЛЯ INPUT (ANTE / ACTION)
 // ============================================================================
 
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlTableType {
+    Cash,
+    Tournament,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlGameVariant {
+    Holdem,
+    Omaha,
+    ShortDeck,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlBettingKind {
+    NoLimit,
+    PotLimit,
+    FixedLimit,
+    SpreadLimit,
+}
+
+/// Структура ставок стола. Для FixedLimit нужны smallBet и bigBet
+/// (maxBets по умолчанию 4), для SpreadLimit — minBet и maxBet.
+#[derive(InputObject)]
+struct GqlBettingInput {
+    structure: GqlBettingKind,
+    small_bet: Option<U64>,
+    big_bet: Option<U64>,
+    max_bets: Option<i32>,
+    min_bet: Option<U64>,
+    max_bet: Option<U64>,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlLobbySort {
+    AvgPot,
+    Players,
+    Stakes,
+}
+
+/// По какому показателю строить рейтинг игроков.
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlPlayerRanking {
+    NetCash,
+    NetTournament,
+    HandsPlayed,
+    TournamentCashes,
+    TournamentWinnings,
+}
+
+/// Показатель сезонного рейтинга.
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlSeasonRanking {
+    NetWinnings,
+    TournamentPoints,
+    HandsPlayed,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+enum GqlRole {
+    Admin,
+    TournamentDirector,
+    TableManager,
+    DealerBot,
+}
+
 #[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
 enum GqlAnteType {
     None,
@@ -175,11 +791,16 @@ enum GqlPlayerActionKind {
 //                         ВСПОМОГАТЕЛЬНЫЕ ФУНКЦИИ МАППИНГА
 // ============================================================================
 
-fn chips_to_i64(chips: Chips) -> i64 {
-    serde_json::to_value(chips)
-        .ok()
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0) as i64
+fn map_gql_ante_type(ante_type: GqlAnteType) -> AnteTypeApi {
+    match ante_type {
+        GqlAnteType::None => AnteTypeApi::None,
+        GqlAnteType::Classic => AnteTypeApi::Classic,
+        GqlAnteType::BigBlind => AnteTypeApi::BigBlind,
+    }
+}
+
+fn chips_to_gql(chips: Chips) -> U64 {
+    U64(chips.0)
 }
 
 fn card_to_gql(card: &Card) -> GqlCard {
@@ -203,7 +824,7 @@ fn card_to_gql(card: &Card) -> GqlCard {
     GqlCard { rank, suit }
 }
 
-fn table_dto_to_gql(dto: &TableViewDto) -> GqlTableView {
+fn table_dto_to_gql(dto: &TableViewDto, rules: &TableRules) -> GqlTableView {
     // street как String без ссылок
     let street_val: JsonValue =
         serde_json::to_value(&dto.street).unwrap_or(JsonValue::Null);
@@ -229,11 +850,11 @@ fn table_dto_to_gql(dto: &TableViewDto) -> GqlTableView {
             });
 
             GqlPlayerAtTable {
-                player_id: p.player_id as i64,
+                player_id: U64(p.player_id),
                 display_name: p.display_name.clone(),
                 seat_index: p.seat_index as i32,
-                stack: chips_to_i64(p.stack),
-                current_bet: chips_to_i64(p.current_bet),
+                stack: chips_to_gql(p.stack),
+                current_bet: chips_to_gql(p.current_bet),
                 status,
                 hole_cards,
             }
@@ -241,15 +862,18 @@ fn table_dto_to_gql(dto: &TableViewDto) -> GqlTableView {
         .collect::<Vec<_>>();
 
     GqlTableView {
-        table_id: dto.table_id as i64,
+        table_id: U64(dto.table_id),
         name: dto.name.clone(),
+        variant: variant_to_gql(rules.variant),
+        betting: betting_kind_to_gql(rules.betting()),
+        button_ante: rules.button_ante.map(chips_to_gql),
         max_seats: dto.max_seats as i32,
-        small_blind: chips_to_i64(dto.small_blind),
-        big_blind: chips_to_i64(dto.big_blind),
-        ante: chips_to_i64(dto.ante),
+        small_blind: chips_to_gql(dto.small_blind),
+        big_blind: chips_to_gql(dto.big_blind),
+        ante: chips_to_gql(dto.ante),
         street,
         dealer_button: dto.dealer_button.map(|d| d as i32),
-        total_pot: chips_to_i64(dto.total_pot),
+        total_pot: chips_to_gql(dto.total_pot),
         board: dto.board.iter().map(card_to_gql).collect(),
         players,
         hand_in_progress: dto.hand_in_progress,
@@ -259,7 +883,7 @@ fn table_dto_to_gql(dto: &TableViewDto) -> GqlTableView {
 
 fn tournament_dto_to_gql(dto: &TournamentViewDto) -> GqlTournamentView {
     GqlTournamentView {
-        tournament_id: dto.tournament_id as i64,
+        tournament_id: U64(dto.tournament_id),
         name: dto.name.clone(),
         status: dto.status.clone(),
         current_level: dto.current_level as i32,
@@ -268,8 +892,292 @@ fn tournament_dto_to_gql(dto: &TournamentViewDto) -> GqlTournamentView {
     }
 }
 
-fn to_chips(value: i32) -> Chips {
-    Chips(value as u64)
+fn enum_to_string<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value).unwrap_or(JsonValue::Null) {
+        JsonValue::String(s) => s,
+        _ => String::new(),
+    }
+}
+
+fn action_kind_to_gql(kind: &PlayerActionKind) -> (String, Option<U64>) {
+    match kind {
+        PlayerActionKind::Fold => ("Fold".to_string(), None),
+        PlayerActionKind::Check => ("Check".to_string(), None),
+        PlayerActionKind::Call => ("Call".to_string(), None),
+        PlayerActionKind::Bet(c) => ("Bet".to_string(), Some(chips_to_gql(*c))),
+        PlayerActionKind::Raise(c) => ("Raise".to_string(), Some(chips_to_gql(*c))),
+        PlayerActionKind::AllIn => ("AllIn".to_string(), None),
+    }
+}
+
+fn hand_to_gql(hand: &ArchivedHand) -> GqlHandHistory {
+    let seats = hand
+        .seats
+        .iter()
+        .map(|s| GqlHandSeat {
+            seat_index: s.seat as i32,
+            player_id: U64(s.player_id),
+            display_name: s.display_name.clone(),
+            starting_stack: chips_to_gql(s.starting_stack),
+            final_stack: chips_to_gql(s.final_stack),
+            showdown_cards: s
+                .showdown_cards
+                .as_ref()
+                .map(|cards| cards.iter().map(card_to_gql).collect()),
+        })
+        .collect();
+
+    let actions = hand
+        .actions
+        .iter()
+        .map(|a| {
+            let (action, amount) = action_kind_to_gql(&a.kind);
+            GqlHandAction {
+                street: enum_to_string(&a.street),
+                seat_index: a.seat as i32,
+                player_id: U64(a.player_id),
+                action,
+                amount,
+                auto: a.auto,
+            }
+        })
+        .collect();
+
+    let winners = hand
+        .winners
+        .iter()
+        .map(|w| GqlHandWinner {
+            seat_index: w.seat as i32,
+            player_id: U64(w.player_id),
+            amount: chips_to_gql(w.amount),
+        })
+        .collect();
+
+    // SidePot — доменный тип, берём поле amount через serde.
+    let side_pots = hand
+        .side_pots
+        .iter()
+        .map(|sp| {
+            U64(
+                serde_json::to_value(sp)
+                    .ok()
+                    .and_then(|v| v.get("amount").and_then(|a| a.as_u64()))
+                    .unwrap_or(0),
+            )
+        })
+        .collect();
+
+    GqlHandHistory {
+        hand_id: U64(hand.hand_id),
+        table_id: U64(hand.table_id),
+        table_name: hand.table_name.clone(),
+        tournament_id: hand.tournament_id.map(U64),
+        variant: variant_to_gql(hand.variant),
+        small_blind: chips_to_gql(hand.small_blind),
+        big_blind: chips_to_gql(hand.big_blind),
+        ante: chips_to_gql(hand.ante),
+        dealer_button: hand.dealer_button.map(|b| b as i32),
+        started_at: U64(hand.started_at.micros()),
+        finished_at: hand.finished_at.map(|t| U64(t.micros())),
+        seats,
+        actions,
+        board: hand.board.iter().map(card_to_gql).collect(),
+        winners,
+        total_pot: chips_to_gql(hand.total_pot),
+        side_pots,
+    }
+}
+
+fn event_to_gql(index: u64, record: &EventRecord) -> GqlPokerEvent {
+    let mut out = GqlPokerEvent {
+        index: U64(index),
+        at: U64(record.at.micros()),
+        kind: String::new(),
+        table_id: record.event.table_id().map(U64),
+        tournament_id: record.event.tournament_id().map(U64),
+        hand_id: None,
+        seat_index: None,
+        player_id: None,
+        action: None,
+        amount: None,
+        auto: None,
+        street: None,
+        board: None,
+        winners: None,
+        dealer_button: None,
+        level: None,
+        multiplier: None,
+    };
+
+    match &record.event {
+        PokerEvent::HandStarted {
+            hand_id,
+            dealer_button,
+            ..
+        } => {
+            out.kind = "HandStarted".to_string();
+            out.hand_id = Some(U64(*hand_id));
+            out.dealer_button = dealer_button.map(|b| b as i32);
+        }
+        PokerEvent::ActionTaken {
+            hand_id,
+            seat,
+            player_id,
+            kind,
+            auto,
+            ..
+        } => {
+            let (action, amount) = action_kind_to_gql(kind);
+            out.kind = "ActionTaken".to_string();
+            out.hand_id = Some(U64(*hand_id));
+            out.seat_index = Some(*seat as i32);
+            out.player_id = Some(U64(*player_id));
+            out.action = Some(action);
+            out.amount = amount;
+            out.auto = Some(*auto);
+        }
+        PokerEvent::StreetDealt {
+            hand_id,
+            street,
+            board,
+            ..
+        } => {
+            out.kind = "StreetDealt".to_string();
+            out.hand_id = Some(U64(*hand_id));
+            out.street = Some(enum_to_string(street));
+            out.board = Some(board.iter().map(card_to_gql).collect());
+        }
+        PokerEvent::HandFinished {
+            hand_id, winners, ..
+        } => {
+            out.kind = "HandFinished".to_string();
+            out.hand_id = Some(U64(*hand_id));
+            out.winners = Some(
+                winners
+                    .iter()
+                    .map(|(player_id, amount)| GqlEventWinner {
+                        player_id: U64(*player_id),
+                        amount: chips_to_gql(*amount),
+                    })
+                    .collect(),
+            );
+        }
+        PokerEvent::PlayerSeated {
+            seat, player_id, ..
+        } => {
+            out.kind = "PlayerSeated".to_string();
+            out.seat_index = Some(*seat as i32);
+            out.player_id = Some(U64(*player_id));
+        }
+        PokerEvent::LevelChanged { level, .. } => {
+            out.kind = "LevelChanged".to_string();
+            out.level = Some(*level as i32);
+        }
+        PokerEvent::TournamentStarted {
+            prize_pool,
+            spin_multiplier,
+            ..
+        } => {
+            out.kind = "TournamentStarted".to_string();
+            out.amount = Some(chips_to_gql(*prize_pool));
+            out.multiplier = spin_multiplier.map(|m| m as i32);
+        }
+    }
+
+    out
+}
+
+/// Сколько записей ленты читаем из хранилища за раз.
+const EVENT_READ_BATCH: usize = 100;
+
+/// Размер страницы по умолчанию / максимум для запросов истории.
+const HAND_PAGE_DEFAULT: usize = 20;
+const HAND_PAGE_MAX: usize = 100;
+
+fn page_limit(limit: Option<i32>) -> usize {
+    limit
+        .map(|l| l.max(1) as usize)
+        .unwrap_or(HAND_PAGE_DEFAULT)
+        .min(HAND_PAGE_MAX)
+}
+
+/// Собрать страницу раздач: `ids` — hand_id страницы от новых к старым,
+/// `next_cursor` — номер последней записи индекса, если есть ещё.
+async fn load_hand_page(
+    state: &PokerState,
+    ids: &[HandId],
+    next_cursor: Option<u64>,
+) -> GqlResult<GqlHandHistoryPage> {
+    let mut hands = Vec::new();
+    for id in ids {
+        if let Some(hand) = state.hand_archive.get(id).await.map_err(storage_error)? {
+            hands.push(hand_to_gql(&hand));
+        }
+    }
+
+    Ok(GqlHandHistoryPage {
+        hands,
+        next_cursor: next_cursor.map(U64),
+        has_more: next_cursor.is_some(),
+    })
+}
+
+fn to_chips(value: U64) -> Chips {
+    Chips(value.0)
+}
+
+/// i32 из GraphQL → u8 (места, размер стола) с проверкой диапазона.
+fn to_u8(value: i32, field: &str) -> Result<u8, MutationAck> {
+    u8::try_from(value).map_err(|_| MutationAck {
+        ok: false,
+        message: format!("{field} out of range: {value}"),
+    })
+}
+
+/// ChainId из строки аргумента мутации.
+fn to_chain_id(value: &str, field: &str) -> Result<ChainId, MutationAck> {
+    ChainId::from_str(value).map_err(|e| MutationAck {
+        ok: false,
+        message: format!("invalid {field} {value}: {e}"),
+    })
+}
+
+// ============================================================================
+//                              GQL ОШИБКИ
+// ============================================================================
+
+/// Коды ошибок, которые клиент видит в `extensions.code`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum GqlErrorCode {
+    TableNotFound,
+    TournamentNotFound,
+    HandNotFound,
+    PlayerNotFound,
+    SeasonNotFound,
+    Storage,
+}
+
+impl GqlErrorCode {
+    fn as_str(self) -> &'static str {
+        match self {
+            GqlErrorCode::TableNotFound => "TABLE_NOT_FOUND",
+            GqlErrorCode::TournamentNotFound => "TOURNAMENT_NOT_FOUND",
+            GqlErrorCode::HandNotFound => "HAND_NOT_FOUND",
+            GqlErrorCode::PlayerNotFound => "PLAYER_NOT_FOUND",
+            GqlErrorCode::SeasonNotFound => "SEASON_NOT_FOUND",
+            GqlErrorCode::Storage => "STORAGE",
+        }
+    }
+}
+
+/// Ошибка резолвера с кодом в extensions.
+fn gql_error(code: GqlErrorCode, message: impl Into<String>) -> GqlError {
+    GqlError::new(message).extend_with(|_, ext| ext.set("code", code.as_str()))
+}
+
+/// Любая ошибка view-хранилища → STORAGE.
+fn storage_error(err: ViewError) -> GqlError {
+    gql_error(GqlErrorCode::Storage, format!("storage error: {err}"))
 }
 
 // ============================================================================
@@ -280,7 +1188,7 @@ async fn build_table_view_for_service(
     state: &PokerState,
     table: &Table,
     active: Option<&HandEngineSnapshot>,
-) -> TableViewDto {
+) -> GqlResult<TableViewDto> {
     let current_actor_seat: Option<u8> =
         active.and_then(|s| s.current_actor).map(|s| s as u8);
 
@@ -294,7 +1202,7 @@ async fn build_table_view_for_service(
                 .player_names
                 .get(&player_id)
                 .await
-                .unwrap_or_else(|_| Some(format!("Player #{}", player_id)))
+                .map_err(storage_error)?
                 .unwrap_or_else(|| format!("Player #{}", player_id));
 
             players.push(PlayerAtTableDto {
@@ -309,7 +1217,7 @@ async fn build_table_view_for_service(
         }
     }
 
-    TableViewDto {
+    Ok(TableViewDto {
         table_id: table.id,
         name: table.name.clone(),
         max_seats: table.config.max_seats,
@@ -323,603 +1231,2279 @@ async fn build_table_view_for_service(
         players,
         hand_in_progress: table.hand_in_progress,
         current_actor_seat,
-    }
+    })
 }
 
-// ============================================================================
-//                                 QUERY ROOT
+/// Стол + активная раздача → GqlTableView.
+async fn table_to_gql(state: &PokerState, table: &Table) -> GqlResult<GqlTableView> {
+    let active = state
+        .active_hands
+        .get(&table.id)
+        .await
+        .map_err(storage_error)?
+        .flatten();
+
+    let rules = table_rules_of(state, table.id).await?;
+
+    let dto = build_table_view_for_service(state, table, active.as_ref()).await?;
+    Ok(table_dto_to_gql(&dto, &rules))
+}
+
+async fn table_rules_of(state: &PokerState, table_id: TableId) -> GqlResult<TableRules> {
+    Ok(state
+        .table_rules
+        .get(&table_id)
+        .await
+        .map_err(storage_error)?
+        .unwrap_or_default())
+}
+
+fn variant_to_gql(variant: GameVariant) -> GqlGameVariant {
+    match variant {
+        GameVariant::Holdem => GqlGameVariant::Holdem,
+        GameVariant::Omaha => GqlGameVariant::Omaha,
+        GameVariant::ShortDeck => GqlGameVariant::ShortDeck,
+    }
+}
+
+fn variant_from_gql(variant: GqlGameVariant) -> GameVariant {
+    match variant {
+        GqlGameVariant::Holdem => GameVariant::Holdem,
+        GqlGameVariant::Omaha => GameVariant::Omaha,
+        GqlGameVariant::ShortDeck => GameVariant::ShortDeck,
+    }
+}
+
+fn betting_kind_to_gql(betting: BettingStructure) -> GqlBettingKind {
+    match betting {
+        BettingStructure::NoLimit => GqlBettingKind::NoLimit,
+        BettingStructure::PotLimit => GqlBettingKind::PotLimit,
+        BettingStructure::FixedLimit { .. } => GqlBettingKind::FixedLimit,
+        BettingStructure::SpreadLimit { .. } => GqlBettingKind::SpreadLimit,
+    }
+}
+
+/// Правила стола из аргументов мутации.
+fn rules_from_gql(
+    variant: Option<GqlGameVariant>,
+    betting: Option<GqlBettingInput>,
+    button_ante: Option<U64>,
+) -> Result<TableRules, MutationAck> {
+    let required = |value: Option<U64>, field: &str| {
+        value.map(to_chips).ok_or_else(|| MutationAck {
+            ok: false,
+            message: format!("{field} is required for this betting structure"),
+        })
+    };
+
+    let betting = match betting {
+        None => BettingStructure::NoLimit,
+        Some(input) => match input.structure {
+            GqlBettingKind::NoLimit => BettingStructure::NoLimit,
+            GqlBettingKind::PotLimit => BettingStructure::PotLimit,
+            GqlBettingKind::FixedLimit => BettingStructure::FixedLimit {
+                small_bet: required(input.small_bet, "small_bet")?,
+                big_bet: required(input.big_bet, "big_bet")?,
+                max_bets: to_u8(input.max_bets.unwrap_or(4), "max_bets")?,
+            },
+            GqlBettingKind::SpreadLimit => BettingStructure::SpreadLimit {
+                min_bet: required(input.min_bet, "min_bet")?,
+                max_bet: required(input.max_bet, "max_bet")?,
+            },
+        },
+    };
+
+    Ok(TableRules {
+        variant: variant.map(variant_from_gql).unwrap_or_default(),
+        betting,
+        button_ante: button_ante.map(to_chips),
+    })
+}
+
+/// Турнир → GqlTournamentView (с количеством живых столов).
+async fn tournament_to_gql(
+    state: &PokerState,
+    tournament: &Tournament,
+) -> GqlResult<GqlTournamentView> {
+    let tables_running = state
+        .tournament_tables
+        .get(&tournament.id)
+        .await
+        .map_err(storage_error)?
+        .map(|v| v.len() as u32)
+        .unwrap_or(0);
+
+    let dto = build_tournament_view(tournament, tables_running);
+    Ok(tournament_dto_to_gql(&dto))
+}
+
+// ============================================================================
+//                            ТАБЛИЦА ТУРНИРА
+// ============================================================================
+
+async fn load_tournament_or_error(
+    state: &PokerState,
+    tournament_id: TournamentId,
+) -> GqlResult<Tournament> {
+    state
+        .tournaments
+        .get(&tournament_id)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(|| {
+            gql_error(
+                GqlErrorCode::TournamentNotFound,
+                format!("tournament {tournament_id} not found"),
+            )
+        })
+}
+
+async fn standing_to_gql(state: &PokerState, s: &Standing) -> GqlResult<GqlStanding> {
+    Ok(GqlStanding {
+        rank: s.rank as i32,
+        player_id: U64(s.player_id),
+        display_name: state.player_names.get(&s.player_id).await.map_err(storage_error)?,
+        chips: chips_to_gql(s.chips),
+        table_id: s.table_id.map(U64),
+        seat_index: s.seat_index.map(|i| i as i32),
+    })
+}
+
+async fn finish_to_gql(state: &PokerState, f: &TournamentFinish) -> GqlResult<GqlTournamentFinish> {
+    Ok(GqlTournamentFinish {
+        place: f.place as i32,
+        player_id: U64(f.player_id),
+        display_name: state.player_names.get(&f.player_id).await.map_err(storage_error)?,
+        prize: chips_to_gql(f.prize),
+        ticket_tournament_id: f.ticket.map(U64),
+        finished_at: U64(f.at.micros()),
+    })
+}
+
+/// Призовой фонд: бай-ины (с множителем спина), у фрироллов — сумма призовых.
+/// Все столы турнира: разыгранные на старте (включая уже закрытые)
+/// и текущие.
+async fn tournament_table_ids(
+    state: &PokerState,
+    tournament_id: TournamentId,
+) -> GqlResult<HashSet<TableId>> {
+    let mut ids: HashSet<TableId> = state
+        .tournament_tables
+        .get(&tournament_id)
+        .await
+        .map_err(storage_error)?
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    if let Some(draw) = state.seat_draws.get(&tournament_id).await.map_err(storage_error)? {
+        ids.extend(draw.tables.iter().map(|t| t.table_id));
+    }
+    Ok(ids)
+}
+
+async fn tournament_prize_pool(
+    state: &PokerState,
+    tournament: &Tournament,
+    payouts: &[Chips],
+) -> GqlResult<Chips> {
+    let buy_in = state
+        .tournament_buy_ins
+        .get(&tournament.id)
+        .await
+        .map_err(storage_error)?;
+    let multiplier = state
+        .spin_multipliers
+        .get(&tournament.id)
+        .await
+        .map_err(storage_error)?
+        .unwrap_or(1);
+
+    let overflow = || GqlError::new(format!("prize pool of tournament {} overflows", tournament.id));
+    let pool = match buy_in {
+        Some(buy_in) => buy_in
+            .0
+            .checked_mul(tournament.registrations.len() as u64)
+            .and_then(|pool| pool.checked_mul(u64::from(multiplier))),
+        None => payouts.iter().try_fold(0u64, |sum, p| sum.checked_add(p.0)),
+    };
+    pool.map(Chips).ok_or_else(overflow)
+}
+
+// ============================================================================
+//                                   РОЛИ
+// ============================================================================
+
+fn role_from_gql(role: GqlRole) -> Role {
+    match role {
+        GqlRole::Admin => Role::Admin,
+        GqlRole::TournamentDirector => Role::TournamentDirector,
+        GqlRole::TableManager => Role::TableManager,
+        GqlRole::DealerBot => Role::DealerBot,
+    }
+}
+
+fn role_to_gql(role: Role) -> GqlRole {
+    match role {
+        Role::Admin => GqlRole::Admin,
+        Role::TournamentDirector => GqlRole::TournamentDirector,
+        Role::TableManager => GqlRole::TableManager,
+        Role::DealerBot => GqlRole::DealerBot,
+    }
+}
+
+fn role_assignment_to_gql(a: &RoleAssignment) -> GqlRoleAssignment {
+    GqlRoleAssignment {
+        role: role_to_gql(a.role),
+        table_id: a.table_id.map(U64),
+    }
+}
+
+fn role_change_to_gql(index: u64, change: &RoleChange) -> GqlRoleChange {
+    let (kind, assignment, previous_owner) = match &change.kind {
+        RoleChangeKind::Granted(a) => ("GRANTED", Some(a), None),
+        RoleChangeKind::Revoked(a) => ("REVOKED", Some(a), None),
+        RoleChangeKind::OwnershipProposed => ("OWNERSHIP_PROPOSED", None, None),
+        RoleChangeKind::OwnershipAccepted { previous } => (
+            "OWNERSHIP_ACCEPTED",
+            None,
+            previous.map(|o| o.to_string()),
+        ),
+    };
+
+    GqlRoleChange {
+        index: U64(index),
+        at: U64(change.at.micros()),
+        by: change.by.to_string(),
+        account: change.account.to_string(),
+        kind: kind.to_string(),
+        role: assignment.map(|a| role_to_gql(a.role)),
+        table_id: assignment.and_then(|a| a.table_id).map(U64),
+        previous_owner,
+    }
+}
+
+/// AccountOwner из строки аргумента мутации.
+fn to_account(value: &str, field: &str) -> Result<AccountOwner, MutationAck> {
+    AccountOwner::from_str(value).map_err(|e| MutationAck {
+        ok: false,
+        message: format!("invalid {field} {value}: {e}"),
+    })
+}
+
+// ============================================================================
+//                                  ИГРОКИ
+// ============================================================================
+
+fn preferences_to_gql(p: &PlayerPreferences) -> GqlPlayerPreferences {
+    GqlPlayerPreferences {
+        auto_muck: p.auto_muck,
+        four_color_deck: p.four_color_deck,
+        auto_rebuy: p.auto_rebuy,
+    }
+}
+
+fn player_stats_to_gql(s: &PlayerStats) -> GqlPlayerStats {
+    GqlPlayerStats {
+        hands_played: U64(s.hands_played),
+        vpip: s.vpip(),
+        pfr: s.pfr(),
+        aggression_factor: s.aggression_factor(),
+        showdowns: U64(s.showdowns),
+        showdowns_won: U64(s.showdowns_won),
+        showdown_win_rate: s.showdown_win_rate(),
+        net_cash: I64(s.net_cash),
+        net_tournament: I64(s.net_tournament),
+        tournaments_played: U64(s.tournaments_played),
+        tournament_cashes: U64(s.tournament_cashes),
+        tournament_winnings: chips_to_gql(s.tournament_winnings),
+    }
+}
+
+fn ranking_value(s: &PlayerStats, by: GqlPlayerRanking) -> i64 {
+    match by {
+        GqlPlayerRanking::NetCash => s.net_cash,
+        GqlPlayerRanking::NetTournament => s.net_tournament,
+        GqlPlayerRanking::HandsPlayed => s.hands_played as i64,
+        GqlPlayerRanking::TournamentCashes => s.tournament_cashes as i64,
+        GqlPlayerRanking::TournamentWinnings => s.tournament_winnings.0 as i64,
+    }
+}
+
+fn season_value(e: &SeasonEntry, by: GqlSeasonRanking) -> i64 {
+    match by {
+        GqlSeasonRanking::NetWinnings => e.net_winnings,
+        GqlSeasonRanking::TournamentPoints => e.tournament_points as i64,
+        GqlSeasonRanking::HandsPlayed => e.hands_played as i64,
+    }
+}
+
+/// Таблица сезона, отсортированная по показателю (при равенстве — по player_id).
+///
+/// `season_id = None` — текущий сезон, иначе — из архива
+/// (номера сезонов — u32, большее значение — ошибка запроса).
+async fn ranked_season(
+    state: &PokerState,
+    season_id: Option<U64>,
+    by: GqlSeasonRanking,
+) -> GqlResult<Vec<(PlayerId, i64)>> {
+    let standings: Vec<(PlayerId, SeasonEntry)> = match season_id {
+        None => {
+            let mut rows = Vec::new();
+            for pid in state.season_standings.indices().await.map_err(storage_error)? {
+                if let Some(e) = state.season_standings.get(&pid).await.map_err(storage_error)? {
+                    rows.push((pid, e));
+                }
+            }
+            rows
+        }
+        Some(U64(id)) => {
+            let id = u32::try_from(id)
+                .map_err(|_| GqlError::new(format!("seasonId {id} is out of range")))?;
+            let archived = state
+                .season_archive
+                .get(&id)
+                .await
+                .map_err(storage_error)?
+                .ok_or_else(|| {
+                    gql_error(GqlErrorCode::SeasonNotFound, format!("season {id} not found"))
+                })?;
+            archived.standings
+        }
+    };
+
+    let mut ranked: Vec<(PlayerId, i64)> = standings
+        .iter()
+        .map(|(pid, e)| (*pid, season_value(e, by)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    Ok(ranked)
+}
+
+async fn leaderboard_entry(
+    state: &PokerState,
+    rank: usize,
+    player_id: PlayerId,
+    value: i64,
+) -> GqlResult<GqlLeaderboardEntry> {
+    Ok(GqlLeaderboardEntry {
+        rank: rank as i32,
+        player_id: U64(player_id),
+        display_name: state.player_names.get(&player_id).await.map_err(storage_error)?,
+        value: I64(value),
+    })
+}
+
+// ============================================================================
+//                                   ЛОББИ
+// ============================================================================
+
+/// Фильтры лобби; `None` — без ограничения.
+struct LobbyFilter {
+    table_type: Option<GqlTableType>,
+    min_big_blind: Option<u64>,
+    max_big_blind: Option<u64>,
+    min_free_seats: Option<i32>,
+    variant: Option<GqlGameVariant>,
+    hand_in_progress: Option<bool>,
+}
+
+impl LobbyFilter {
+    fn matches(&self, t: &GqlLobbyTable) -> bool {
+        self.table_type.is_none_or(|v| t.table_type == v)
+            && self.min_big_blind.is_none_or(|v| t.big_blind.0 >= v)
+            && self.max_big_blind.is_none_or(|v| t.big_blind.0 <= v)
+            && self.min_free_seats.is_none_or(|v| t.free_seats >= v)
+            && self.variant.is_none_or(|v| t.variant == v)
+            && self.hand_in_progress.is_none_or(|v| t.hand_in_progress == v)
+    }
+}
+
+fn lobby_table(table: &Table, stats: TableStats, rules: &TableRules) -> GqlLobbyTable {
+    let players = table.seats.iter().filter(|s| s.is_some()).count() as i32;
+
+    GqlLobbyTable {
+        table_id: U64(table.id),
+        name: table.name.clone(),
+        table_type: if table.config.table_type == TableType::Tournament {
+            GqlTableType::Tournament
+        } else {
+            GqlTableType::Cash
+        },
+        variant: variant_to_gql(rules.variant),
+        betting: betting_kind_to_gql(rules.betting()),
+        max_seats: table.config.max_seats as i32,
+        players,
+        free_seats: table.config.max_seats as i32 - players,
+        small_blind: chips_to_gql(table.config.stakes.small_blind),
+        big_blind: chips_to_gql(table.config.stakes.big_blind),
+        ante: chips_to_gql(table.config.stakes.ante),
+        hand_in_progress: table.hand_in_progress,
+        hands_played: U64(stats.hands_played),
+        avg_pot: chips_to_gql(stats.average_pot()),
+    }
+}
+
+fn lobby_sort_key(t: &GqlLobbyTable, sort: GqlLobbySort) -> u64 {
+    match sort {
+        GqlLobbySort::AvgPot => t.avg_pot.0,
+        GqlLobbySort::Players => t.players as u64,
+        GqlLobbySort::Stakes => t.big_blind.0,
+    }
+}
+
+/// Курсор лобби: `"<ключ сортировки>:<table_id>"` последней карточки страницы.
+fn parse_lobby_cursor(cursor: &str) -> GqlResult<(u64, TableId)> {
+    cursor
+        .split_once(':')
+        .and_then(|(k, id)| Some((k.parse().ok()?, id.parse().ok()?)))
+        .ok_or_else(|| GqlError::new(format!("invalid lobby cursor: {cursor:?}")))
+}
+
+// ============================================================================
+//                                 QUERY ROOT
 // ============================================================================
 
+/// Все резолверы работают с одним и тем же состоянием,
+/// загруженным один раз при создании сервиса.
 struct QueryRoot {
-    runtime: Arc<ServiceRuntime<PokerService>>,
-    storage_context: ViewStorageContext,
+    state: Arc<PokerState>,
 }
 
 #[Object]
 impl QueryRoot {
-    async fn summary(&self) -> SummaryGql {
-        let state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in summary");
+    async fn summary(&self) -> GqlResult<SummaryGql> {
+        let state = &self.state;
 
-        let total_hands_played = *state.total_hands_played.get() as i64;
+        let total_hands_played = U64(*state.total_hands_played.get());
 
         let tables_count = state
             .tables
             .indices()
             .await
-            .unwrap_or_default()
+            .map_err(storage_error)?
             .len() as i32;
 
         let tournaments_count = state
             .tournaments
             .indices()
             .await
-            .unwrap_or_default()
+            .map_err(storage_error)?
             .len() as i32;
 
-        SummaryGql {
+        Ok(SummaryGql {
             total_hands_played,
             tables_count,
             tournaments_count,
-        }
+        })
     }
 
-    async fn table(&self, table_id: i32) -> Option<GqlTableView> {
-        let mut state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in table query");
-
-        let table_id: TableId = table_id as u64;
+    async fn table(&self, table_id: U64) -> GqlResult<GqlTableView> {
+        let state = &self.state;
+        let table_id: TableId = table_id.0;
 
-        let table_opt = state
+        let table = state
             .tables
             .get(&table_id)
             .await
-            .expect("tables.get error");
-
-        let table = match table_opt {
-            Some(t) => t,
-            None => return None,
-        };
-
-        let active = state
-            .active_hands
-            .get(&table_id)
-            .await
-            .expect("active_hands.get error")
-            .flatten();
-
-        let dto =
-            build_table_view_for_service(&state, &table, active.as_ref()).await;
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                gql_error(
+                    GqlErrorCode::TableNotFound,
+                    format!("table {table_id} not found"),
+                )
+            })?;
 
-        Some(table_dto_to_gql(&dto))
+        table_to_gql(state, &table).await
     }
 
-    async fn tables(&self) -> Vec<GqlTableView> {
-        let mut state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in tables query");
+    async fn tables(&self) -> GqlResult<Vec<GqlTableView>> {
+        let state = &self.state;
 
-        let ids = state
-            .tables
-            .indices()
-            .await
-            .expect("tables.indices error");
+        let ids = state.tables.indices().await.map_err(storage_error)?;
 
         let mut out = Vec::new();
 
         for id in ids {
-            if let Some(table) =
-                state.tables.get(&id).await.unwrap_or(None)
-            {
-                let active = state
-                    .active_hands
-                    .get(&id)
-                    .await
-                    .unwrap_or(None)
-                    .flatten();
+            if let Some(table) = state.tables.get(&id).await.map_err(storage_error)? {
+                out.push(table_to_gql(state, &table).await?);
+            }
+        }
 
-                let dto = build_table_view_for_service(
-                    &state,
-                    &table,
-                    active.as_ref(),
-                )
-                .await;
+        Ok(out)
+    }
+
+    /// Лобби: лёгкие карточки столов с фильтрами, сортировкой и курсором.
+    ///
+    /// Сортировка по `sortBy` (по умолчанию — игроки), по убыванию,
+    /// если не задано `descending: false`; при равенстве — по table_id.
+    async fn lobby(
+        &self,
+        table_type: Option<GqlTableType>,
+        min_big_blind: Option<U64>,
+        max_big_blind: Option<U64>,
+        min_free_seats: Option<i32>,
+        variant: Option<GqlGameVariant>,
+        hand_in_progress: Option<bool>,
+        sort_by: Option<GqlLobbySort>,
+        descending: Option<bool>,
+        after: Option<String>,
+        limit: Option<i32>,
+    ) -> GqlResult<GqlLobbyPage> {
+        let state = &self.state;
+        let filter = LobbyFilter {
+            table_type,
+            min_big_blind: min_big_blind.map(|v| v.0),
+            max_big_blind: max_big_blind.map(|v| v.0),
+            min_free_seats,
+            variant,
+            hand_in_progress,
+        };
+        let sort = sort_by.unwrap_or(GqlLobbySort::Players);
+        let descending = descending.unwrap_or(true);
+        let after = after.as_deref().map(parse_lobby_cursor).transpose()?;
+        let limit = page_limit(limit);
+
+        let mut rows = Vec::new();
+        for id in state.tables.indices().await.map_err(storage_error)? {
+            let Some(table) = state.tables.get(&id).await.map_err(storage_error)?
+            else {
+                continue;
+            };
+            let stats = state
+                .table_stats
+                .get(&id)
+                .await
+                .map_err(storage_error)?
+                .unwrap_or_default();
+
+            let rules = table_rules_of(state, id).await?;
 
-                out.push(table_dto_to_gql(&dto));
+            let row = lobby_table(&table, stats, &rules);
+            if filter.matches(&row) {
+                rows.push((lobby_sort_key(&row, sort), row));
             }
         }
 
-        out
+        let order = |a: &(u64, TableId), b: &(u64, TableId)| {
+            let by_key = if descending { b.0.cmp(&a.0) } else { a.0.cmp(&b.0) };
+            by_key.then(a.1.cmp(&b.1))
+        };
+        rows.sort_by(|(ka, a), (kb, b)| {
+            order(&(*ka, a.table_id.0), &(*kb, b.table_id.0))
+        });
+
+        let mut iter = rows.into_iter().filter(|(key, row)| {
+            after.is_none_or(|c| order(&c, &(*key, row.table_id.0)).is_lt())
+        });
+
+        let page: Vec<(u64, GqlLobbyTable)> = iter.by_ref().take(limit).collect();
+        let has_more = iter.next().is_some();
+        let next_cursor = page
+            .last()
+            .map(|(key, row)| format!("{key}:{}", row.table_id.0));
+
+        Ok(GqlLobbyPage {
+            tables: page.into_iter().map(|(_, row)| row).collect(),
+            next_cursor,
+            has_more,
+        })
     }
 
-    async fn tournaments(&self) -> Vec<GqlTournamentView> {
-        let mut state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in tournaments query");
+    async fn tournaments(&self) -> GqlResult<Vec<GqlTournamentView>> {
+        let state = &self.state;
 
-        let ids = state
-            .tournaments
-            .indices()
-            .await
-            .expect("tournaments.indices error");
+        let ids = state.tournaments.indices().await.map_err(storage_error)?;
 
         let mut out = Vec::new();
 
         for id in ids {
-            if let Some(t) =
-                state.tournaments.get(&id).await.unwrap_or(None)
-            {
-                let tables_running = state
-                    .tournament_tables
-                    .get(&id)
-                    .await
-                    .unwrap_or(None)
-                    .map(|v| v.len() as u32)
-                    .unwrap_or(0);
-
-                let dto = build_tournament_view(&t, tables_running);
-                out.push(tournament_dto_to_gql(&dto));
+            if let Some(t) = state.tournaments.get(&id).await.map_err(storage_error)? {
+                out.push(tournament_to_gql(state, &t).await?);
             }
         }
 
-        out
+        Ok(out)
     }
 
     async fn tournament_by_id(
         &self,
-        tournament_id: i32,
-    ) -> Option<GqlTournamentView> {
-        let mut state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in tournament_by_id query");
-
-        let tournament_id: TournamentId = tournament_id as u64;
+        tournament_id: U64,
+    ) -> GqlResult<GqlTournamentView> {
+        let state = &self.state;
+        let tournament_id: TournamentId = tournament_id.0;
 
-        let t_opt = state
+        let t = state
             .tournaments
             .get(&tournament_id)
             .await
-            .unwrap_or(None);
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                gql_error(
+                    GqlErrorCode::TournamentNotFound,
+                    format!("tournament {tournament_id} not found"),
+                )
+            })?;
+
+        tournament_to_gql(state, &t).await
+    }
+
+    /// Живая таблица турнира: стеки и места, блайнды, призы, вылетевшие.
+    async fn tournament_standings(
+        &self,
+        tournament_id: U64,
+    ) -> GqlResult<GqlTournamentStandings> {
+        let state = &self.state;
+        let t = load_tournament_or_error(state, tournament_id.0).await?;
+
+        let live = live_standings(&t);
+        let mut standings = Vec::with_capacity(live.len());
+        for s in &live {
+            standings.push(standing_to_gql(state, s).await?);
+        }
+
+        let mut results = state
+            .tournament_results
+            .get(&t.id)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default();
+        results.sort_by_key(|f| f.place);
+        let mut eliminated = Vec::with_capacity(results.len());
+        for f in &results {
+            eliminated.push(finish_to_gql(state, f).await?);
+        }
+
+        let payouts = state
+            .tournament_payouts
+            .get(&t.id)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default();
+        let prize_pool = tournament_prize_pool(state, &t, &payouts).await?;
+
+        let (small_blind, big_blind, ante) = t
+            .config
+            .blind_structure
+            .level_by_number(t.current_level)
+            .map(|l| (l.small_blind, l.big_blind, l.ante))
+            .unwrap_or((Chips::ZERO, Chips::ZERO, Chips::ZERO));
+
+        let level_started_at = state
+            .tournament_level_started
+            .get(&t.id)
+            .await
+            .map_err(storage_error)?;
+        let has_next_level = t
+            .config
+            .blind_structure
+            .level_by_number(t.current_level.saturating_add(1))
+            .is_some();
+        let level_duration = state
+            .tournament_level_durations
+            .get(&t.id)
+            .await
+            .map_err(storage_error)?;
+        let next_level_at = level_started_at
+            .filter(|_| has_next_level)
+            .zip(level_duration)
+            .map(|(at, secs)| U64(next_level_at(at, secs)));
+
+        let dto = build_tournament_view(&t, 0);
+
+        Ok(GqlTournamentStandings {
+            tournament_id: U64(t.id),
+            name: dto.name,
+            status: dto.status,
+            current_level: t.current_level as i32,
+            small_blind: chips_to_gql(small_blind),
+            big_blind: chips_to_gql(big_blind),
+            ante: chips_to_gql(ante),
+            level_started_at: level_started_at.map(|at| U64(at.micros())),
+            next_level_at,
+            players_registered: t.registrations.len() as i32,
+            players_remaining: live.len() as i32,
+            average_stack: chips_to_gql(average_stack(&live)),
+            prize_pool: chips_to_gql(prize_pool),
+            payouts: payouts.into_iter().map(chips_to_gql).collect(),
+            standings,
+            eliminated,
+        })
+    }
 
-        let t = match t_opt {
-            Some(t) => t,
-            None => return None,
+    /// Турнир игрока: стол, место, стек и позиция среди живых или финиш.
+    async fn my_tournament(
+        &self,
+        tournament_id: U64,
+        player_id: U64,
+    ) -> GqlResult<Option<GqlMyTournament>> {
+        let state = &self.state;
+        let t = load_tournament_or_error(state, tournament_id.0).await?;
+
+        let Some(reg) = t.registrations.get(&player_id.0) else {
+            return Ok(None);
         };
 
-        let tables_running = state
-            .tournament_tables
-            .get(&tournament_id)
+        let live = live_standings(&t);
+        let rank = live
+            .iter()
+            .find(|s| s.player_id == player_id.0)
+            .map(|s| s.rank as i32);
+
+        let finish = match state
+            .tournament_results
+            .get(&t.id)
             .await
-            .unwrap_or(None)
-            .map(|v| v.len() as u32)
-            .unwrap_or(0);
+            .map_err(storage_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|f| f.player_id == player_id.0)
+        {
+            Some(f) => Some(finish_to_gql(state, &f).await?),
+            None => None,
+        };
 
-        let dto = build_tournament_view(&t, tables_running);
-        Some(tournament_dto_to_gql(&dto))
+        let busted = reg.is_busted;
+        Ok(Some(GqlMyTournament {
+            tournament_id: U64(t.id),
+            player_id,
+            status: build_tournament_view(&t, 0).status,
+            chips: chips_to_gql(reg.total_chips),
+            rank,
+            players_remaining: live.len() as i32,
+            table_id: reg.table_id.filter(|_| !busted).map(U64),
+            seat_index: reg.seat_index.filter(|_| !busted).map(|i| i as i32),
+            finish,
+        }))
     }
 
     async fn tournament_tables(
         &self,
-        tournament_id: i32,
-    ) -> Vec<GqlTableView> {
-        let mut state =
-            PokerState::load(self.storage_context.clone())
-                .await
-                .expect("Failed to load state in tournament_tables query");
+        tournament_id: U64,
+    ) -> GqlResult<Vec<GqlTableView>> {
+        let state = &self.state;
+        let tournament_id: TournamentId = tournament_id.0;
 
-        let tournament_id: TournamentId = tournament_id as u64;
-
-        let table_ids_opt = state
+        let table_ids = state
             .tournament_tables
             .get(&tournament_id)
             .await
-            .unwrap_or(None);
-
-        let table_ids = match table_ids_opt {
-            Some(v) => v,
-            None => return Vec::new(),
-        };
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                gql_error(
+                    GqlErrorCode::TournamentNotFound,
+                    format!("tournament {tournament_id} not found"),
+                )
+            })?;
 
         let mut out = Vec::new();
 
         for tid in table_ids {
-            if let Some(table) =
-                state.tables.get(&tid).await.unwrap_or(None)
-            {
-                let active = state
-                    .active_hands
-                    .get(&tid)
-                    .await
-                    .unwrap_or(None)
-                    .flatten();
+            if let Some(table) = state.tables.get(&tid).await.map_err(storage_error)? {
+                out.push(table_to_gql(state, &table).await?);
+            }
+        }
 
-                let dto = build_table_view_for_service(
-                    &state,
-                    &table,
-                    active.as_ref(),
-                )
-                .await;
+        Ok(out)
+    }
+
+    /// Одна завершённая раздача из архива.
+    async fn hand_history(&self, hand_id: U64) -> GqlResult<GqlHandHistory> {
+        let hand = self.load_archived_hand(hand_id).await?;
+        Ok(hand_to_gql(&hand))
+    }
+
+    /// Раздача в текстовом формате PokerStars (для импорта в трекеры).
+    async fn hand_history_text(&self, hand_id: U64) -> GqlResult<String> {
+        let hand = self.load_archived_hand(hand_id).await?;
+        Ok(to_pokerstars_text(&hand))
+    }
+
+    /// Раздачи стола, от новых к старым.
+    /// after — курсор (nextCursor предыдущей страницы).
+    async fn table_hands(
+        &self,
+        table_id: U64,
+        after: Option<U64>,
+        limit: Option<i32>,
+    ) -> GqlResult<GqlHandHistoryPage> {
+        let state = &self.state;
+        let table_id: TableId = table_id.0;
+
+        let count = state
+            .table_hand_counts
+            .get(&table_id)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or(0);
+        let (positions, has_more) = page_positions(count, after.map(|a| a.0), page_limit(limit));
 
-                out.push(table_dto_to_gql(&dto));
+        let mut ids = Vec::new();
+        for position in &positions {
+            if let Some(id) = state
+                .table_hands
+                .get(&(table_id, *position))
+                .await
+                .map_err(storage_error)?
+            {
+                ids.push(id);
             }
         }
 
-        out
+        let next_cursor = positions.last().copied().filter(|_| has_more);
+        load_hand_page(state, &ids, next_cursor).await
     }
-}
 
-// ============================================================================
-//                               MUTATION ROOT
-// ============================================================================
+    /// Раздачи игрока, от новых к старым.
+    async fn player_hands(
+        &self,
+        player_id: U64,
+        after: Option<U64>,
+        limit: Option<i32>,
+    ) -> GqlResult<GqlHandHistoryPage> {
+        let state = &self.state;
+        let player_id: PlayerId = player_id.0;
+
+        let count = state
+            .player_hand_counts
+            .get(&player_id)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or(0);
+        let (positions, has_more) = page_positions(count, after.map(|a| a.0), page_limit(limit));
 
-struct MutationRoot {
-    runtime: Arc<ServiceRuntime<PokerService>>,
-    storage_context: ViewStorageContext,
-}
+        let mut ids = Vec::new();
+        for position in &positions {
+            if let Some(id) = state
+                .player_hands
+                .get(&(player_id, *position))
+                .await
+                .map_err(storage_error)?
+            {
+                ids.push(id);
+            }
+        }
 
-#[Object]
-impl MutationRoot {
-    /// 1) Создать стол.
-    async fn create_table(
+        let next_cursor = positions.last().copied().filter(|_| has_more);
+        load_hand_page(state, &ids, next_cursor).await
+    }
+    /// Лента событий начиная после курсора `after` (позиция в ленте).
+    ///
+    /// Клиент подписывается на новые блоки цепи и дочитывает ленту
+    /// от последнего `nextCursor`. Фильтры по столу / турниру опциональны;
+    /// фильтр по турниру включает и события его столов (в том числе уже
+    /// закрытых — по записи жеребьёвки).
+    async fn events(
         &self,
-        table_id: i32,
-        name: String,
-        max_seats: i32,
-        small_blind: i32,
-        big_blind: i32,
-        ante: i32,
-        ante_type: GqlAnteType,
-    ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
-
-        let ante_type_api = match ante_type {
-            GqlAnteType::None => AnteTypeApi::None,
-            GqlAnteType::Classic => AnteTypeApi::Classic,
-            GqlAnteType::BigBlind => AnteTypeApi::BigBlind,
+        after: Option<U64>,
+        limit: Option<i32>,
+        table_id: Option<U64>,
+        tournament_id: Option<U64>,
+    ) -> GqlResult<GqlEventPage> {
+        let log = &self.state.event_log;
+        let total = log.count();
+        let limit = page_limit(limit);
+
+        // Курсор за пределами usize — дальше конца ленты.
+        let mut start = match after {
+            Some(a) => usize::try_from(a.0)
+                .ok()
+                .and_then(|i| i.checked_add(1))
+                .unwrap_or(usize::MAX),
+            None => 0,
         };
+        let mut events = Vec::new();
 
-        let cmd = EngineCommand::CreateTable(CreateTableCommand {
-            table_id,
-            name,
-            max_seats: max_seats as u8,
-            small_blind: to_chips(small_blind),
-            big_blind: to_chips(big_blind),
-            ante: to_chips(ante),
-            ante_type: ante_type_api,
-        });
+        let tournament_tables = match tournament_id {
+            Some(t) => tournament_table_ids(&self.state, t.0).await?,
+            None => HashSet::new(),
+        };
 
-        self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+        while start < total && events.len() < limit {
+            let end = start.saturating_add(EVENT_READ_BATCH).min(total);
+            let batch = log.read(start..end).await.map_err(storage_error)?;
+
+            for (offset, record) in batch.iter().enumerate() {
+                let matches_table = table_id
+                    .is_none_or(|t| record.event.table_id() == Some(t.0));
+                let matches_tournament = tournament_id.is_none_or(|t| {
+                    record.event.tournament_id() == Some(t.0)
+                        || record
+                            .event
+                            .table_id()
+                            .is_some_and(|id| tournament_tables.contains(&id))
+                });
+
+                if matches_table && matches_tournament {
+                    events.push(event_to_gql((start + offset) as u64, record));
+                    if events.len() == limit {
+                        break;
+                    }
+                }
+            }
 
-        MutationAck {
-            ok: true,
-            message: "CreateTable scheduled".to_string(),
+            start = end;
         }
+
+        // Курсор — последняя отданная запись, либо последняя просмотренная.
+        let next_cursor = events
+            .last()
+            .map(|e| e.index)
+            .or_else(|| total.checked_sub(1).map(|i| U64(i as u64)));
+
+        Ok(GqlEventPage {
+            events,
+            next_cursor,
+        })
     }
 
-    /// 2) Посадить игрока.
-    async fn seat_player(
+    /// Профиль и статистика игрока.
+    async fn player(&self, player_id: U64) -> GqlResult<GqlPlayer> {
+        let state = &self.state;
+        let pid: PlayerId = player_id.0;
+
+        let profile: Option<PlayerProfile> =
+            state.player_profiles.get(&pid).await.map_err(storage_error)?;
+        let Some(profile) = profile else {
+            return Err(gql_error(
+                GqlErrorCode::PlayerNotFound,
+                format!("player {pid} not found"),
+            ));
+        };
+
+        let stats = state
+            .player_stats
+            .get(&pid)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default();
+        let display_name = state.player_names.get(&pid).await.map_err(storage_error)?;
+
+        Ok(GqlPlayer {
+            player_id,
+            display_name,
+            avatar_url: profile.avatar_url,
+            country: profile.country,
+            created_at: U64(profile.created_at.micros()),
+            preferences: preferences_to_gql(&profile.preferences),
+            stats: player_stats_to_gql(&stats),
+        })
+    }
+
+    /// player_id по отображаемому имени (без учёта регистра).
+    async fn player_id_by_name(&self, name: String) -> GqlResult<Option<U64>> {
+        Ok(self
+            .state
+            .name_index
+            .get(&normalize_name(&name))
+            .await
+            .map_err(storage_error)?
+            .map(U64))
+    }
+
+    /// Рейтинг игроков за всё время по выбранному показателю.
+    async fn player_leaderboard(
+        &self,
+        by: GqlPlayerRanking,
+        limit: Option<i32>,
+    ) -> GqlResult<Vec<GqlLeaderboardEntry>> {
+        let state = &self.state;
+
+        let mut rows = Vec::new();
+        for pid in state.player_stats.indices().await.map_err(storage_error)? {
+            if let Some(stats) = state.player_stats.get(&pid).await.map_err(storage_error)? {
+                rows.push((pid, ranking_value(&stats, by)));
+            }
+        }
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut out = Vec::new();
+        for (idx, (pid, value)) in rows.into_iter().take(page_limit(limit)).enumerate() {
+            out.push(leaderboard_entry(state, idx + 1, pid, value).await?);
+        }
+
+        Ok(out)
+    }
+
+    /// Текущий сезон рейтинга (None — сезоны не запущены).
+    async fn season(&self) -> Option<GqlSeason> {
+        self.state
+            .current_season
+            .get()
+            .as_ref()
+            .map(|s| GqlSeason {
+                season_id: U64(u64::from(s.season_id)),
+                started_at: U64(s.started_at.micros()),
+                ends_at: U64(s.ends_at.micros()),
+                duration_secs: U64(s.duration_secs),
+            })
+    }
+
+    /// Топ-N сезона. `seasonId` не задан — текущий сезон, иначе архивный.
+    async fn season_leaderboard(
+        &self,
+        by: GqlSeasonRanking,
+        season_id: Option<U64>,
+        limit: Option<i32>,
+    ) -> GqlResult<Vec<GqlLeaderboardEntry>> {
+        let state = &self.state;
+        let ranked = ranked_season(state, season_id, by).await?;
+
+        let mut out = Vec::new();
+        for (idx, (pid, value)) in ranked.into_iter().take(page_limit(limit)).enumerate() {
+            out.push(leaderboard_entry(state, idx + 1, pid, value).await?);
+        }
+
+        Ok(out)
+    }
+
+    /// Место игрока в сезоне (None — в этом сезоне не играл).
+    async fn season_rank(
+        &self,
+        player_id: U64,
+        by: GqlSeasonRanking,
+        season_id: Option<U64>,
+    ) -> GqlResult<Option<GqlLeaderboardEntry>> {
+        let state = &self.state;
+        let ranked = ranked_season(state, season_id, by).await?;
+
+        let Some(idx) = ranked.iter().position(|(pid, _)| *pid == player_id.0) else {
+            return Ok(None);
+        };
+        let (pid, value) = ranked[idx];
+        Ok(Some(leaderboard_entry(state, idx + 1, pid, value).await?))
+    }
+
+    /// Текущий владелец и ожидающий подтверждения новый владелец.
+    async fn ownership(&self) -> GqlOwnership {
+        GqlOwnership {
+            owner: self.state.owner.get().map(|o| o.to_string()),
+            pending_owner: self.state.pending_owner.get().map(|o| o.to_string()),
+        }
+    }
+
+    /// Роли аккаунта.
+    async fn roles(&self, account: String) -> GqlResult<Vec<GqlRoleAssignment>> {
+        let account = AccountOwner::from_str(&account)
+            .map_err(|e| GqlError::new(format!("invalid account {account}: {e}")))?;
+
+        Ok(self
+            .state
+            .roles
+            .get(&account)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default()
+            .iter()
+            .map(role_assignment_to_gql)
+            .collect())
+    }
+
+    /// Журнал изменений ролей начиная после позиции `after`.
+    async fn role_log(
+        &self,
+        after: Option<U64>,
+        limit: Option<i32>,
+    ) -> GqlResult<Vec<GqlRoleChange>> {
+        let log = &self.state.role_log;
+        let start = after.map(|a| a.0 as usize + 1).unwrap_or(0);
+        let end = (start + page_limit(limit)).min(log.count());
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let changes = log.read(start..end).await.map_err(storage_error)?;
+        Ok(changes
+            .iter()
+            .enumerate()
+            .map(|(offset, c)| role_change_to_gql((start + offset) as u64, c))
+            .collect())
+    }
+
+    /// Расписание турнира (None — стартует вручную).
+    ///
+    /// Состояние в хранилище обновляется при первой операции над турниром,
+    /// поэтому `state` может отставать от времени.
+    async fn tournament_schedule(
+        &self,
+        tournament_id: U64,
+    ) -> GqlResult<Option<GqlTournamentSchedule>> {
+        let schedule = self
+            .state
+            .tournament_schedules
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(schedule.map(|s| GqlTournamentSchedule {
+            start_at: U64(s.start_at.micros()),
+            reg_close_at: U64(s.reg_close_at.micros()),
+            min_players: s.min_players as i32,
+            state: match s.state {
+                ScheduleState::Scheduled => GqlScheduleState::Scheduled,
+                ScheduleState::RegistrationClosed => GqlScheduleState::RegistrationClosed,
+                ScheduleState::Started => GqlScheduleState::Started,
+                ScheduleState::Cancelled => GqlScheduleState::Cancelled,
+            },
+        }))
+    }
+
+    /// Цель сателлита (None — обычный турнир).
+    async fn satellite(&self, tournament_id: U64) -> GqlResult<Option<GqlSatellite>> {
+        let satellite = self
+            .state
+            .satellites
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(satellite.map(|s| GqlSatellite {
+            target_tournament_id: U64(s.target),
+            seats: s.seats as i32,
+        }))
+    }
+
+    /// Билеты игрока, включая уже потраченные.
+    async fn tickets(&self, player_id: U64) -> GqlResult<Vec<GqlTicket>> {
+        let tickets = self
+            .state
+            .tickets
+            .get(&player_id.0)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default();
+
+        Ok(tickets.into_iter().map(GqlTicket::from).collect())
+    }
+
+    /// Жеребьёвка мест при старте турнира (None — турнир не стартовал).
+    async fn seat_draw(&self, tournament_id: U64) -> GqlResult<Option<GqlSeatDraw>> {
+        let draw = self
+            .state
+            .seat_draws
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(draw.map(|d| GqlSeatDraw {
+            seed: U64(d.seed),
+            base_seed: U64(d.inputs.base_seed),
+            block_height: U64(d.inputs.block_height),
+            started_at: U64(d.inputs.started_at.micros()),
+            entrants: d.inputs.entrants.into_iter().map(U64).collect(),
+            drawn_at: U64(d.drawn_at.micros()),
+            tables: d
+                .tables
+                .into_iter()
+                .map(|t| GqlTableDraw {
+                    table_id: U64(t.table_id),
+                    button: t.button as i32,
+                    seats: t
+                        .seats
+                        .into_iter()
+                        .map(|(seat, player_id)| GqlDrawnSeat {
+                            seat: seat as i32,
+                            player_id: U64(player_id),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }))
+    }
+
+    /// Режим рука-в-руку (None — столы играют независимо).
+    async fn hand_for_hand(&self, tournament_id: U64) -> GqlResult<Option<GqlHandForHand>> {
+        let hfh = self
+            .state
+            .hand_for_hand
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(hfh.map(|h| GqlHandForHand {
+            round: h.round as i32,
+            waiting_tables: h.done.into_iter().map(U64).collect(),
+            pending_busts: h.busted.into_iter().map(U64).collect(),
+        }))
+    }
+
+    /// Калькулятор сделки по текущим стекам, без записи в стейт.
+    async fn deal_quote(
+        &self,
+        tournament_id: U64,
+        kind: GqlDealKind,
+        play_for: Option<U64>,
+    ) -> GqlResult<GqlDeal> {
+        let tournament = self
+            .state
+            .tournaments
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                gql_error(
+                    GqlErrorCode::TournamentNotFound,
+                    format!("tournament {} not found", tournament_id.0),
+                )
+            })?;
+
+        let stacks = remaining_stacks(&tournament);
+        let prizes: Vec<Chips> = self
+            .state
+            .tournament_payouts
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .take(stacks.len())
+            .collect();
+
+        let play_for = play_for.map(to_chips).unwrap_or(Chips::ZERO);
+        let shares = compute_deal(kind.into(), &stacks, &prizes, play_for)
+            .map_err(GqlError::new)?;
+
+        Ok(deal_to_gql(kind.into(), play_for, shares, &[], None, false))
+    }
+
+    /// Сделка турнира: принятая, иначе открытое предложение.
+    async fn deal(&self, tournament_id: U64) -> GqlResult<Option<GqlDeal>> {
+        if let Some(deal) = self
+            .state
+            .locked_deals
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?
+        {
+            return Ok(Some(deal_to_gql(
+                deal.kind,
+                deal.play_for,
+                deal.shares,
+                &[],
+                None,
+                true,
+            )));
+        }
+
+        let proposal = self
+            .state
+            .deal_proposals
+            .get(&tournament_id.0)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(proposal.map(|p| {
+            deal_to_gql(
+                p.kind,
+                p.play_for,
+                p.shares,
+                &p.accepted,
+                Some(p.proposer),
+                false,
+            )
+        }))
+    }
+
+    /// Шаблоны SNG / спинов с их открытыми турнирами.
+    async fn sng_templates(&self) -> GqlResult<Vec<GqlSngTemplate>> {
+        let state = &self.state;
+        let mut out = Vec::new();
+
+        for id in state.sng_templates.indices().await.map_err(storage_error)? {
+            let Some(t) = state.sng_templates.get(&id).await.map_err(storage_error)? else {
+                continue;
+            };
+            let registered = match t.current {
+                Some(tid) => state
+                    .tournaments
+                    .get(&tid)
+                    .await
+                    .map_err(storage_error)?
+                    .map_or(0, |tournament| tournament.registrations.len() as i32),
+                None => 0,
+            };
+
+            out.push(GqlSngTemplate {
+                template_id: U64(t.template_id),
+                name: t.config.name.clone(),
+                players: t.players as i32,
+                buy_in: chips_to_gql(t.buy_in),
+                spin: t
+                    .spin
+                    .iter()
+                    .map(|m| GqlSpinMultiplier {
+                        multiplier: m.multiplier as i32,
+                        weight: m.weight as i32,
+                    })
+                    .collect(),
+                current_tournament_id: t.current.map(U64),
+                registered,
+                launched: t.launched as i32,
+            });
+        }
+
+        Ok(out)
+    }
+
+    /// Банкролл игрока на этой цепи (фишки вне столов).
+    async fn bankroll(&self, player_id: U64) -> GqlResult<U64> {
+        let balance = self
+            .state
+            .bankrolls
+            .get(&player_id.0)
+            .await
+            .map_err(storage_error)?
+            .unwrap_or(Chips::ZERO);
+
+        Ok(chips_to_gql(balance))
+    }
+}
+
+impl QueryRoot {
+    async fn load_archived_hand(&self, hand_id: U64) -> GqlResult<ArchivedHand> {
+        let hand_id: HandId = hand_id.0;
+
+        self.state
+            .hand_archive
+            .get(&hand_id)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                gql_error(
+                    GqlErrorCode::HandNotFound,
+                    format!("hand {hand_id} not found"),
+                )
+            })
+    }
+}
+
+// ============================================================================
+//                               MUTATION ROOT
+// ============================================================================
+
+struct MutationRoot {
+    runtime: Arc<ServiceRuntime<PokerService>>,
+    state: Arc<PokerState>,
+}
+
+#[Object]
+impl MutationRoot {
+    /// 1) Создать стол.
+    ///
+    /// `variant`, `betting` и `buttonAnte` необязательны: без них — NL Hold'em.
+    /// Для анте только с баттона передайте `anteType: NONE` и `buttonAnte`.
+    async fn create_table(
+        &self,
+        table_id: U64,
+        name: String,
+        max_seats: i32,
+        small_blind: U64,
+        big_blind: U64,
+        ante: U64,
+        ante_type: GqlAnteType,
+        variant: Option<GqlGameVariant>,
+        betting: Option<GqlBettingInput>,
+        button_ante: Option<U64>,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+        let max_seats = match to_u8(max_seats, "max_seats") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+        let rules = match rules_from_gql(variant, betting, button_ante) {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+
+        let ante_type_api = map_gql_ante_type(ante_type);
+
+        let table = CreateTableCommand {
+            table_id,
+            name,
+            max_seats,
+            small_blind: to_chips(small_blind),
+            big_blind: to_chips(big_blind),
+            ante: to_chips(ante),
+            ante_type: ante_type_api,
+        };
+
+        // Столы с особыми правилами создаются on-chain командой.
+        if rules == TableRules::default() {
+            self.runtime
+                .schedule_operation(&Operation::Command(EngineCommand::CreateTable(table)));
+        } else {
+            let cmd = OnchainCommand::CreateTableWithRules(CreateTableWithRulesCommand {
+                table,
+                rules,
+            });
+            self.runtime
+                .schedule_operation(&Operation::Onchain(cmd));
+        }
+
+        MutationAck {
+            ok: true,
+            message: "CreateTable scheduled".to_string(),
+        }
+    }
+
+    /// 2) Посадить игрока.
+    async fn seat_player(
+        &self,
+        table_id: U64,
+        player_id: U64,
+        seat_index: i32,
+        display_name: String,
+        initial_stack: U64,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+        let seat_index = match to_u8(seat_index, "seat_index") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+        let player_id: PlayerId = player_id.0;
+
+        let cmd = EngineCommand::TableCommand(TableCommand::SeatPlayer(
+            SeatPlayerCommand {
+                table_id,
+                player_id,
+                seat_index,
+                display_name,
+                initial_stack: to_chips(initial_stack),
+            },
+        ));
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "SeatPlayer scheduled".to_string(),
+        }
+    }
+
+    /// 3) Убрать игрока с места.
+    async fn unseat_player(
+        &self,
+        table_id: U64,
+        seat_index: i32,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+        let seat_index = match to_u8(seat_index, "seat_index") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+
+        let cmd = EngineCommand::TableCommand(TableCommand::UnseatPlayer(
+            UnseatPlayerCommand {
+                table_id,
+                seat_index,
+            },
+        ));
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "UnseatPlayer scheduled".to_string(),
+        }
+    }
+
+    /// 4) Изменить стек игрока (кэш-ин/кэш-аут).
+    async fn adjust_stack(
+        &self,
+        table_id: U64,
+        seat_index: i32,
+        delta: I64,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+        let seat_index = match to_u8(seat_index, "seat_index") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+
+        let cmd = EngineCommand::TableCommand(TableCommand::AdjustStack(
+            AdjustStackCommand {
+                table_id,
+                seat_index,
+                delta: delta.0,
+            },
+        ));
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "AdjustStack scheduled".to_string(),
+        }
+    }
+
+    /// 5) Запустить раздачу. Нужна роль DealerBot или TableManager.
+    async fn start_hand(
+        &self,
+        table_id: U64,
+        hand_id: U64,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+
+        let cmd = EngineCommand::TableCommand(TableCommand::StartHand(
+            StartHandCommand {
+                table_id,
+                hand_id: hand_id.0,
+            },
+        ));
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "StartHand scheduled".to_string(),
+        }
+    }
+
+    /// 6) Игровое действие за столом (fold/check/call/bet/raise/all-in).
+    ///
+    /// Игрок и место определяются контрактом по подписанту операции
+    /// (`account_players`); действие чужого игрока или не в свой ход отклоняется.
+    ///
+    /// На лимитных столах bet/raise можно отправить без `amount` —
+    /// контракт подставит фиксированную ставку (или минимум спреда).
+    async fn player_action(
+        &self,
+        table_id: U64,
+        action: GqlPlayerActionKind,
+        amount: Option<U64>,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+
+        let kind: PlayerActionKind = match action {
+            GqlPlayerActionKind::Fold => PlayerActionKind::Fold,
+            GqlPlayerActionKind::Check => PlayerActionKind::Check,
+            GqlPlayerActionKind::Call => PlayerActionKind::Call,
+            GqlPlayerActionKind::Bet => {
+                let chips = to_chips(amount.unwrap_or(U64(0)));
+                PlayerActionKind::Bet(chips)
+            }
+            GqlPlayerActionKind::Raise => {
+                let chips = to_chips(amount.unwrap_or(U64(0)));
+                PlayerActionKind::Raise(chips)
+            }
+            GqlPlayerActionKind::AllIn => PlayerActionKind::AllIn,
+        };
+
+        let cmd = OnchainCommand::Act(ActCommand { table_id, kind });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "PlayerAction scheduled".to_string(),
+        }
+    }
+
+    /// 7) Tick таймера стола. Нужна роль DealerBot или TableManager.
+    async fn tick_table(
+        &self,
+        table_id: U64,
+        delta_secs: i32,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+
+        let cmd = EngineCommand::TableCommand(TableCommand::TickTable(
+            TickTableCommand {
+                table_id,
+                delta_secs,
+            },
+        ));
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "TickTable scheduled".to_string(),
+        }
+    }
+
+    /// Включить / выключить авто-раздачу (дилер-бот) на столе.
+    ///
+    /// delay_secs — пауза между раздачами по времени цепи.
+    async fn set_auto_deal(
+        &self,
+        table_id: U64,
+        enabled: bool,
+        delay_secs: U64,
+    ) -> MutationAck {
+        let table_id: TableId = table_id.0;
+
+        let cmd = OnchainCommand::SetAutoDeal(SetAutoDealCommand {
+            table_id,
+            enabled,
+            delay_secs: delay_secs.0,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "SetAutoDeal scheduled".to_string(),
+        }
+    }
+
+    // ========================================================================
+    //                        МУЛЬТИЧЕЙН (ШАРДИНГ СТОЛОВ)
+    // ========================================================================
+
+    /// Открыть cash-стол на отдельной микроцепи (текущая цепь — координатор).
+    async fn open_remote_table(
+        &self,
+        chain_id: String,
+        table_id: U64,
+        name: String,
+        max_seats: i32,
+        small_blind: U64,
+        big_blind: U64,
+        ante: U64,
+        ante_type: GqlAnteType,
+        variant: Option<GqlGameVariant>,
+        betting: Option<GqlBettingInput>,
+        button_ante: Option<U64>,
+    ) -> MutationAck {
+        let chain_id = match ChainId::from_str(&chain_id) {
+            Ok(c) => c,
+            Err(e) => {
+                return MutationAck {
+                    ok: false,
+                    message: format!("invalid chain_id: {e}"),
+                }
+            }
+        };
+        let max_seats = match to_u8(max_seats, "max_seats") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+        let rules = match rules_from_gql(variant, betting, button_ante) {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
+
+        let cmd = OnchainCommand::OpenRemoteTable(OpenRemoteTableCommand {
+            chain_id,
+            table: CreateTableCommand {
+                table_id: table_id.0,
+                name,
+                max_seats,
+                small_blind: to_chips(small_blind),
+                big_blind: to_chips(big_blind),
+                ante: to_chips(ante),
+                ante_type: map_gql_ante_type(ante_type),
+            },
+            rules,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "OpenRemoteTable scheduled".to_string(),
+        }
+    }
+
+    /// Задать цепи, по которым раскладываются столы турниров при старте.
+    async fn set_table_chains(&self, chains: Vec<String>) -> MutationAck {
+        let mut parsed = Vec::with_capacity(chains.len());
+        for c in &chains {
+            match ChainId::from_str(c) {
+                Ok(id) => parsed.push(id),
+                Err(e) => {
+                    return MutationAck {
+                        ok: false,
+                        message: format!("invalid chain_id {c}: {e}"),
+                    }
+                }
+            }
+        }
+
+        let cmd = OnchainCommand::SetTableChains(SetTableChainsCommand {
+            chains: parsed,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "SetTableChains scheduled".to_string(),
+        }
+    }
+
+    // ========================================================================
+    //                       РОЛИ И ПЕРЕДАЧА ВЛАДЕНИЯ
+    // ========================================================================
+
+    /// Выдать роль. `tableId` ограничивает роль одним столом.
+    async fn grant_role(
+        &self,
+        account: String,
+        role: GqlRole,
+        table_id: Option<U64>,
+    ) -> MutationAck {
+        let account = match to_account(&account, "account") {
+            Ok(a) => a,
+            Err(ack) => return ack,
+        };
+
+        let cmd = OnchainCommand::GrantRole(GrantRoleCommand {
+            account,
+            role: role_from_gql(role),
+            table_id: table_id.map(|t| t.0),
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "GrantRole scheduled".to_string(),
+        }
+    }
+
+    async fn revoke_role(
+        &self,
+        account: String,
+        role: GqlRole,
+        table_id: Option<U64>,
+    ) -> MutationAck {
+        let account = match to_account(&account, "account") {
+            Ok(a) => a,
+            Err(ack) => return ack,
+        };
+
+        let cmd = OnchainCommand::RevokeRole(RevokeRoleCommand {
+            account,
+            role: role_from_gql(role),
+            table_id: table_id.map(|t| t.0),
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "RevokeRole scheduled".to_string(),
+        }
+    }
+
+    /// Шаг 1 передачи владения (подписывает текущий owner).
+    async fn propose_owner(&self, new_owner: String) -> MutationAck {
+        let new_owner = match to_account(&new_owner, "new_owner") {
+            Ok(a) => a,
+            Err(ack) => return ack,
+        };
+
+        let cmd = OnchainCommand::ProposeOwner(ProposeOwnerCommand { new_owner });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "ProposeOwner scheduled".to_string(),
+        }
+    }
+
+    /// Шаг 2 передачи владения (подписывает новый owner).
+    async fn accept_ownership(&self) -> MutationAck {
+        let cmd = OnchainCommand::AcceptOwnership(AcceptOwnershipCommand);
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "AcceptOwnership scheduled".to_string(),
+        }
+    }
+
+    // ========================================================================
+    //                     ИДЕНТИЧНОСТЬ ИГРОКА И ИМЕНА
+    // ========================================================================
+
+    /// Связать подписанта с player_id и занять отображаемое имя.
+    async fn register_player(&self, player_id: U64, display_name: String) -> MutationAck {
+        let cmd = OnchainCommand::RegisterPlayer(RegisterPlayerCommand {
+            player_id: player_id.0,
+            display_name,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "RegisterPlayer scheduled".to_string(),
+        }
+    }
+
+    async fn set_display_name(&self, player_id: U64, display_name: String) -> MutationAck {
+        let cmd = OnchainCommand::SetDisplayName(SetDisplayNameCommand {
+            player_id: player_id.0,
+            display_name,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "SetDisplayName scheduled".to_string(),
+        }
+    }
+
+    /// Перенести игрока на новый аккаунт (подписывает текущий аккаунт).
+    async fn rotate_account(&self, player_id: U64, new_account: String) -> MutationAck {
+        let new_account = match to_account(&new_account, "new_account") {
+            Ok(a) => a,
+            Err(ack) => return ack,
+        };
+
+        let cmd = OnchainCommand::RotateAccount(RotateAccountCommand {
+            player_id: player_id.0,
+            new_account,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "RotateAccount scheduled".to_string(),
+        }
+    }
+
+    // ========================================================================
+    //                          ПРОФИЛЬ ИГРОКА
+    // ========================================================================
+
+    /// Обновить профиль своего игрока. Пустая строка очищает поле,
+    /// отсутствующий аргумент оставляет его как есть.
+    async fn update_profile(
         &self,
-        table_id: i32,
-        player_id: i32,
-        seat_index: i32,
-        display_name: String,
-        initial_stack: i32,
+        player_id: U64,
+        avatar_url: Option<String>,
+        country: Option<String>,
+        preferences: Option<GqlPlayerPreferencesInput>,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
-        let player_id: PlayerId = player_id as u64;
-
-        let cmd = EngineCommand::TableCommand(TableCommand::SeatPlayer(
-            SeatPlayerCommand {
-                table_id,
-                player_id,
-                seat_index: seat_index as u8,
-                display_name,
-                initial_stack: to_chips(initial_stack),
-            },
-        ));
+        let cmd = OnchainCommand::UpdateProfile(UpdateProfileCommand {
+            player_id: player_id.0,
+            avatar_url,
+            country,
+            preferences: preferences.map(|p| PlayerPreferences {
+                auto_muck: p.auto_muck,
+                four_color_deck: p.four_color_deck,
+                auto_rebuy: p.auto_rebuy,
+            }),
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "SeatPlayer scheduled".to_string(),
+            message: "UpdateProfile scheduled".to_string(),
         }
     }
 
-    /// 3) Убрать игрока с места.
-    async fn unseat_player(
+    // ========================================================================
+    //                      БАНКРОЛЛ НА ЦЕПИ ИГРОКА
+    // ========================================================================
+
+    /// Пополнить банкролл игрока на его цепи (admin).
+    async fn credit_bankroll(
         &self,
-        table_id: i32,
-        seat_index: i32,
+        player_chain: String,
+        player_id: U64,
+        amount: U64,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
+        let player_chain = match to_chain_id(&player_chain, "player_chain") {
+            Ok(c) => c,
+            Err(ack) => return ack,
+        };
 
-        let cmd = EngineCommand::TableCommand(TableCommand::UnseatPlayer(
-            UnseatPlayerCommand {
-                table_id,
-                seat_index: seat_index as u8,
-            },
-        ));
+        let cmd = OnchainCommand::CreditBankroll(CreditBankrollCommand {
+            player_chain,
+            player_id: player_id.0,
+            amount: to_chips(amount),
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "UnseatPlayer scheduled".to_string(),
+            message: "CreditBankroll scheduled".to_string(),
         }
     }
 
-    /// 4) Изменить стек игрока (кэш-ин/кэш-аут).
-    async fn adjust_stack(
+    /// Сесть за стол на другой цепи, заплатив бай-ин из банкролла.
+    /// Вызывается на цепи игрока.
+    async fn request_seat(
         &self,
-        table_id: i32,
+        table_chain: String,
+        table_id: U64,
+        player_id: U64,
         seat_index: i32,
-        delta: i32,
+        buy_in: U64,
+        display_name: String,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
+        let table_chain = match to_chain_id(&table_chain, "table_chain") {
+            Ok(c) => c,
+            Err(ack) => return ack,
+        };
+        let seat_index = match to_u8(seat_index, "seat_index") {
+            Ok(v) => v,
+            Err(ack) => return ack,
+        };
 
-        let cmd = EngineCommand::TableCommand(TableCommand::AdjustStack(
-            AdjustStackCommand {
-                table_id,
-                seat_index: seat_index as u8,
-                delta: delta as i64,
-            },
-        ));
+        let cmd = OnchainCommand::RequestSeat(RequestSeatCommand {
+            table_chain,
+            table_id: table_id.0,
+            player_id: player_id.0,
+            seat_index,
+            buy_in: to_chips(buy_in),
+            display_name,
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "AdjustStack scheduled".to_string(),
+            message: "RequestSeat scheduled".to_string(),
         }
     }
 
-    /// 5) Запустить раздачу.
-    async fn start_hand(
+    /// Встать из-за стола на другой цепи; стек вернётся в банкролл.
+    async fn cash_out(
         &self,
-        table_id: i32,
-        hand_id: i32,
+        table_chain: String,
+        table_id: U64,
+        player_id: U64,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
+        let table_chain = match to_chain_id(&table_chain, "table_chain") {
+            Ok(c) => c,
+            Err(ack) => return ack,
+        };
 
-        let cmd = EngineCommand::TableCommand(TableCommand::StartHand(
-            StartHandCommand {
-                table_id,
-                hand_id: hand_id as u64,
-            },
-        ));
+        let cmd = OnchainCommand::CashOut(CashOutCommand {
+            table_chain,
+            table_id: table_id.0,
+            player_id: player_id.0,
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "StartHand scheduled".to_string(),
+            message: "CashOut scheduled".to_string(),
         }
     }
 
-    /// 6) Игровое действие за столом (fold/check/call/bet/raise/all-in).
+    // ========================================================================
+    //                           ТУРНИРНЫЕ МУТАЦИИ
+    // ========================================================================
+
+    /// 8) Создать турнир с полным TournamentConfig.
     ///
-    /// seat / player_id берём из current_actor и стола.
-    async fn player_action(
+    /// config — это JSON, который 1:1 маппится в твой TournamentConfig
+    /// через serde. Пример аргумента в GraphQL:
+    ///
+    ///   config: {
+    ///     "name": "...",
+    ///     "starting_stack": 5000,
+    ///     ...
+    ///   }
+    async fn create_tournament(
         &self,
-        table_id: i32,
-        action: GqlPlayerActionKind,
-        amount: Option<i32>,
+        tournament_id: U64,
+        config: Json<JsonValue>,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
 
-        // 1) Лоадим стейт.
-        let state =
-            match PokerState::load(self.storage_context.clone()).await {
-                Ok(s) => s,
-                Err(e) => {
-                    return MutationAck {
-                        ok: false,
-                        message: format!("Failed to load state: {e:?}"),
-                    }
-                }
-            };
+        // `start_time` / `reg_close_time` из UI-конфига — сразу в расписание.
+        let schedule = match config_schedule(&config.0) {
+            Ok(s) => s,
+            Err(message) => return MutationAck { ok: false, message },
+        };
 
-        // 2) Берём стол.
-        let table_opt = match state.tables.get(&table_id).await {
-            Ok(t) => t,
+        let cfg: TournamentConfig = match serde_json::from_value(config.0) {
+            Ok(c) => c,
             Err(e) => {
                 return MutationAck {
                     ok: false,
-                    message: format!("tables.get error: {e:?}"),
+                    message: format!("Invalid TournamentConfig JSON: {e}"),
                 }
             }
         };
 
-        let table = match table_opt {
-            Some(t) => t,
-            None => {
-                return MutationAck {
-                    ok: false,
-                    message: format!("table_not_found: {table_id}"),
-                }
-            }
-        };
+        let cmd = EngineCommand::TournamentCommand(
+            TournamentCommand::CreateTournament(CreateTournamentCommand {
+                tournament_id,
+                config: cfg,
+            }),
+        );
+
+        self.runtime
+            .schedule_operation(&Operation::Command(cmd));
+
+        if let Some((start_at, reg_close_at)) = schedule {
+            let cmd = OnchainCommand::ScheduleTournament(ScheduleTournamentCommand {
+                tournament_id,
+                start_at,
+                reg_close_at,
+                min_players: 2,
+            });
+            self.runtime
+                .schedule_operation(&Operation::Onchain(cmd));
+        }
+
+        MutationAck {
+            ok: true,
+            message: "CreateTournament scheduled".to_string(),
+        }
+    }
 
-        // 3) Берём active hand snapshot, чтобы понять current_actor.
-        let active_snapshot = match state.active_hands.get(&table_id).await {
-            Ok(opt) => opt.flatten(),
+    /// Шаблон SNG на 2, 6 или 9 игроков (config — JSON TournamentConfig,
+    /// как в createTournament). С `spin` — спин со случайным множителем.
+    async fn create_sng_template(
+        &self,
+        template_id: U64,
+        config: Json<JsonValue>,
+        players: i32,
+        buy_in: U64,
+        spin: Option<Vec<GqlSpinMultiplierInput>>,
+    ) -> MutationAck {
+        let config: TournamentConfig = match serde_json::from_value(config.0) {
+            Ok(c) => c,
             Err(e) => {
                 return MutationAck {
                     ok: false,
-                    message: format!("active_hands.get error: {e:?}"),
+                    message: format!("Invalid TournamentConfig JSON: {e}"),
                 }
             }
         };
 
-        let snapshot = match active_snapshot {
-            Some(s) => s,
-            None => {
-                return MutationAck {
-                    ok: false,
-                    message: "no_active_hand_for_table".to_string(),
+        let mut multipliers = Vec::new();
+        for m in spin.unwrap_or_default() {
+            match (u32::try_from(m.multiplier), u32::try_from(m.weight)) {
+                (Ok(multiplier), Ok(weight)) => {
+                    multipliers.push(SpinMultiplier { multiplier, weight })
                 }
-            }
-        };
-
-        let seat_index_u8 = match snapshot.current_actor {
-            Some(s) => s,
-            None => {
-                return MutationAck {
-                    ok: false,
-                    message: "no_current_actor_for_table".to_string(),
+                _ => {
+                    return MutationAck {
+                        ok: false,
+                        message: "spin multiplier and weight must be non-negative".to_string(),
+                    }
                 }
             }
-        };
+        }
 
-        let idx = seat_index_u8 as usize;
-        if idx >= table.seats.len() {
-            return MutationAck {
-                ok: false,
-                message: "current_actor_seat_out_of_bounds".to_string(),
-            };
+        let cmd = OnchainCommand::CreateSngTemplate(CreateSngTemplateCommand {
+            template_id: template_id.0,
+            config,
+            players: players.max(0) as u32,
+            buy_in: to_chips(buy_in),
+            spin: multipliers,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "CreateSngTemplate scheduled".to_string(),
         }
+    }
 
-        let player_at_seat = match &table.seats[idx] {
-            Some(p) => p,
-            None => {
-                return MutationAck {
-                    ok: false,
-                    message: "current_actor_seat_empty".to_string(),
-                }
-            }
+    /// Запланировать турнир. Время — RFC 3339 (как `start_time` /
+    /// `reg_close_time` из UI-конфига) или секунды Unix-времени.
+    async fn schedule_tournament(
+        &self,
+        tournament_id: U64,
+        start_time: String,
+        reg_close_time: Option<String>,
+        min_players: i32,
+    ) -> MutationAck {
+        let start_at = match parse_time(&start_time) {
+            Ok(t) => t,
+            Err(message) => return MutationAck { ok: false, message },
+        };
+        let reg_close_at = match reg_close_time.as_deref().map(parse_time).transpose() {
+            Ok(t) => t,
+            Err(message) => return MutationAck { ok: false, message },
         };
 
-        let player_id: PlayerId = player_at_seat.player_id;
-        let seat: SeatIndex = seat_index_u8;
+        let cmd = OnchainCommand::ScheduleTournament(ScheduleTournamentCommand {
+            tournament_id: tournament_id.0,
+            start_at,
+            reg_close_at,
+            min_players: min_players.max(0) as u32,
+        });
 
-        // 4) Маппим экшен + amount в PlayerActionKind.
-        let kind: PlayerActionKind = match action {
-            GqlPlayerActionKind::Fold => PlayerActionKind::Fold,
-            GqlPlayerActionKind::Check => PlayerActionKind::Check,
-            GqlPlayerActionKind::Call => PlayerActionKind::Call,
-            GqlPlayerActionKind::Bet => {
-                let chips = to_chips(amount.unwrap_or(0));
-                PlayerActionKind::Bet(chips)
-            }
-            GqlPlayerActionKind::Raise => {
-                let chips = to_chips(amount.unwrap_or(0));
-                PlayerActionKind::Raise(chips)
-            }
-            GqlPlayerActionKind::AllIn => PlayerActionKind::AllIn,
-        };
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
 
-        let pa = PlayerAction {
-            player_id,
-            seat,
-            kind,
-        };
+        MutationAck {
+            ok: true,
+            message: "ScheduleTournament scheduled".to_string(),
+        }
+    }
 
-        let cmd = EngineCommand::TableCommand(TableCommand::PlayerAction(
-            PlayerActionCommand { table_id, action: pa },
-        ));
+    /// Сделать турнир сателлитом: призы — билеты в `target_tournament_id`.
+    async fn set_satellite(&self, tournament_id: U64, target_tournament_id: U64) -> MutationAck {
+        let cmd = OnchainCommand::SetSatellite(SetSatelliteCommand {
+            tournament_id: tournament_id.0,
+            target_tournament_id: target_tournament_id.0,
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "PlayerAction scheduled".to_string(),
+            message: "SetSatellite scheduled".to_string(),
         }
     }
 
-    /// 7) Tick таймера стола.
-    async fn tick_table(
+    /// Предложить сделку (подписант — один из оставшихся игроков).
+    async fn propose_deal(
         &self,
-        table_id: i32,
-        delta_secs: i32,
+        tournament_id: U64,
+        kind: GqlDealKind,
+        play_for: Option<U64>,
     ) -> MutationAck {
-        let table_id: TableId = table_id as u64;
+        let cmd = OnchainCommand::ProposeDeal(ProposeDealCommand {
+            tournament_id: tournament_id.0,
+            kind: kind.into(),
+            play_for: play_for.map(to_chips).unwrap_or(Chips::ZERO),
+        });
 
-        let cmd = EngineCommand::TableCommand(TableCommand::TickTable(
-            TickTableCommand {
-                table_id,
-                delta_secs,
-            },
-        ));
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "ProposeDeal scheduled".to_string(),
+        }
+    }
+
+    async fn accept_deal(&self, tournament_id: U64) -> MutationAck {
+        let cmd = OnchainCommand::AcceptDeal(DealVoteCommand {
+            tournament_id: tournament_id.0,
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "TickTable scheduled".to_string(),
+            message: "AcceptDeal scheduled".to_string(),
         }
     }
 
-    // ========================================================================
-    //                           ТУРНИРНЫЕ МУТАЦИИ
-    // ========================================================================
+    async fn reject_deal(&self, tournament_id: U64) -> MutationAck {
+        let cmd = OnchainCommand::RejectDeal(DealVoteCommand {
+            tournament_id: tournament_id.0,
+        });
 
-    /// 8) Создать турнир с полным TournamentConfig.
-    ///
-    /// config — это JSON, который 1:1 маппится в твой TournamentConfig
-    /// через serde. Пример аргумента в GraphQL:
-    ///
-    ///   config: {
-    ///     "name": "...",
-    ///     "starting_stack": 5000,
-    ///     ...
-    ///   }
-    async fn create_tournament(
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "RejectDeal scheduled".to_string(),
+        }
+    }
+
+    async fn remove_sng_template(&self, template_id: U64) -> MutationAck {
+        let cmd = OnchainCommand::RemoveSngTemplate(RemoveSngTemplateCommand {
+            template_id: template_id.0,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "RemoveSngTemplate scheduled".to_string(),
+        }
+    }
+
+    /// Начать новый сезон рейтинга; дальше сезоны сменяются сами.
+    async fn start_season(&self, duration_secs: U64) -> MutationAck {
+        let cmd = OnchainCommand::StartSeason(StartSeasonCommand {
+            duration_secs: duration_secs.0,
+        });
+
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
+
+        MutationAck {
+            ok: true,
+            message: "StartSeason scheduled".to_string(),
+        }
+    }
+
+    /// Призовые турнира по местам: payouts[0] — за первое место.
+    async fn set_tournament_payouts(
         &self,
-        tournament_id: i32,
-        config: Json<JsonValue>,
+        tournament_id: U64,
+        payouts: Vec<U64>,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
+        let cmd = OnchainCommand::SetTournamentPayouts(SetTournamentPayoutsCommand {
+            tournament_id: tournament_id.0,
+            payouts: payouts.into_iter().map(to_chips).collect(),
+        });
 
-        let cfg: TournamentConfig = match serde_json::from_value(config.0) {
-            Ok(c) => c,
-            Err(e) => {
-                return MutationAck {
-                    ok: false,
-                    message: format!("Invalid TournamentConfig JSON: {e}"),
-                }
-            }
-        };
+        self.runtime
+            .schedule_operation(&Operation::Onchain(cmd));
 
-        let cmd = EngineCommand::TournamentCommand(
-            TournamentCommand::CreateTournament(CreateTournamentCommand {
-                tournament_id,
-                config: cfg,
-            }),
-        );
+        MutationAck {
+            ok: true,
+            message: "SetTournamentPayouts scheduled".to_string(),
+        }
+    }
+
+    /// Длительность уровня блайндов турнира в секундах (0 — снять).
+    async fn set_level_duration(&self, tournament_id: U64, duration_secs: U64) -> MutationAck {
+        let cmd = OnchainCommand::SetLevelDuration(SetLevelDurationCommand {
+            tournament_id: tournament_id.0,
+            duration_secs: duration_secs.0,
+        });
 
         self.runtime
-            .schedule_operation(&Operation::Command(cmd));
+            .schedule_operation(&Operation::Onchain(cmd));
 
         MutationAck {
             ok: true,
-            message: "CreateTournament scheduled".to_string(),
+            message: "SetLevelDuration scheduled".to_string(),
         }
     }
 
     /// 9) Зарегистрировать игрока в турнир.
     async fn register_player_to_tournament(
         &self,
-        tournament_id: i32,
-        player_id: i32,
+        tournament_id: U64,
+        player_id: U64,
         display_name: String,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
-        let player_id: PlayerId = player_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
+        let player_id: PlayerId = player_id.0;
 
         let cmd = EngineCommand::TournamentCommand(
             TournamentCommand::RegisterPlayer(RegisterPlayerInTournamentCommand {
@@ -941,11 +3525,11 @@ impl MutationRoot {
     /// 10) Отменить регистрацию игрока в турнире.
     async fn unregister_player_from_tournament(
         &self,
-        tournament_id: i32,
-        player_id: i32,
+        tournament_id: U64,
+        player_id: U64,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
-        let player_id: PlayerId = player_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
+        let player_id: PlayerId = player_id.0;
 
         let cmd = EngineCommand::TournamentCommand(
             TournamentCommand::UnregisterPlayer(UnregisterPlayerFromTournamentCommand {
@@ -966,9 +3550,9 @@ impl MutationRoot {
     /// 11) Старт турнира.
     async fn start_tournament(
         &self,
-        tournament_id: i32,
+        tournament_id: U64,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
 
         let cmd = EngineCommand::TournamentCommand(
             TournamentCommand::StartTournament(StartTournamentCommand {
@@ -988,9 +3572,9 @@ impl MutationRoot {
     /// 12) Перевести турнир на следующий уровень блайндов.
     async fn advance_tournament_level(
         &self,
-        tournament_id: i32,
+        tournament_id: U64,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
 
         let cmd = EngineCommand::TournamentCommand(
             TournamentCommand::AdvanceLevel(AdvanceLevelCommand {
@@ -1010,9 +3594,9 @@ impl MutationRoot {
     /// 13) Закрыть турнир (финальный флаг Finished).
     async fn close_tournament(
         &self,
-        tournament_id: i32,
+        tournament_id: U64,
     ) -> MutationAck {
-        let tournament_id: TournamentId = tournament_id as u64;
+        let tournament_id: TournamentId = tournament_id.0;
 
         let cmd = EngineCommand::TournamentCommand(
             TournamentCommand::CloseTournament(CloseTournamentCommand {
@@ -0,0 +1,426 @@
+//! Оценка рук и делёжка банков для вариантов, которых нет в движке.
+//!
+//! Движок сам распределяет банк по правилам холдема. Для остальных
+//! вариантов оркестратор откатывает его выплаты и делит банки заново:
+//! `split_pots` строит основной и побочные банки из вкладов игроков
+//! и отдаёт каждый лучшей руке среди претендентов.
+
+use std::collections::HashMap;
+
+use serde_json::Value as JsonValue;
+
+use poker_engine::domain::card::Card;
+use poker_engine::domain::SeatIndex;
+
+use crate::rules::GameVariant;
+
+/// Карта как (ранг 2..=14, масть 0..=3).
+pub type RankSuit = (u8, u8);
+
+/// Ранг и масть карты. У домена нет публичного API для этого,
+/// поэтому читаем через serde. На этом же строится
+/// `hand_export::card_to_text`.
+pub fn rank_suit(card: &Card) -> Option<RankSuit> {
+    let val = serde_json::to_value(card).ok()?;
+    let field = |name: &str| match val.get(name) {
+        Some(JsonValue::String(s)) => Some(s.clone()),
+        Some(JsonValue::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let rank = match field("rank")?.as_str() {
+        "Two" | "2" => 2,
+        "Three" | "3" => 3,
+        "Four" | "4" => 4,
+        "Five" | "5" => 5,
+        "Six" | "6" => 6,
+        "Seven" | "7" => 7,
+        "Eight" | "8" => 8,
+        "Nine" | "9" => 9,
+        "Ten" | "T" | "10" => 10,
+        "Jack" | "J" | "11" => 11,
+        "Queen" | "Q" | "12" => 12,
+        "King" | "K" | "13" => 13,
+        "Ace" | "A" | "14" => 14,
+        _ => return None,
+    };
+
+    let suit = match field("suit")?.as_str() {
+        "Spades" | "Spade" | "s" => 0,
+        "Hearts" | "Heart" | "h" => 1,
+        "Diamonds" | "Diamond" | "d" => 2,
+        "Clubs" | "Club" | "c" => 3,
+        _ => return None,
+    };
+
+    Some((rank, suit))
+}
+
+/// Категории рук по возрастанию силы.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    Trips,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+}
+
+/// Сила пятикарточной руки: старшинство категории в варианте,
+/// затем ранги для сравнения.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandValue {
+    /// Порядок категорий зависит от варианта (в шорт-деке флеш старше фулл-хауса).
+    pub strength: u8,
+    pub category: HandCategory,
+    pub tiebreak: [u8; 5],
+}
+
+/// Старшинство категории в варианте.
+fn category_strength(variant: GameVariant, category: HandCategory) -> u8 {
+    match (variant, category) {
+        (GameVariant::ShortDeck, HandCategory::Flush) => HandCategory::FullHouse as u8,
+        (GameVariant::ShortDeck, HandCategory::FullHouse) => HandCategory::Flush as u8,
+        _ => category as u8,
+    }
+}
+
+/// Оценить ровно пять карт по правилам варианта.
+pub fn eval_five(variant: GameVariant, cards: &[RankSuit; 5]) -> HandValue {
+    let flush = cards.iter().all(|c| c.1 == cards[0].1);
+
+    // Ранги, сгруппированные по количеству: (кол-во, ранг) по убыванию.
+    let mut counts: HashMap<u8, u8> = HashMap::new();
+    for (rank, _) in cards {
+        *counts.entry(*rank).or_default() += 1;
+    }
+    let mut groups: Vec<(u8, u8)> = counts.into_iter().map(|(r, n)| (n, r)).collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut tiebreak = [0u8; 5];
+    let mut i = 0;
+    for (n, rank) in &groups {
+        for _ in 0..*n {
+            tiebreak[i] = *rank;
+            i += 1;
+        }
+    }
+
+    let straight_high = if groups.len() == 5 {
+        let high = tiebreak[0];
+        let low = tiebreak[4];
+        if high - low == 4 {
+            Some(high)
+        } else if tiebreak == [14, 5, 4, 3, 2] {
+            // Колесо: A-2-3-4-5, туз играет единицей.
+            Some(5)
+        } else if variant == GameVariant::ShortDeck && tiebreak == [14, 9, 8, 7, 6] {
+            // В шорт-деке туз замыкает младший стрит A-6-7-8-9.
+            Some(9)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let category = match (straight_high, flush, groups[0].0, groups.get(1).map(|g| g.0)) {
+        (Some(_), true, _, _) => HandCategory::StraightFlush,
+        (_, _, 4, _) => HandCategory::Quads,
+        (_, _, 3, Some(2)) => HandCategory::FullHouse,
+        (_, true, _, _) => HandCategory::Flush,
+        (Some(_), _, _, _) => HandCategory::Straight,
+        (_, _, 3, _) => HandCategory::Trips,
+        (_, _, 2, Some(2)) => HandCategory::TwoPair,
+        (_, _, 2, _) => HandCategory::Pair,
+        _ => HandCategory::HighCard,
+    };
+
+    if let Some(high) = straight_high {
+        if matches!(category, HandCategory::Straight | HandCategory::StraightFlush) {
+            tiebreak = [high, 0, 0, 0, 0];
+        }
+    }
+
+    HandValue {
+        strength: category_strength(variant, category),
+        category,
+        tiebreak,
+    }
+}
+
+/// Все сочетания по `k` индексов из `0..n`.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut out = Vec::new();
+    let mut idx: Vec<usize> = (0..k).collect();
+    if k > n {
+        return out;
+    }
+    loop {
+        out.push(idx.clone());
+        let Some(pos) = (0..k).rev().find(|&i| idx[i] != i + n - k) else {
+            return out;
+        };
+        idx[pos] += 1;
+        let base = idx[pos];
+        for (offset, slot) in idx[pos + 1..].iter_mut().enumerate() {
+            *slot = base + offset + 1;
+        }
+    }
+}
+
+/// Лучшая рука игрока по правилам варианта.
+///
+/// Омаха — ровно две закрытые + три с борда; холдем и шорт-дек — любые
+/// пять из всех.
+pub fn best_hand(
+    variant: GameVariant,
+    hole: &[RankSuit],
+    board: &[RankSuit],
+) -> Option<HandValue> {
+    let mut best: Option<HandValue> = None;
+    let mut consider = |five: [RankSuit; 5]| {
+        let value = eval_five(variant, &five);
+        if best.is_none_or(|b| value > b) {
+            best = Some(value);
+        }
+    };
+
+    match variant {
+        GameVariant::Omaha => {
+            for h in combinations(hole.len(), 2) {
+                for b in combinations(board.len(), 3) {
+                    consider([hole[h[0]], hole[h[1]], board[b[0]], board[b[1]], board[b[2]]]);
+                }
+            }
+        }
+        GameVariant::Holdem | GameVariant::ShortDeck => {
+            let all: Vec<RankSuit> = hole.iter().chain(board).copied().collect();
+            for c in combinations(all.len(), 5) {
+                consider([all[c[0]], all[c[1]], all[c[2]], all[c[3]], all[c[4]]]);
+            }
+        }
+    }
+
+    best
+}
+
+/// Участник делёжки: вклад в банк и рука (None — сбросил).
+#[derive(Clone, Debug)]
+pub struct PotEntry {
+    pub seat: SeatIndex,
+    pub contributed: u64,
+    pub hand: Option<HandValue>,
+}
+
+/// Разделить банк: основной и побочные банки по уровням вкладов,
+/// каждый — лучшей руке среди тех, кто вложил не меньше уровня.
+///
+/// `order` — места от кнопки: при ничьей нечётная фишка уходит первому.
+///
+/// Уровень без живых претендентов бывает только выше вкладов всех живых
+/// рук (невозвращённый излишек сбросившего); претендентов нет и на более
+/// высоких уровнях, поэтому такие фишки не теряются, а целиком уходят
+/// лучшей живой руке (при ничьей — ближнему к кнопке).
+pub fn split_pots(entries: &[PotEntry], order: &[SeatIndex]) -> HashMap<SeatIndex, u64> {
+    let mut awards: HashMap<SeatIndex, u64> = HashMap::new();
+
+    let mut levels: Vec<u64> = entries
+        .iter()
+        .map(|e| e.contributed)
+        .filter(|c| *c > 0)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let position = |seat: SeatIndex| order.iter().position(|s| *s == seat).unwrap_or(usize::MAX);
+
+    let mut prev = 0u64;
+    let mut orphaned = 0u64;
+    for level in levels {
+        let slice: u64 = entries
+            .iter()
+            .map(|e| e.contributed.min(level) - e.contributed.min(prev))
+            .sum();
+        prev = level;
+
+        let contenders: Vec<&PotEntry> = entries
+            .iter()
+            .filter(|e| e.hand.is_some() && e.contributed >= level)
+            .collect();
+        let Some(best) = contenders.iter().filter_map(|e| e.hand).max() else {
+            orphaned += slice;
+            continue;
+        };
+
+        let mut winners: Vec<SeatIndex> = contenders
+            .iter()
+            .filter(|e| e.hand == Some(best))
+            .map(|e| e.seat)
+            .collect();
+        winners.sort_by_key(|s| position(*s));
+
+        let share = slice / winners.len() as u64;
+        let odd = slice % winners.len() as u64;
+        for (i, seat) in winners.iter().enumerate() {
+            *awards.entry(*seat).or_default() += share + u64::from((i as u64) < odd);
+        }
+    }
+
+    if orphaned > 0 {
+        let best = entries
+            .iter()
+            .filter(|e| e.hand.is_some())
+            .max_by(|a, b| a.hand.cmp(&b.hand).then(position(b.seat).cmp(&position(a.seat))));
+        if let Some(e) = best {
+            *awards.entry(e.seat).or_default() += orphaned;
+        }
+    }
+
+    awards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{card, full_deck};
+
+    fn rs(text: &str) -> Vec<RankSuit> {
+        text.split_whitespace()
+            .map(|c| rank_suit(&card(c)).expect("parsable card"))
+            .collect()
+    }
+
+    fn five(variant: GameVariant, text: &str) -> HandValue {
+        let c = rs(text);
+        eval_five(variant, &[c[0], c[1], c[2], c[3], c[4]])
+    }
+
+    fn best(variant: GameVariant, hole: &str, board: &str) -> HandValue {
+        best_hand(variant, &rs(hole), &rs(board)).expect("enough cards")
+    }
+
+    fn entry(seat: SeatIndex, contributed: u64, hand: Option<HandValue>) -> PotEntry {
+        PotEntry {
+            seat,
+            contributed,
+            hand,
+        }
+    }
+
+    #[test]
+    fn rank_suit_reads_real_engine_cards() {
+        assert_eq!(rank_suit(&card("As")), Some((14, 0)));
+        assert_eq!(rank_suit(&card("Th")), Some((10, 1)));
+        assert_eq!(rank_suit(&card("2d")), Some((2, 2)));
+        assert_eq!(rank_suit(&card("Qc")), Some((12, 3)));
+
+        let mut all: Vec<RankSuit> = full_deck()
+            .iter()
+            .map(|c| rank_suit(c).expect("every card parses"))
+            .collect();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), 52);
+    }
+
+    #[test]
+    fn omaha_uses_exactly_two_hole_cards() {
+        // Четыре червы на руках и одна на борде — в холдеме флеш, в омахе нет.
+        let holdem = best(GameVariant::Holdem, "Ah Kh Qh Jh", "2h 3c 7d 9s 4s");
+        assert_eq!(holdem.category, HandCategory::Flush);
+        let omaha = best(GameVariant::Omaha, "Ah Kh Qh Jh", "2h 3c 7d 9s 4s");
+        assert_eq!(omaha.category, HandCategory::HighCard);
+
+        // Четыре пики на борде и одна на руках — в омахе тоже не флеш.
+        let omaha = best(GameVariant::Omaha, "Ts 3c 4c 8d", "As Ks Qs Js 2d");
+        assert!(omaha.category < HandCategory::Flush);
+    }
+
+    #[test]
+    fn short_deck_flush_beats_full_house() {
+        let flush = "As Js 9s 8s 6s";
+        let full_house = "Kd Kc Kh 7d 7c";
+
+        assert!(five(GameVariant::ShortDeck, flush) > five(GameVariant::ShortDeck, full_house));
+        assert!(five(GameVariant::Holdem, flush) < five(GameVariant::Holdem, full_house));
+    }
+
+    #[test]
+    fn short_deck_ace_six_straight() {
+        let low = five(GameVariant::ShortDeck, "Ac 6d 7h 8s 9c");
+        assert_eq!(low.category, HandCategory::Straight);
+        assert_eq!(low.tiebreak[0], 9);
+        assert!(low < five(GameVariant::ShortDeck, "6d 7h 8s 9c Td"));
+        assert!(low > five(GameVariant::ShortDeck, "Ac Ad Kh Ks Qc"));
+
+        // В обычной колоде это просто туз-хай.
+        assert_eq!(
+            five(GameVariant::Holdem, "Ac 6d 7h 8s 9c").category,
+            HandCategory::HighCard
+        );
+    }
+
+    #[test]
+    fn wheel_is_five_high() {
+        let wheel = five(GameVariant::Holdem, "Ac 2d 3h 4s 5c");
+        assert_eq!(wheel.category, HandCategory::Straight);
+        assert!(wheel < five(GameVariant::Holdem, "2d 3h 4s 5c 6c"));
+    }
+
+    #[test]
+    fn odd_chip_goes_first_from_the_button() {
+        let tie = five(GameVariant::Holdem, "As Kd Qh Js 9c");
+        let entries = [
+            entry(0, 50, Some(tie)),
+            entry(1, 50, Some(tie)),
+            entry(2, 1, None),
+        ];
+
+        // Кнопка на месте 0: первым от кнопки идёт место 1.
+        let awards = split_pots(&entries, &[1, 2, 0]);
+        assert_eq!(awards[&1], 51);
+        assert_eq!(awards[&0], 50);
+
+        // Кнопка на месте 1: первым идёт место 2 (сбросил), затем 0.
+        let awards = split_pots(&entries, &[2, 0, 1]);
+        assert_eq!(awards[&0], 51);
+        assert_eq!(awards[&1], 50);
+    }
+
+    #[test]
+    fn short_all_in_wins_only_the_main_pot() {
+        let nuts = five(GameVariant::Holdem, "As Ad Ac Ks Kd");
+        let pair = five(GameVariant::Holdem, "Qs Qd 9c 7s 2d");
+        let worse = five(GameVariant::Holdem, "Js Jd 9c 7s 2d");
+        let entries = [
+            entry(0, 100, Some(nuts)),
+            entry(1, 300, Some(pair)),
+            entry(2, 300, Some(worse)),
+        ];
+
+        let awards = split_pots(&entries, &[0, 1, 2]);
+        assert_eq!(awards[&0], 300);
+        assert_eq!(awards[&1], 400);
+        assert!(!awards.contains_key(&2));
+    }
+
+    #[test]
+    fn orphaned_chips_go_to_the_best_live_hand() {
+        let better = five(GameVariant::Holdem, "Qs Qd 9c 7s 2d");
+        let worse = five(GameVariant::Holdem, "Js Jd 9c 7s 2d");
+        let entries = [
+            entry(0, 200, None),
+            entry(1, 100, Some(better)),
+            entry(2, 100, Some(worse)),
+        ];
+
+        let awards = split_pots(&entries, &[0, 1, 2]);
+        assert_eq!(awards[&1], 400);
+        assert_eq!(awards.values().sum::<u64>(), 400);
+    }
+}
@@ -0,0 +1,235 @@
+//! Сит-энд-гоу и спины по шаблонам.
+//!
+//! Шаблон (`PokerState::sng_templates`) описывает однотабличный турнир:
+//! конфиг, размер (2, 6 или 9 игроков) и бай-ин. У шаблона всегда есть
+//! один открытый турнир; как только регистраций становится `players`,
+//! турнир стартует сам, а шаблон открывает следующий.
+//!
+//! Спин — тот же SNG, но при старте тянется множитель призового фонда
+//! (см. `draw_multiplier`). Случайность — закоммиченный `base_seed`,
+//! смешанный с данными старта (`StartEntropy`): высота блока, время и
+//! состав участников неизвестны, пока идёт регистрация.
+
+use linera_sdk::linera_base_types::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::tournament::TournamentConfig;
+use poker_engine::domain::{PlayerId, TournamentId};
+
+pub type SngTemplateId = u64;
+
+/// Допустимые размеры SNG.
+pub const SNG_SIZES: [u32; 3] = [2, 6, 9];
+
+/// Строка таблицы множителей спина: множитель и его вес.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SpinMultiplier {
+    pub multiplier: u32,
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SngTemplate {
+    pub template_id: SngTemplateId,
+    pub config: TournamentConfig,
+    /// Сколько регистраций запускает турнир.
+    pub players: u32,
+    pub buy_in: Chips,
+    /// Таблица множителей; пустая — обычный SNG без спина.
+    pub spin: Vec<SpinMultiplier>,
+    /// Открытый сейчас турнир шаблона.
+    pub current: Option<TournamentId>,
+    /// Сколько турниров шаблон уже запустил.
+    pub launched: u32,
+}
+
+impl SngTemplate {
+    pub fn is_spin(&self) -> bool {
+        !self.spin.is_empty()
+    }
+
+    /// Доли призового фонда по местам, в процентах.
+    /// Спин — победитель забирает всё.
+    pub fn payout_percents(&self) -> &'static [u64] {
+        if self.is_spin() {
+            return &[100];
+        }
+        match self.players {
+            0..=2 => &[100],
+            3..=6 => &[65, 35],
+            _ => &[50, 30, 20],
+        }
+    }
+
+    /// Призовые по местам для фонда `pool`; остаток от деления — первому.
+    pub fn payouts(&self, pool: Chips) -> Vec<Chips> {
+        let percents = self.payout_percents();
+        let mut payouts: Vec<Chips> = percents
+            .iter()
+            // pool * p / 100 без переполнения на больших фондах.
+            .map(|p| Chips(pool.0 / 100 * p + pool.0 % 100 * p / 100))
+            .collect();
+        let paid: u64 = payouts.iter().map(|c| c.0).sum();
+        if let Some(first) = payouts.first_mut() {
+            first.0 += pool.0 - paid;
+        }
+        payouts
+    }
+}
+
+/// Проверить шаблон. Err — человекочитаемая причина.
+pub fn validate_template(
+    players: u32,
+    table_size: u8,
+    spin: &[SpinMultiplier],
+) -> Result<(), String> {
+    if !SNG_SIZES.contains(&players) {
+        return Err(format!("players must be one of {SNG_SIZES:?}"));
+    }
+    if u32::from(table_size) < players {
+        return Err(format!("table_size {table_size} is less than {players} players"));
+    }
+    if spin.iter().any(|m| m.multiplier == 0) {
+        return Err("spin multipliers must be positive".to_string());
+    }
+    if !spin.is_empty() && spin.iter().all(|m| m.weight == 0) {
+        return Err("spin table needs at least one non-zero weight".to_string());
+    }
+    Ok(())
+}
+
+/// SplitMix64: детерминированное перемешивание seed'а.
+pub fn mix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Соль розыгрыша множителя спина.
+pub const SPIN_SALT: u64 = 0x5B1A_0C7E;
+
+/// Входные данные розыгрышей при старте турнира. Пишутся в аудит
+/// (`SeatDraw::inputs`), чтобы розыгрыш можно было повторить.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartEntropy {
+    pub base_seed: u64,
+    /// Высота блока, в котором турнир стартовал.
+    pub block_height: u64,
+    pub started_at: Timestamp,
+    /// Участники по возрастанию player_id.
+    pub entrants: Vec<PlayerId>,
+}
+
+impl StartEntropy {
+    pub fn new(
+        base_seed: u64,
+        block_height: u64,
+        started_at: Timestamp,
+        mut entrants: Vec<PlayerId>,
+    ) -> Self {
+        entrants.sort_unstable();
+        Self {
+            base_seed,
+            block_height,
+            started_at,
+            entrants,
+        }
+    }
+
+    /// Seed розыгрыша с солью `salt` для турнира `tournament_id`.
+    pub fn seed(&self, tournament_id: TournamentId, salt: u64) -> u64 {
+        let mut h = mix64(self.base_seed ^ tournament_id ^ salt);
+        h = mix64(h ^ self.block_height);
+        h = mix64(h ^ self.started_at.micros());
+        for player_id in &self.entrants {
+            h = mix64(h ^ player_id);
+        }
+        h
+    }
+}
+
+/// Множитель спина по весам. `roll` — случайное число из
+/// `StartEntropy::seed(tournament_id, SPIN_SALT)`, так что результат
+/// можно перепроверить по записанным входным данным.
+pub fn draw_multiplier(spin: &[SpinMultiplier], roll: u64) -> u32 {
+    let total: u64 = spin.iter().map(|m| u64::from(m.weight)).sum();
+    if total == 0 {
+        return 1;
+    }
+    let mut ticket = roll % total;
+    for m in spin {
+        let weight = u64::from(m.weight);
+        if ticket < weight {
+            return m.multiplier;
+        }
+        ticket -= weight;
+    }
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Vec<SpinMultiplier> {
+        [(2, 75), (5, 20), (10, 5), (1000, 0)]
+            .into_iter()
+            .map(|(multiplier, weight)| SpinMultiplier { multiplier, weight })
+            .collect()
+    }
+
+    #[test]
+    fn multiplier_follows_ticket_ranges() {
+        let spin = table();
+        assert_eq!(draw_multiplier(&spin, 0), 2);
+        assert_eq!(draw_multiplier(&spin, 74), 2);
+        assert_eq!(draw_multiplier(&spin, 75), 5);
+        assert_eq!(draw_multiplier(&spin, 94), 5);
+        assert_eq!(draw_multiplier(&spin, 95), 10);
+        assert_eq!(draw_multiplier(&spin, 99), 10);
+        assert_eq!(draw_multiplier(&spin, 100), 2);
+        assert_eq!(draw_multiplier(&[], 42), 1);
+    }
+
+    #[test]
+    fn multiplier_distribution_matches_weights() {
+        let spin = table();
+        let draws = 100_000u64;
+        let mut counts = [0u64; 3];
+        for i in 0..draws {
+            let entropy = StartEntropy::new(7, i, Timestamp::from(i * 1_000), vec![1, 2, 3]);
+            match draw_multiplier(&spin, entropy.seed(i, SPIN_SALT)) {
+                2 => counts[0] += 1,
+                5 => counts[1] += 1,
+                10 => counts[2] += 1,
+                other => panic!("unexpected multiplier {other}"),
+            }
+        }
+
+        // Ожидаемые доли 75/20/5 %; допуск — один процентный пункт.
+        for (count, expected) in counts.iter().zip([75u64, 20, 5]) {
+            let percent = count * 100 / draws;
+            assert!(
+                percent.abs_diff(expected) <= 1,
+                "got {percent}% instead of {expected}%"
+            );
+        }
+    }
+
+    #[test]
+    fn start_seed_depends_on_start_data() {
+        let base = StartEntropy::new(7, 100, Timestamp::from(5_000), vec![3, 1, 2]);
+        let same = StartEntropy::new(7, 100, Timestamp::from(5_000), vec![1, 2, 3]);
+        assert_eq!(base.seed(9, SPIN_SALT), same.seed(9, SPIN_SALT));
+
+        let later_block = StartEntropy::new(7, 101, Timestamp::from(5_000), vec![1, 2, 3]);
+        let later_time = StartEntropy::new(7, 100, Timestamp::from(5_001), vec![1, 2, 3]);
+        let other_field = StartEntropy::new(7, 100, Timestamp::from(5_000), vec![1, 2, 4]);
+        for other in [later_block, later_time, other_field] {
+            assert_ne!(base.seed(9, SPIN_SALT), other.seed(9, SPIN_SALT));
+        }
+        assert_ne!(base.seed(9, SPIN_SALT), base.seed(10, SPIN_SALT));
+    }
+}
@@ -0,0 +1,70 @@
+//! Таблица турнира: живые игроки по стекам и тайминг уровней.
+//!
+//! Время начала уровня хранит `PokerState::tournament_level_started`
+//! (старт турнира и `AdvanceLevel`), длительность —
+//! `PokerState::tournament_level_durations` (`SetLevelDuration`).
+
+use linera_sdk::linera_base_types::Timestamp;
+
+use poker_engine::domain::chips::Chips;
+use poker_engine::domain::tournament::Tournament;
+use poker_engine::domain::{PlayerId, SeatIndex, TableId};
+
+/// Строка таблицы живых игроков.
+#[derive(Clone, Debug)]
+pub struct Standing {
+    /// 1 — лидер по фишкам; равные стеки делят место.
+    pub rank: u32,
+    pub player_id: PlayerId,
+    pub chips: Chips,
+    pub table_id: Option<TableId>,
+    pub seat_index: Option<SeatIndex>,
+}
+
+/// Живые игроки по убыванию стека (при равенстве — по player_id).
+pub fn live_standings(tournament: &Tournament) -> Vec<Standing> {
+    let mut rows: Vec<Standing> = tournament
+        .registrations
+        .iter()
+        .filter(|(_, r)| !r.is_busted)
+        .map(|(pid, r)| Standing {
+            rank: 0,
+            player_id: *pid,
+            chips: r.total_chips,
+            table_id: r.table_id,
+            seat_index: r.seat_index,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.chips
+            .0
+            .cmp(&a.chips.0)
+            .then(a.player_id.cmp(&b.player_id))
+    });
+
+    let mut prev: Option<(u64, u32)> = None;
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.rank = match prev {
+            Some((chips, rank)) if chips == row.chips.0 => rank,
+            _ => i as u32 + 1,
+        };
+        prev = Some((row.chips.0, row.rank));
+    }
+    rows
+}
+
+/// Средний стек живых игроков (0 — живых нет).
+pub fn average_stack(standings: &[Standing]) -> Chips {
+    if standings.is_empty() {
+        return Chips::ZERO;
+    }
+    let total: u64 = standings.iter().map(|s| s.chips.0).sum();
+    Chips(total / standings.len() as u64)
+}
+
+/// Время смены уровня (микросекунды): начало уровня плюс его длительность.
+pub fn next_level_at(level_started: Timestamp, duration_secs: u64) -> u64 {
+    level_started
+        .micros()
+        .saturating_add(duration_secs.saturating_mul(1_000_000))
+}
@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 
-use linera_sdk::linera_base_types::AccountOwner;
-use linera_sdk::views::{MapView, RegisterView, RootView, ViewStorageContext};
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+use linera_sdk::views::{LogView, MapView, RegisterView, RootView, ViewStorageContext};
 use serde::{Deserialize, Serialize};
 
 // Всё берём из off-chain движка: домен + engine.
 // On-chain ничего из этого не реализует, только хранит снимки.
 use poker_engine::domain::chips::Chips;
 use poker_engine::domain::deck::Deck;
+use poker_engine::domain::player::PlayerAtTable;
 use poker_engine::domain::table::Table;
 use poker_engine::domain::tournament::Tournament;
 use poker_engine::domain::{HandId, PlayerId, SeatIndex, TableId, TournamentId};
@@ -18,6 +19,19 @@ use poker_engine::engine::pot::Pot;
 use poker_engine::engine::side_pots::SidePot;
 use poker_engine::time_ctrl::TimeController;
 
+use crate::deals::{DealProposal, LockedDeal};
+use crate::events::EventRecord;
+use crate::hand_for_hand::HandForHand;
+use crate::hand_archive::ArchivedHand;
+use crate::players::{PlayerProfile, PlayerStats};
+use crate::roles::{RoleAssignment, RoleChange, TableRoles};
+use crate::rules::TableRules;
+use crate::satellites::{SatelliteConfig, Ticket};
+use crate::schedule::TournamentSchedule;
+use crate::seasons::{ArchivedSeason, Season, SeasonEntry};
+use crate::seat_draw::SeatDraw;
+use crate::sng::{SngTemplate, SngTemplateId};
+
 /// Полный снапшот HandEngine для хранения в Chain View.
 ///
 /// Важное правило:
@@ -68,6 +82,68 @@ impl HandEngineSnapshot {
     }
 }
 
+/// Настройки авто-раздачи для стола.
+///
+/// Если `enabled` — после завершения раздачи следующая стартует
+/// не раньше `next_hand_at` (время цепи). Пока за столом меньше двух
+/// игроков с фишками (не считая sit-out), авто-раздача стоит на паузе
+/// (`next_hand_at = None`).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AutoDealConfig {
+    pub enabled: bool,
+    /// Пауза между раздачами, в секундах.
+    pub delay_secs: u64,
+    /// Когда можно стартовать следующую раздачу.
+    pub next_hand_at: Option<Timestamp>,
+}
+
+/// Накопительная статистика стола для лобби.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TableStats {
+    pub hands_played: u64,
+    /// Сумма банков всех сыгранных раздач.
+    pub total_pot: Chips,
+}
+
+impl TableStats {
+    pub fn record_hand(&mut self, pot: Chips) {
+        self.hands_played += 1;
+        self.total_pot += pot;
+    }
+
+    /// Средний банк (0, если раздач ещё не было).
+    pub fn average_pot(&self) -> Chips {
+        if self.hands_played == 0 {
+            return Chips::ZERO;
+        }
+        Chips(self.total_pot.0 / self.hands_played)
+    }
+}
+
+/// Финиш игрока в турнире.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TournamentFinish {
+    pub player_id: PlayerId,
+    /// 1 — победитель.
+    pub place: u32,
+    pub prize: Chips,
+    pub at: Timestamp,
+    /// Билет, выигранный в сателлите (вместо приза фишками).
+    #[serde(default)]
+    pub ticket: Option<TournamentId>,
+}
+
+/// Цепь игрока: место за столом на другой цепи.
+///
+/// Заводится при отправке SeatRequest и закрывается SeatRejected или
+/// CashOut — только с цепи `table_chain` и не больше `stack`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SeatClaim {
+    pub table_chain: ChainId,
+    /// Бай-ин, затем последний стек из SeatStack.
+    pub stack: Chips,
+}
+
 /// Глобальное состояние on-chain приложения Poker.
 /// Это единственное, что реально сохраняется в состоянии цепи Linera.
 #[derive(RootView)]
@@ -122,10 +198,197 @@ pub struct PokerState {
     #[view(map)]
     pub account_players: MapView<AccountOwner, PlayerId>,
 
+    /// Аккаунты, с которых игрок ушёл через RotateAccount: повторно
+    /// не привязываются (значение — чей это был аккаунт).
+    #[view(map)]
+    pub retired_accounts: MapView<AccountOwner, PlayerId>,
+
     /// Тайм-контроллеры (shot-clock + таймбанк) по столам.
     ///
     /// Тут хранится `TimeController` из off-chain модуля `time_ctrl` —
     /// полностью сериализуемая структура (Serialize/Deserialize).
     #[view(map)]
     pub time_controllers: MapView<TableId, TimeController>,
+
+    /// Авто-раздача по столам (дилер-бот).
+    #[view(map)]
+    pub auto_deal: MapView<TableId, AutoDealConfig>,
+
+    /// Запись текущей раздачи по столу (стартовые стеки + действия).
+    /// Переезжает в `hand_archive`, когда раздача завершается.
+    #[view(map)]
+    pub hand_logs: MapView<TableId, ArchivedHand>,
+
+    /// Архив завершённых раздач: hand_id → полная запись.
+    #[view(map)]
+    pub hand_archive: MapView<HandId, ArchivedHand>,
+
+    /// Индекс: (стол, порядковый номер) → hand_id. Номера идут с нуля
+    /// в порядке завершения раздач, так что запись — одна вставка,
+    /// а страница — чтение только своих ключей.
+    #[view(map)]
+    pub table_hands: MapView<(TableId, u64), HandId>,
+
+    /// Сколько раздач в `table_hands` у стола.
+    #[view(map)]
+    pub table_hand_counts: MapView<TableId, u64>,
+
+    /// Индекс: (игрок, порядковый номер) → раздача, в которой он участвовал.
+    #[view(map)]
+    pub player_hands: MapView<(PlayerId, u64), HandId>,
+
+    /// Сколько раздач в `player_hands` у игрока.
+    #[view(map)]
+    pub player_hand_counts: MapView<PlayerId, u64>,
+
+    /// Лента событий (то же, что уходит в Linera-стрим) для чтения по курсору.
+    #[view(log)]
+    pub event_log: LogView<EventRecord>,
+
+    /// Координатор: на какой цепи живёт стол (если не на этой).
+    /// Сам стол в `tables` — зеркало, играть за ним нельзя.
+    #[view(map)]
+    pub table_chains: MapView<TableId, ChainId>,
+
+    /// Цепь стола: куда отчитываться о раздачах (цепь-координатор).
+    #[view(map)]
+    pub table_coordinators: MapView<TableId, ChainId>,
+
+    /// Цепь стола: роли на столе, присланные координатором.
+    #[view(map)]
+    pub table_roles: MapView<TableId, TableRoles>,
+
+    /// Координатор: цепи для размещения турнирных столов (round-robin).
+    #[view(register)]
+    pub table_chain_pool: RegisterView<Vec<ChainId>>,
+
+    /// Цепь стола: рассадка от координатора, ждущая конца текущей раздачи.
+    #[view(map)]
+    pub pending_seats: MapView<TableId, Vec<Option<PlayerAtTable>>>,
+
+    /// Цепь игрока: банкролл (фишки вне столов).
+    #[view(map)]
+    pub bankrolls: MapView<PlayerId, Chips>,
+
+    /// Цепь игрока: места за столами на других цепях.
+    #[view(map)]
+    pub seat_claims: MapView<(PlayerId, TableId), SeatClaim>,
+
+    /// Цепь стола: запросы на cash-out, пришедшие во время раздачи.
+    #[view(map)]
+    pub pending_cash_outs: MapView<TableId, Vec<PlayerId>>,
+
+    /// Цепь стола: с какой цепи игрок пришёл (его идентичность для cross-chain).
+    #[view(map)]
+    pub player_chains: MapView<PlayerId, ChainId>,
+
+    /// Статистика столов для лобби (на координаторе — и по удалённым столам).
+    #[view(map)]
+    pub table_stats: MapView<TableId, TableStats>,
+
+    /// Профили игроков (аватар, страна, настройки).
+    #[view(map)]
+    pub player_profiles: MapView<PlayerId, PlayerProfile>,
+
+    /// Статистика игроков за всё время.
+    #[view(map)]
+    pub player_stats: MapView<PlayerId, PlayerStats>,
+
+    /// Призовые по местам: payouts[0] — за первое место.
+    #[view(map)]
+    pub tournament_payouts: MapView<TournamentId, Vec<Chips>>,
+
+    /// Финиши игроков в порядке вылета (победитель — последним).
+    #[view(map)]
+    pub tournament_results: MapView<TournamentId, Vec<TournamentFinish>>,
+
+    /// Текущий сезон рейтинга (None — сезоны не запущены).
+    #[view(register)]
+    pub current_season: RegisterView<Option<Season>>,
+
+    /// Результаты игроков в текущем сезоне.
+    #[view(map)]
+    pub season_standings: MapView<PlayerId, SeasonEntry>,
+
+    /// Итоговые таблицы завершённых сезонов: season_id → standings.
+    #[view(map)]
+    pub season_archive: MapView<u32, ArchivedSeason>,
+
+    /// Выданные роли по аккаунтам (владелец сюда не пишется).
+    #[view(map)]
+    pub roles: MapView<AccountOwner, Vec<RoleAssignment>>,
+
+    /// Предложенный новый владелец, ждущий `AcceptOwnership`.
+    #[view(register)]
+    pub pending_owner: RegisterView<Option<AccountOwner>>,
+
+    /// Журнал всех изменений ролей и владения.
+    #[view(log)]
+    pub role_log: LogView<RoleChange>,
+
+    /// Индекс уникальности имён: normalize_name(name) → player_id.
+    #[view(map)]
+    pub name_index: MapView<String, PlayerId>,
+
+    /// Имена из `player_names`, занятые до появления индекса, уже внесены
+    /// в `name_index`.
+    #[view(register)]
+    pub name_index_backfilled: RegisterView<bool>,
+
+    /// Правила столов, отличные от NL Hold'em (нет записи — правила по умолчанию).
+    #[view(map)]
+    pub table_rules: MapView<TableId, TableRules>,
+
+    /// Шаблоны SNG / спинов (см. `crate::sng`).
+    #[view(map)]
+    pub sng_templates: MapView<SngTemplateId, SngTemplate>,
+
+    /// Турнир → шаблон, из которого он открыт.
+    #[view(map)]
+    pub tournament_templates: MapView<TournamentId, SngTemplateId>,
+
+    /// Бай-ины платных турниров: списываются из банкролла при регистрации.
+    #[view(map)]
+    pub tournament_buy_ins: MapView<TournamentId, Chips>,
+
+    /// Выпавший множитель спина (для аудита).
+    #[view(map)]
+    pub spin_multipliers: MapView<TournamentId, u32>,
+
+    /// Расписание турниров (см. `crate::schedule`).
+    #[view(map)]
+    pub tournament_schedules: MapView<TournamentId, TournamentSchedule>,
+
+    /// Сателлиты: турнир → целевой турнир и число билетов.
+    #[view(map)]
+    pub satellites: MapView<TournamentId, SatelliteConfig>,
+
+    /// Билеты игроков (см. `crate::satellites`).
+    #[view(map)]
+    pub tickets: MapView<PlayerId, Vec<Ticket>>,
+
+    /// Открытые предложения сделок (см. `crate::deals`).
+    #[view(map)]
+    pub deal_proposals: MapView<TournamentId, DealProposal>,
+
+    /// Принятые сделки: призы оставшихся игроков зафиксированы.
+    #[view(map)]
+    pub locked_deals: MapView<TournamentId, LockedDeal>,
+
+    /// Турниры в режиме рука-в-руку (см. `crate::hand_for_hand`).
+    #[view(map)]
+    pub hand_for_hand: MapView<TournamentId, HandForHand>,
+
+    /// Жеребьёвка мест при старте турнира (аудит, см. `crate::seat_draw`).
+    #[view(map)]
+    pub seat_draws: MapView<TournamentId, SeatDraw>,
+
+    /// Когда начался текущий уровень блайндов турнира.
+    #[view(map)]
+    pub tournament_level_started: MapView<TournamentId, Timestamp>,
+
+    /// Длительность уровня блайндов турнира, секунды (см. `SetLevelDuration`).
+    /// Нет записи — длительность не задана, уровни меняют вручную.
+    #[view(map)]
+    pub tournament_level_durations: MapView<TournamentId, u64>,
 }
@@ -0,0 +1,76 @@
+//! Общее для unit-тестов: карты движка из записи PokerStars.
+
+use serde_json::json;
+
+use poker_engine::domain::card::Card;
+
+const RANKS: [(char, &str, u8); 13] = [
+    ('2', "Two", 2),
+    ('3', "Three", 3),
+    ('4', "Four", 4),
+    ('5', "Five", 5),
+    ('6', "Six", 6),
+    ('7', "Seven", 7),
+    ('8', "Eight", 8),
+    ('9', "Nine", 9),
+    ('T', "Ten", 10),
+    ('J', "Jack", 11),
+    ('Q', "Queen", 12),
+    ('K', "King", 13),
+    ('A', "Ace", 14),
+];
+
+const SUITS: [(char, &str, &str); 4] = [
+    ('s', "Spades", "Spade"),
+    ('h', "Hearts", "Heart"),
+    ('d', "Diamonds", "Diamond"),
+    ('c', "Clubs", "Club"),
+];
+
+/// Карта из записи вида "As", "Td". Представление `Card` у движка закрыто,
+/// поэтому перебираем написания, которые понимает `showdown::rank_suit`,
+/// пока движок не десериализует одно из них. С настоящими картами
+/// движка это сверяет `orchestrator::tests::engine_serde_layout_round_trips`.
+pub fn card(text: &str) -> Card {
+    let mut chars = text.chars();
+    let (Some(r), Some(s)) = (chars.next(), chars.next()) else {
+        panic!("bad card {text:?}");
+    };
+    let (_, rank_word, rank_num) = RANKS
+        .iter()
+        .find(|(c, ..)| *c == r)
+        .unwrap_or_else(|| panic!("bad rank in {text:?}"));
+    let (_, suit_word, suit_single) = SUITS
+        .iter()
+        .find(|(c, ..)| *c == s)
+        .unwrap_or_else(|| panic!("bad suit in {text:?}"));
+
+    let candidates = [
+        json!({ "rank": rank_word, "suit": suit_word }),
+        json!({ "rank": rank_word, "suit": suit_single }),
+        json!({ "rank": rank_num, "suit": suit_word }),
+        json!({ "rank": r.to_string(), "suit": s.to_string() }),
+        json!({ "rank": rank_num, "suit": s.to_string() }),
+    ];
+    candidates
+        .into_iter()
+        .find_map(|v| serde_json::from_value(v).ok())
+        .unwrap_or_else(|| panic!("engine Card does not accept {text:?}"))
+}
+
+/// Несколько карт через пробел: "Ah Kd 2c".
+pub fn cards(text: &str) -> Vec<Card> {
+    text.split_whitespace().map(card).collect()
+}
+
+/// Все 52 карты колоды.
+pub fn full_deck() -> Vec<Card> {
+    RANKS
+        .iter()
+        .flat_map(|(r, ..)| {
+            SUITS
+                .iter()
+                .map(move |(s, ..)| card(&format!("{r}{s}")))
+        })
+        .collect()
+}
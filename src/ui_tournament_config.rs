@@ -26,6 +26,9 @@ pub struct UiTournamentConfig {
     pub name: String,
     pub description: String,
     pub prize_description: String,
+    /// Время старта и закрытия регистрации (RFC 3339 или unix-секунды):
+    /// при createTournament турнир сразу ставится в расписание
+    /// (`schedule::config_schedule`).
     pub start_time: Option<String>,
     pub reg_close_time: Option<String>,
 